@@ -0,0 +1,76 @@
+#ifndef LIBRYPT_ENTROPY_H
+#define LIBRYPT_ENTROPY_H
+
+/* Generated by cbindgen from src/capi.rs; do not edit. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+// The result of a C API call.
+typedef enum LibryptStatus {
+  // The call succeeded.
+  LIBRYPT_STATUS_OK = 0,
+  // A required pointer argument was null.
+  LIBRYPT_STATUS_NULL_POINTER = 1,
+  // The OS entropy source is not supported on this platform (or is blocked, e.g. by a seccomp filter).
+  LIBRYPT_STATUS_UNSUPPORTED = 2,
+  // The entropy source failed.
+  LIBRYPT_STATUS_SOURCE_FAILED = 3,
+  // A Rust panic was caught at the API boundary.
+  LIBRYPT_STATUS_PANIC = 4,
+} LibryptStatus;
+
+// An HMAC_DRBG (SHA-256) seeded from the OS entropy source.
+//
+// NOTE: Safe to use from multiple threads at once; calls are serialized internally.
+typedef struct LibryptDrbg LibryptDrbg;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Fill `len` bytes at `buf` with entropy from the OS.
+//
+// # Safety
+//
+// `buf` must be valid for writes of `len` bytes.
+enum LibryptStatus librypt_entropy_os_read(uint8_t *buf, size_t len);
+
+// Create a DRBG seeded from the OS, storing it in `*out` (to be released with `librypt_drbg_free`).
+//
+// # Safety
+//
+// `out` must be valid for writes of a pointer.
+enum LibryptStatus librypt_drbg_new(struct LibryptDrbg **out);
+
+// Fill `len` bytes at `buf` with output from `drbg`, reseeding from the OS when required.
+//
+// # Safety
+//
+// `drbg` must come from `librypt_drbg_new` and not have been freed, and `buf` must be valid for writes of `len` bytes.
+enum LibryptStatus librypt_drbg_generate(const struct LibryptDrbg *drbg,
+                                         uint8_t *buf,
+                                         size_t len);
+
+// Reseed `drbg` from the OS immediately (e.g. after restoring a VM snapshot).
+//
+// # Safety
+//
+// `drbg` must come from `librypt_drbg_new` and not have been freed.
+enum LibryptStatus librypt_drbg_reseed(const struct LibryptDrbg *drbg);
+
+// Release (and wipe) a DRBG. Passing null does nothing.
+//
+// # Safety
+//
+// `drbg` must be null or come from `librypt_drbg_new`, and must not be used again.
+void librypt_drbg_free(struct LibryptDrbg *drbg);
+
+// Get a static, NUL-terminated description of a status (values outside `LibryptStatus` are described as unknown).
+const char *librypt_status_str(int status);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* LIBRYPT_ENTROPY_H */
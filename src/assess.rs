@@ -0,0 +1,143 @@
+//! Min-entropy accounting: the rate each source is credited with, and what that credit is based on.
+
+use crate::EntropySource;
+
+/// Margin of input entropy over output size required for a conditioned block to be credited with full entropy.
+#[cfg(any(
+    all(feature = "alloc", feature = "combine"),
+    feature = "condition",
+    feature = "extract",
+    feature = "havege",
+    feature = "jitter"
+))]
+const FULL_ENTROPY_MARGIN: f64 = 64.0;
+
+/// Security strength a generator's seed must carry for its output to be credited with full entropy, in bits.
+#[cfg(any(feature = "csprng", feature = "drbg"))]
+const GENERATOR_STRENGTH: f64 = 256.0;
+
+/// What kind of source produces the output, for quorum rules and reporting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SourceClass {
+    /// The operating system (e.g. `OsEntropy`).
+    Os,
+    /// A hardware generator (e.g. `RdSeed`, `HwRng`, a TPM).
+    Hardware,
+    /// A raw noise source (e.g. `JitterSource`, audio or camera noise).
+    Noise,
+    /// Anything else, including combinations of different classes.
+    Other,
+}
+
+/// What the credited rate of a source is based on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Basis {
+    /// Output of a cryptographic generator kept seeded by the platform (e.g. the OS or `RDRAND`), conventionally
+    /// credited with full entropy.
+    Generator,
+    /// The rate the hardware's specification guarantees (e.g. full entropy for `RDSEED`).
+    Specified,
+    /// A claim configured by the caller (e.g. the min-entropy passed to `AdcNoise`).
+    Claimed,
+    /// A conservative estimate built into this crate for its own noise sources.
+    Estimated,
+    /// Derived from the assessments of the wrapped or combined sources.
+    Derived,
+}
+
+/// Where the output of a source comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Provenance {
+    pub class: SourceClass,
+    pub basis: Basis,
+}
+
+/// A source that can be credited with a min-entropy rate, for principled entropy accounting (e.g. by `PolicySource`).
+///
+/// NOTE: Wrappers and combiners derive their rate from the sources they read, so a rate is only as sound as the
+/// claims at the bottom of the chain.
+pub trait EntropyAssessment: EntropySource {
+    /// Get the min-entropy credited to every output byte, in bits (`0.0..=8.0`).
+    fn min_entropy_per_byte(&self) -> f64;
+
+    /// Get the class of the source and what its rate is based on.
+    fn provenance(&self) -> Provenance;
+}
+
+/// An async source that can be credited with a min-entropy rate, as in `EntropyAssessment`.
+#[cfg(feature = "async")]
+pub trait AsyncEntropyAssessment: crate::asynchronous::AsyncEntropySource {
+    /// Get the min-entropy credited to every output byte, in bits (`0.0..=8.0`).
+    fn min_entropy_per_byte(&self) -> f64;
+
+    /// Get the class of the source and what its rate is based on.
+    fn provenance(&self) -> Provenance;
+}
+
+/// Forward `EntropyAssessment` through a reference or smart pointer.
+macro_rules! forward_entropy_assessment {
+    ($($(#[$meta:meta])* $pointer:ty),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            impl<S: EntropyAssessment + ?Sized> EntropyAssessment for $pointer {
+                fn min_entropy_per_byte(&self) -> f64 {
+                    (**self).min_entropy_per_byte()
+                }
+
+                fn provenance(&self) -> Provenance {
+                    (**self).provenance()
+                }
+            }
+        )*
+    };
+}
+
+forward_entropy_assessment!(
+    &S,
+    &mut S,
+    #[cfg(feature = "alloc")]
+    alloc::boxed::Box<S>,
+    #[cfg(feature = "alloc")]
+    alloc::rc::Rc<S>,
+    #[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+    alloc::sync::Arc<S>,
+);
+
+/// Get the rate credited to the output of a vetted conditioning function, given the input entropy per output block.
+///
+/// NOTE: Follows SP 800-90B section 3.1.5.1.2 for `n_in >= n_out`: full entropy with a 64-bit margin, otherwise the
+/// input entropy capped at `0.999 * n_out`.
+#[cfg(any(
+    all(feature = "alloc", feature = "combine"),
+    feature = "condition",
+    feature = "extract",
+    feature = "havege",
+    feature = "jitter"
+))]
+pub(crate) fn conditioned(input_bits: f64, output_bytes: usize) -> f64 {
+    let output_bits = 8.0 * output_bytes as f64;
+
+    if output_bytes == 0 || input_bits.is_nan() {
+        return 0.0;
+    }
+
+    let bits = match input_bits >= output_bits + FULL_ENTROPY_MARGIN {
+        true => output_bits,
+        false => input_bits.clamp(0.0, 0.999 * output_bits),
+    };
+
+    bits / output_bytes as f64
+}
+
+/// Get the rate credited to the output of a generator which (re)seeds itself with `seed_bytes` from a source credited
+/// with `source_rate`.
+///
+/// NOTE: A generator is only as strong as its seed, so it is credited with full entropy if every seed carries 256 bits,
+/// and with nothing otherwise.
+#[cfg(any(feature = "csprng", feature = "drbg"))]
+pub(crate) fn seeded(source_rate: f64, seed_bytes: usize) -> f64 {
+    match source_rate * seed_bytes as f64 >= GENERATOR_STRENGTH {
+        true => 8.0,
+        false => 0.0,
+    }
+}
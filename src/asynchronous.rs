@@ -0,0 +1,125 @@
+//! Async entropy sources and a bridge for blocking sources.
+
+use core::future::Future;
+use std::error::Error;
+use std::sync::Arc;
+use std::vec;
+
+use zeroize::Zeroizing;
+
+use crate::assess::{AsyncEntropyAssessment, EntropyAssessment, Provenance};
+use crate::error::{Classify, ErrorKind};
+use crate::EntropySource;
+
+/// An async source for random bytes used in cryptographic algorithms.
+///
+/// # Safety
+///
+/// This trait is `unsafe` because it assumes the source is cryptographically secure.
+pub unsafe trait AsyncEntropySource {
+    type EntropySourceError: Error;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> impl Future<Output = Result<(), Self::EntropySourceError>> + Send;
+}
+
+#[derive(Debug)]
+pub enum BlockingError<E: Error> {
+    /// The wrapped source failed.
+    Source(E),
+    /// The blocking task panicked or was cancelled.
+    Join(tokio::task::JoinError),
+}
+
+impl<E: Error> core::fmt::Display for BlockingError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<E: Error> Error for BlockingError<E> {}
+
+impl<E: Error + Classify> Classify for BlockingError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Source(e) => e.kind(),
+            Self::Join(_) => ErrorKind::Backend(0),
+        }
+    }
+}
+
+/// Runs reads from a blocking `EntropySource` on tokio's blocking thread pool (via `spawn_blocking`).
+///
+/// NOTE: Each read goes through an intermediate buffer which is wiped afterwards. A cancelled read still runs to
+/// completion in the background.
+pub struct Blocking<S: EntropySource> {
+    source: Arc<S>,
+}
+
+impl<S: EntropySource> Blocking<S> {
+    /// Wrap a blocking source.
+    pub fn new(source: S) -> Self {
+        Self::from_arc(Arc::new(source))
+    }
+
+    /// Wrap a blocking source shared with other users.
+    pub fn from_arc(source: Arc<S>) -> Self {
+        Self { source }
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &Arc<S> {
+        &self.source
+    }
+}
+
+unsafe impl<S> AsyncEntropySource for Blocking<S>
+where
+    S: EntropySource + Send + Sync + 'static,
+    S::EntropySourceError: Send + 'static,
+{
+    type EntropySourceError = BlockingError<S::EntropySourceError>;
+
+    async fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let source = self.source.clone();
+        let length = buffer.len();
+
+        let result = tokio::task::spawn_blocking(move || {
+            let mut bytes = Zeroizing::new(vec![0u8; length]);
+
+            source.read_bytes(&mut bytes).map(|_| bytes)
+        })
+        .await;
+
+        match result {
+            Ok(Ok(bytes)) => {
+                buffer.copy_from_slice(&bytes);
+
+                Ok(())
+            }
+            Ok(Err(e)) => Err(BlockingError::Source(e)),
+            Err(e) => Err(BlockingError::Join(e)),
+        }
+    }
+}
+
+impl<S> AsyncEntropyAssessment for Blocking<S>
+where
+    S: EntropyAssessment + Send + Sync + 'static,
+    S::EntropySourceError: Send + 'static,
+{
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.source.provenance()
+    }
+}
+
+/// An async PKCS#11 token source, running `C_GenerateRandom` on the blocking thread pool.
+#[cfg(feature = "hsm")]
+pub type AsyncPkcs11Source = Blocking<crate::sources::Pkcs11Source>;
+
+/// An async TPM 2.0 source, running `TPM2_GetRandom` on the blocking thread pool.
+#[cfg(feature = "tpm")]
+pub type AsyncTpm2<T = crate::sources::DeviceTcti> = Blocking<crate::sources::Tpm2<T>>;
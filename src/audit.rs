@@ -0,0 +1,286 @@
+//! Machine-readable audit reports on a source: self test, health tests, min-entropy and throughput in one artifact.
+
+use std::fmt::Write;
+use std::string::String;
+
+use crate::bench::{measure, BenchConfig, ThroughputReport};
+use crate::estimate::{collect, estimate, EntropyEstimate, EstimateError};
+use crate::health::{HealthConfig, HealthError, HealthTests};
+use crate::json::{Json, Number};
+use crate::selftest::{SelfTest, SelfTestError, SelfTestReport};
+use crate::EntropySource;
+
+/// Default number of samples collected for the health tests and the estimators.
+pub const DEFAULT_SAMPLES: usize = 1_000_000;
+
+/// What `Report::collect` measures.
+#[derive(Debug, Clone)]
+pub struct AuditConfig {
+    samples: usize,
+    bits_per_sample: u8,
+    claimed_min_entropy: u8,
+    bench: Option<BenchConfig>,
+}
+
+impl AuditConfig {
+    /// Collect the default number of full-byte samples, test them against a claim of full entropy, and benchmark with
+    /// the default configuration.
+    pub fn new() -> Self {
+        Self {
+            samples: DEFAULT_SAMPLES,
+            bits_per_sample: 8,
+            claimed_min_entropy: 8,
+            bench: Some(BenchConfig::new()),
+        }
+    }
+
+    /// Collect the given number of samples (one byte each) instead.
+    pub fn with_samples(mut self, samples: usize) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    /// Estimate samples holding the given number of bits each (1 to 8; higher bits are ignored).
+    pub fn with_bits_per_sample(mut self, bits: u8) -> Self {
+        self.bits_per_sample = bits;
+        self
+    }
+
+    /// Run the health tests with the cutoffs for the given claimed min-entropy per sample (in bits, clamped to 1..=8).
+    pub fn with_claimed_min_entropy(mut self, bits: u8) -> Self {
+        self.claimed_min_entropy = bits;
+        self
+    }
+
+    /// Benchmark with the given configuration.
+    pub fn with_bench_config(mut self, config: BenchConfig) -> Self {
+        self.bench = Some(config);
+        self
+    }
+
+    /// Skip the throughput measurement (e.g. for slow or rate-limited sources).
+    pub fn without_throughput(mut self) -> Self {
+        self.bench = None;
+        self
+    }
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What was audited, and where.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backend {
+    /// The type name of the source.
+    pub source: &'static str,
+    /// The version of this crate.
+    pub crate_version: &'static str,
+    /// The target architecture (e.g. `x86_64`).
+    pub target_arch: &'static str,
+    /// The target operating system (e.g. `linux`).
+    pub target_os: &'static str,
+}
+
+impl Backend {
+    /// Describe the source type `S` on this build.
+    pub(crate) fn of<S>() -> Self {
+        Self {
+            source: core::any::type_name::<S>(),
+            crate_version: env!("CARGO_PKG_VERSION"),
+            target_arch: std::env::consts::ARCH,
+            target_os: std::env::consts::OS,
+        }
+    }
+
+    /// Write the fields of the backend as a JSON object.
+    pub(crate) fn write_json(&self, json: &mut String) -> core::fmt::Result {
+        write!(json, "{{\"source\":{},", Json(self.source))?;
+        write!(json, "\"crate_version\":{},", Json(self.crate_version))?;
+        write!(json, "\"target_arch\":{},", Json(self.target_arch))?;
+        write!(json, "\"target_os\":{}}}", Json(self.target_os))
+    }
+}
+
+/// The continuous health tests run over the collected samples, with the worst values seen against their cutoffs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthStatistics {
+    /// Number of samples tested.
+    pub samples: usize,
+    /// Claimed min-entropy per sample the cutoffs were chosen for, in bits.
+    pub claimed_min_entropy: u8,
+    /// The cutoffs used.
+    pub config: HealthConfig,
+    /// Longest run of identical samples seen.
+    pub longest_run: usize,
+    /// Highest count of the first sample of an Adaptive Proportion Test window within that window.
+    pub max_window_count: usize,
+    /// The first test failure, if any.
+    pub failure: Option<HealthError>,
+}
+
+/// An audit of a source, for logging or handing to an assessor as JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub backend: Backend,
+    pub self_test: Result<SelfTestReport, SelfTestError>,
+    pub health: HealthStatistics,
+    pub estimate: Result<EntropyEstimate, EstimateError>,
+    /// `None` if skipped by the configuration.
+    pub throughput: Option<ThroughputReport>,
+}
+
+impl Report {
+    /// Run the self test, collect samples for the health tests and the estimators, then measure throughput.
+    ///
+    /// NOTE: Failed tests are recorded in the report rather than returned; only a failing source aborts the audit.
+    /// Audit the raw noise source where possible, since a conditioned one will always appear to have full entropy.
+    pub fn collect<S: EntropySource + SelfTest>(source: &S, config: &AuditConfig) -> Result<Self, S::EntropySourceError> {
+        let self_test = source.self_test();
+
+        let samples = collect(source, config.samples)?;
+
+        let bits = config.bits_per_sample.clamp(1, 8);
+        let mask = (0xffu16 >> (8 - bits)) as u8;
+
+        let health = Self::health(&samples, mask, config.claimed_min_entropy);
+
+        let estimate = estimate(&samples, config.bits_per_sample);
+
+        let throughput = match &config.bench {
+            Some(bench) => Some(measure(source, bench)?),
+            None => None,
+        };
+
+        Ok(Self {
+            backend: Backend::of::<S>(),
+            self_test,
+            health,
+            estimate,
+            throughput,
+        })
+    }
+
+    fn health(samples: &[u8], mask: u8, claimed_min_entropy: u8) -> HealthStatistics {
+        let config = HealthConfig::for_min_entropy(claimed_min_entropy);
+
+        let mut tests = HealthTests::new(config);
+
+        let mut longest_run = 0;
+        let mut run = 0;
+        let mut max_window_count = 0;
+
+        for (i, &sample) in samples.iter().enumerate() {
+            let sample = sample & mask;
+
+            let _ = tests.test(sample as u64);
+
+            match i > 0 && samples[i - 1] & mask == sample {
+                true => run += 1,
+                false => run = 1,
+            }
+
+            longest_run = longest_run.max(run);
+        }
+
+        for window in samples.chunks(config.apt_window()) {
+            let count = window.iter().filter(|&&sample| sample & mask == window[0] & mask).count();
+
+            max_window_count = max_window_count.max(count);
+        }
+
+        HealthStatistics {
+            samples: samples.len(),
+            claimed_min_entropy: claimed_min_entropy.clamp(1, 8),
+            config,
+            longest_run,
+            max_window_count,
+            failure: tests.failure(),
+        }
+    }
+
+    /// Whether the self test and the health tests passed.
+    ///
+    /// NOTE: The estimate is not judged here, since the estimators are conservative and fall short of 8 bits per byte
+    /// even for ideal output; compare `min_entropy` against the entropy the source is credited with instead.
+    pub fn passed(&self) -> bool {
+        self.self_test.is_ok() && self.health.failure.is_none()
+    }
+
+    /// Serialize the report as a JSON object.
+    ///
+    /// NOTE: Durations are in nanoseconds, and failures are recorded as strings (e.g. `"RepetitionCount"`).
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+
+        // NOTE: Writing to a `String` cannot fail.
+        let _ = self.write_json(&mut json);
+
+        json
+    }
+
+    fn write_json(&self, json: &mut String) -> core::fmt::Result {
+        write!(json, "{{\"passed\":{},\"backend\":", self.passed())?;
+
+        self.backend.write_json(json)?;
+
+        json.push(',');
+
+        match &self.self_test {
+            Ok(report) => write!(
+                json,
+                "\"self_test\":{{\"passed\":true,\"known_answer_tests\":{},\"health_samples\":{}}},",
+                report.known_answer_tests, report.health_samples
+            )?,
+            Err(e) => write!(json, "\"self_test\":{{\"passed\":false,\"error\":{}}},", Json(&format_args!("{:?}", e)))?,
+        }
+
+        let health = &self.health;
+
+        write!(json, "\"health\":{{\"samples\":{},", health.samples)?;
+        write!(json, "\"claimed_min_entropy\":{},", health.claimed_min_entropy)?;
+        write!(json, "\"rct_cutoff\":{},", health.config.rct_cutoff())?;
+        write!(json, "\"apt_window\":{},", health.config.apt_window())?;
+        write!(json, "\"apt_cutoff\":{},", health.config.apt_cutoff())?;
+        write!(json, "\"longest_run\":{},", health.longest_run)?;
+        write!(json, "\"max_window_count\":{},", health.max_window_count)?;
+
+        match health.failure {
+            Some(e) => write!(json, "\"failure\":{}}},", Json(&format_args!("{:?}", e)))?,
+            None => write!(json, "\"failure\":null}},")?,
+        }
+
+        match &self.estimate {
+            Ok(estimate) => {
+                write!(json, "\"estimate\":{{\"most_common_value\":{},", Number(estimate.most_common_value))?;
+                write!(json, "\"collision\":{},", Number(estimate.collision))?;
+                write!(json, "\"markov\":{},", Number(estimate.markov))?;
+                write!(json, "\"compression\":{},", Number(estimate.compression))?;
+                write!(json, "\"min_entropy\":{}}},", Number(estimate.min_entropy))?;
+            }
+            Err(e) => write!(json, "\"estimate\":{{\"error\":{}}},", Json(&format_args!("{:?}", e)))?,
+        }
+
+        let throughput = match &self.throughput {
+            Some(throughput) => throughput,
+            None => return write!(json, "\"throughput\":null}}"),
+        };
+
+        write!(json, "\"throughput\":[")?;
+
+        for (i, block) in throughput.blocks.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+
+            write!(json, "{{\"block_size\":{},\"calls\":{},", block.block_size, block.calls)?;
+            write!(json, "\"bytes_per_second\":{},", Number(block.bytes_per_second))?;
+            write!(json, "\"p50_ns\":{},\"p90_ns\":{},", block.p50.as_nanos(), block.p90.as_nanos())?;
+            write!(json, "\"p99_ns\":{},\"max_ns\":{}}}", block.p99.as_nanos(), block.max.as_nanos())?;
+        }
+
+        write!(json, "]}}")
+    }
+}
@@ -0,0 +1,138 @@
+//! Runtime throughput and latency measurement for sources.
+
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+
+use crate::EntropySource;
+
+/// Default block sizes measured, in bytes.
+pub const DEFAULT_BLOCK_SIZES: &[usize] = &[16, 64, 256, 4096, 65536];
+
+/// Default time spent measuring each block size.
+pub const DEFAULT_DURATION: Duration = Duration::from_millis(100);
+
+/// What `measure` measures, and for how long.
+#[derive(Debug, Clone)]
+pub struct BenchConfig {
+    block_sizes: Vec<usize>,
+    duration: Duration,
+    warmup: usize,
+}
+
+impl BenchConfig {
+    /// Measure the default block sizes for the default duration each.
+    pub fn new() -> Self {
+        Self {
+            block_sizes: DEFAULT_BLOCK_SIZES.to_vec(),
+            duration: DEFAULT_DURATION,
+            warmup: 8,
+        }
+    }
+
+    /// Measure the given block sizes (in bytes) instead.
+    pub fn with_block_sizes(mut self, block_sizes: &[usize]) -> Self {
+        self.block_sizes = block_sizes.to_vec();
+        self
+    }
+
+    /// Spend the given time measuring each block size.
+    ///
+    /// NOTE: At least one read is always measured, however slow the source.
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Perform the given number of unmeasured reads before measuring each block size.
+    pub fn with_warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The measurements for one block size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlockReport {
+    /// Bytes requested per call.
+    pub block_size: usize,
+    /// Number of measured calls.
+    pub calls: usize,
+    /// Total time spent in measured calls.
+    pub elapsed: Duration,
+    /// Throughput, in bytes per second.
+    pub bytes_per_second: f64,
+    /// Median latency per call.
+    pub p50: Duration,
+    /// 90th percentile latency per call.
+    pub p90: Duration,
+    /// 99th percentile latency per call.
+    pub p99: Duration,
+    /// Worst latency per call.
+    pub max: Duration,
+}
+
+/// The measurements for every block size, in the configured order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThroughputReport {
+    pub blocks: Vec<BlockReport>,
+}
+
+impl ThroughputReport {
+    /// Get the measurements with the highest throughput, if any.
+    pub fn fastest(&self) -> Option<&BlockReport> {
+        self.blocks.iter().max_by(|a, b| a.bytes_per_second.total_cmp(&b.bytes_per_second))
+    }
+}
+
+/// Measure the throughput and per-call latency of a source at each configured block size.
+///
+/// NOTE: This consumes real output from the source (`duration` worth per block size), which matters for slow or
+/// rate-limited sources such as a TPM.
+pub fn measure<S: EntropySource>(source: &S, config: &BenchConfig) -> Result<ThroughputReport, S::EntropySourceError> {
+    let mut blocks = Vec::with_capacity(config.block_sizes.len());
+
+    for block_size in config.block_sizes.iter().copied() {
+        let mut buffer = std::vec![0u8; block_size];
+
+        for _ in 0..config.warmup {
+            source.read_bytes(&mut buffer)?;
+        }
+
+        let mut latencies = Vec::new();
+
+        let start = Instant::now();
+
+        while latencies.is_empty() || start.elapsed() < config.duration {
+            let call = Instant::now();
+
+            source.read_bytes(&mut buffer)?;
+
+            latencies.push(call.elapsed());
+        }
+
+        let elapsed: Duration = latencies.iter().sum();
+
+        latencies.sort_unstable();
+
+        let percentile = |p: usize| latencies[(latencies.len() * p).div_ceil(100).max(1) - 1];
+
+        blocks.push(BlockReport {
+            block_size,
+            calls: latencies.len(),
+            elapsed,
+            bytes_per_second: (block_size * latencies.len()) as f64 / elapsed.as_secs_f64().max(f64::MIN_POSITIVE),
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: latencies[latencies.len() - 1],
+        });
+    }
+
+    Ok(ThroughputReport { blocks })
+}
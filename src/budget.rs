@@ -0,0 +1,156 @@
+//! Capping the output of a generator per instantiation, as SP 800-90A requires for DRBGs.
+
+use core::error::Error;
+
+use crate::assess::{EntropyAssessment, Provenance};
+use crate::error::{Classify, ErrorKind};
+use crate::lock::Lock;
+use crate::trace;
+use crate::EntropySource;
+
+#[derive(Debug)]
+pub enum BudgetError<E: Error> {
+    /// The wrapped source failed (nothing was charged to the budget).
+    Source(E),
+    /// The read would exceed the remaining budget; reseed or re-instantiate the source, then `replenish` it.
+    BudgetExhausted,
+}
+
+impl<E: Error> core::fmt::Display for BudgetError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<E: Error> Error for BudgetError<E> {}
+
+impl<E: Error + Classify> Classify for BudgetError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Source(e) => e.kind(),
+            Self::BudgetExhausted => ErrorKind::Exhausted,
+        }
+    }
+}
+
+struct BudgetState {
+    used: u64,
+    /// Incremented by every `replenish`, so reads reserved before it do not refund into the restored budget.
+    generation: u64,
+}
+
+/// Serves at most a fixed number of bytes from the wrapped source, then fails every read until it is replenished.
+///
+/// NOTE: A read that does not fit in the remaining budget fails as a whole, without reading anything, so the budget
+/// is never overrun. Failed reads of the source are not charged.
+pub struct BudgetedSource<S: EntropySource> {
+    source: S,
+    budget: u64,
+    state: Lock<BudgetState>,
+}
+
+impl<S: EntropySource> BudgetedSource<S> {
+    /// Wrap a source, allowing `budget` bytes of output.
+    pub fn new(source: S, budget: u64) -> Self {
+        Self {
+            source,
+            budget,
+            state: Lock::new(BudgetState { used: 0, generation: 0 }),
+        }
+    }
+
+    /// Get the total number of bytes allowed.
+    pub fn budget(&self) -> u64 {
+        self.budget
+    }
+
+    /// Get the number of bytes that may still be read.
+    pub fn remaining(&self) -> u64 {
+        self.state.with(|state| self.budget - state.used)
+    }
+
+    /// Restore the full budget.
+    ///
+    /// NOTE: Only do this once the wrapped source has been reseeded, or replaced by a new instantiation.
+    pub fn replenish(&self) {
+        self.state.with(|state| {
+            state.used = 0;
+            state.generation += 1;
+        });
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Unwrap the source.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+#[cfg(feature = "drbg")]
+impl<S: crate::drbg::Drbg> BudgetedSource<S> {
+    /// Attempt to reseed the wrapped generator from its `EntropySource`, restoring the full budget if it succeeds.
+    pub fn reseed(&self) -> Result<(), crate::drbg::DrbgError<<S::Source as EntropySource>::EntropySourceError>> {
+        self.source.reseed()?;
+
+        self.replenish();
+
+        Ok(())
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySource for BudgetedSource<S> {
+    type EntropySourceError = BudgetError<S::EntropySourceError>;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        let length = buffer.len() as u64;
+
+        // NOTE: The bytes are reserved before reading, so concurrent reads cannot overrun the budget together.
+        let reserved = self.state.with(|state| match length <= self.budget - state.used {
+            true => {
+                state.used += length;
+
+                Some(state.generation)
+            }
+            false => None,
+        });
+
+        let generation = match reserved {
+            Some(generation) => generation,
+            None => {
+                trace::event!(warn, budget = self.budget, requested = length, "budget exhausted");
+
+                return Err(BudgetError::BudgetExhausted);
+            }
+        };
+
+        match self.source.read_bytes(buffer) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                // NOTE: A reservation made before the budget was replenished is not part of the restored budget.
+                self.state.with(|state| {
+                    if state.generation == generation {
+                        state.used -= length;
+                    }
+                });
+
+                Err(BudgetError::Source(e))
+            }
+        }
+    }
+}
+
+impl<S: EntropyAssessment> EntropyAssessment for BudgetedSource<S> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.source.provenance()
+    }
+}
@@ -0,0 +1,225 @@
+//! Buffering small reads from expensive entropy sources.
+
+use alloc::vec::Vec;
+
+use zeroize::Zeroize;
+
+use crate::assess::{EntropyAssessment, Provenance};
+use crate::fork;
+use crate::lock::Lock;
+use crate::trace;
+use crate::EntropySource;
+
+/// Default refill buffer size.
+const DEFAULT_CAPACITY: usize = 4096;
+
+/// A refill buffer, shared with `thread_local::LocalPool`.
+pub(crate) struct Buffer {
+    bytes: Vec<u8>,
+    position: usize,
+    fork_epoch: u64,
+}
+
+impl Buffer {
+    pub(crate) const fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            position: 0,
+            fork_epoch: 0,
+        }
+    }
+
+    /// Copy as many buffered bytes as possible into `output`, wiping them from the buffer.
+    fn take(&mut self, output: &mut [u8]) -> usize {
+        let available = &mut self.bytes[self.position..];
+        let length = available.len().min(output.len());
+
+        output[..length].copy_from_slice(&available[..length]);
+
+        available[..length].zeroize();
+
+        self.position += length;
+
+        length
+    }
+
+    /// Serve `output` from the buffer, refilling it with `capacity` bytes from `source` when it runs out.
+    ///
+    /// NOTE: Outputs of at least `capacity` bytes bypass the buffer.
+    pub(crate) fn read<S: EntropySource>(
+        &mut self,
+        source: &S,
+        output: &mut [u8],
+        capacity: usize,
+    ) -> Result<(), S::EntropySourceError> {
+        if output.len() >= capacity {
+            return source.read_bytes(output);
+        }
+
+        self.discard_after_fork();
+
+        let served = self.take(output);
+
+        if served == output.len() {
+            return Ok(());
+        }
+
+        self.bytes.resize(capacity, 0);
+        self.position = 0;
+
+        if let Err(e) = source.read_bytes(&mut self.bytes) {
+            self.clear();
+
+            return Err(e);
+        }
+
+        self.take(&mut output[served..]);
+
+        Ok(())
+    }
+
+    /// Get the bytes not yet served, first refilling the buffer with `capacity` bytes from `source` if none are left.
+    #[cfg(feature = "io")]
+    pub(crate) fn fill<S: EntropySource>(
+        &mut self,
+        source: &S,
+        capacity: usize,
+    ) -> Result<&[u8], S::EntropySourceError> {
+        self.discard_after_fork();
+
+        if self.position == self.bytes.len() {
+            self.bytes.resize(capacity, 0);
+            self.position = 0;
+
+            if let Err(e) = source.read_bytes(&mut self.bytes) {
+                self.clear();
+
+                return Err(e);
+            }
+        }
+
+        Ok(&self.bytes[self.position..])
+    }
+
+    /// Mark the first `amount` bytes returned by `fill` as served, wiping them from the buffer.
+    #[cfg(feature = "io")]
+    pub(crate) fn consume(&mut self, amount: usize) {
+        let end = (self.position + amount).min(self.bytes.len());
+
+        self.bytes[self.position..end].zeroize();
+
+        self.position = end;
+    }
+
+    /// Wipe the buffered bytes if they were read before a `fork`.
+    ///
+    /// NOTE: Bytes buffered before a `fork` were also buffered by the parent, so the child discards them.
+    fn discard_after_fork(&mut self) {
+        let fork_epoch = fork::epoch();
+
+        if self.fork_epoch != fork_epoch {
+            trace::event!(debug, "buffered bytes discarded after fork");
+
+            self.clear();
+            self.fork_epoch = fork_epoch;
+        }
+    }
+
+    /// Get the number of buffered bytes not yet served.
+    #[cfg(feature = "concurrent")]
+    pub(crate) fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    /// Swap in freshly read `bytes` (read in `fork_epoch`), leaving the previously buffered bytes wiped in `bytes`.
+    #[cfg(feature = "concurrent")]
+    pub(crate) fn replace(&mut self, bytes: &mut Vec<u8>, fork_epoch: u64) {
+        core::mem::swap(&mut self.bytes, bytes);
+
+        bytes.zeroize();
+
+        self.position = 0;
+        self.fork_epoch = fork_epoch;
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.bytes.zeroize();
+        self.position = 0;
+    }
+}
+
+impl Drop for Buffer {
+    fn drop(&mut self) {
+        self.bytes.zeroize();
+    }
+}
+
+/// Serves small reads from an internal buffer refilled from the wrapped source in large reads.
+///
+/// NOTE: Reads at least as large as the buffer bypass it. Served bytes are wiped from the buffer immediately, but
+/// unserved bytes stay in memory until they are read, `clear` is called, or the source is dropped.
+pub struct BufferedSource<S: EntropySource> {
+    source: S,
+    buffer: Lock<Buffer>,
+    capacity: usize,
+}
+
+impl<S: EntropySource> BufferedSource<S> {
+    /// Wrap a source with a 4 KiB buffer.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            buffer: Lock::new(Buffer::new()),
+            capacity: DEFAULT_CAPACITY,
+        }
+    }
+
+    /// Set the refill buffer size (minimum 1), discarding any buffered bytes.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.buffer.with(|buffer| buffer.clear());
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Discard (and wipe) any buffered bytes.
+    pub fn clear(&self) {
+        self.buffer.with(|buffer| buffer.clear());
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySource for BufferedSource<S> {
+    type EntropySourceError = S::EntropySourceError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.buffer.with(|state| state.read(&self.source, buffer, self.capacity))
+    }
+
+    #[cfg(feature = "std")]
+    fn read_bytes_vectored(&self, buffers: &mut [std::io::IoSliceMut<'_>]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffers.iter().map(|buffer| buffer.len()).sum());
+
+        self.buffer.with(|state| {
+            buffers
+                .iter_mut()
+                .try_for_each(|buffer| state.read(&self.source, buffer, self.capacity))
+        })
+    }
+}
+
+/// NOTE: Buffering serves the same bytes the source produced, so it keeps the rate of the source.
+impl<S: EntropyAssessment> EntropyAssessment for BufferedSource<S> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.source.provenance()
+    }
+}
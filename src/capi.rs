@@ -0,0 +1,182 @@
+//! A C API over the OS entropy source and HMAC_DRBG.
+//!
+//! Build the shared library with `cargo rustc --release --lib --features capi --crate-type cdylib`, and include
+//! `include/librypt_entropy.h` (regenerate it with `cbindgen --config cbindgen.toml -o include/librypt_entropy.h`
+//! after changing this module).
+//!
+//! NOTE: Every function catches Rust panics and reports them as `LIBRYPT_STATUS_PANIC` instead of unwinding into C.
+
+use core::ffi::{c_char, c_int, CStr};
+use std::boxed::Box;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::drbg::{Drbg, DrbgError, HmacDrbg};
+use crate::os::{OsEntropy, OsEntropySourceError};
+use crate::EntropySource;
+
+/// The result of a C API call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LibryptStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// The OS entropy source is not supported on this platform (or is blocked, e.g. by a seccomp filter).
+    Unsupported = 2,
+    /// The entropy source failed.
+    SourceFailed = 3,
+    /// A Rust panic was caught at the API boundary.
+    Panic = 4,
+}
+
+impl From<OsEntropySourceError> for LibryptStatus {
+    fn from(error: OsEntropySourceError) -> Self {
+        match error.is_unsupported() {
+            true => Self::Unsupported,
+            false => Self::SourceFailed,
+        }
+    }
+}
+
+/// An HMAC_DRBG (SHA-256) seeded from the OS entropy source.
+///
+/// NOTE: Safe to use from multiple threads at once; calls are serialized internally.
+pub struct LibryptDrbg {
+    drbg: HmacDrbg<OsEntropy>,
+}
+
+/// Run `f`, reporting a panic as `LibryptStatus::Panic`.
+fn guard(f: impl FnOnce() -> LibryptStatus) -> LibryptStatus {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(LibryptStatus::Panic)
+}
+
+/// Get `len` bytes at `buf` as a slice, or `None` if `buf` is null (an empty buffer may be null).
+///
+/// # Safety
+///
+/// `buf` must be null or valid for writes of `len` bytes.
+unsafe fn buffer<'a>(buf: *mut u8, len: usize) -> Option<&'a mut [u8]> {
+    match (buf.is_null(), len) {
+        (_, 0) => Some(&mut []),
+        (true, _) => None,
+        // SAFETY: Guaranteed by the caller.
+        (false, _) => Some(unsafe { core::slice::from_raw_parts_mut(buf, len) }),
+    }
+}
+
+/// Fill `len` bytes at `buf` with entropy from the OS.
+///
+/// # Safety
+///
+/// `buf` must be valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn librypt_entropy_os_read(buf: *mut u8, len: usize) -> LibryptStatus {
+    guard(|| {
+        // SAFETY: Guaranteed by the caller.
+        let buffer = match unsafe { buffer(buf, len) } {
+            Some(buffer) => buffer,
+            None => return LibryptStatus::NullPointer,
+        };
+
+        match OsEntropy.read_bytes(buffer) {
+            Ok(()) => LibryptStatus::Ok,
+            Err(e) => e.into(),
+        }
+    })
+}
+
+/// Create a DRBG seeded from the OS, storing it in `*out` (to be released with `librypt_drbg_free`).
+///
+/// # Safety
+///
+/// `out` must be valid for writes of a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn librypt_drbg_new(out: *mut *mut LibryptDrbg) -> LibryptStatus {
+    guard(|| {
+        if out.is_null() {
+            return LibryptStatus::NullPointer;
+        }
+
+        let drbg = match HmacDrbg::instantiate(OsEntropy) {
+            Ok(drbg) => drbg,
+            Err(DrbgError::Source(e)) => return e.into(),
+        };
+
+        // SAFETY: Guaranteed by the caller.
+        unsafe { out.write(Box::into_raw(Box::new(LibryptDrbg { drbg }))) };
+
+        LibryptStatus::Ok
+    })
+}
+
+/// Fill `len` bytes at `buf` with output from `drbg`, reseeding from the OS when required.
+///
+/// # Safety
+///
+/// `drbg` must come from `librypt_drbg_new` and not have been freed, and `buf` must be valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn librypt_drbg_generate(drbg: *const LibryptDrbg, buf: *mut u8, len: usize) -> LibryptStatus {
+    guard(|| {
+        // SAFETY: Guaranteed by the caller.
+        let (drbg, buffer) = match unsafe { (drbg.as_ref(), buffer(buf, len)) } {
+            (Some(drbg), Some(buffer)) => (drbg, buffer),
+            _ => return LibryptStatus::NullPointer,
+        };
+
+        match drbg.drbg.generate(buffer) {
+            Ok(()) => LibryptStatus::Ok,
+            Err(DrbgError::Source(e)) => e.into(),
+        }
+    })
+}
+
+/// Reseed `drbg` from the OS immediately (e.g. after restoring a VM snapshot).
+///
+/// # Safety
+///
+/// `drbg` must come from `librypt_drbg_new` and not have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn librypt_drbg_reseed(drbg: *const LibryptDrbg) -> LibryptStatus {
+    guard(|| {
+        // SAFETY: Guaranteed by the caller.
+        let drbg = match unsafe { drbg.as_ref() } {
+            Some(drbg) => drbg,
+            None => return LibryptStatus::NullPointer,
+        };
+
+        match drbg.drbg.reseed() {
+            Ok(()) => LibryptStatus::Ok,
+            Err(DrbgError::Source(e)) => e.into(),
+        }
+    })
+}
+
+/// Release (and wipe) a DRBG. Passing null does nothing.
+///
+/// # Safety
+///
+/// `drbg` must be null or come from `librypt_drbg_new`, and must not be used again.
+#[no_mangle]
+pub unsafe extern "C" fn librypt_drbg_free(drbg: *mut LibryptDrbg) {
+    if !drbg.is_null() {
+        // SAFETY: Guaranteed by the caller.
+        let _ = catch_unwind(AssertUnwindSafe(|| drop(unsafe { Box::from_raw(drbg) })));
+    }
+}
+
+/// Get a static, NUL-terminated description of a status (values outside `LibryptStatus` are described as unknown).
+#[no_mangle]
+pub extern "C" fn librypt_status_str(status: c_int) -> *const c_char {
+    // NOTE: Taken as an integer, since C may pass any value and an out-of-range Rust enum is undefined behavior.
+    let description: &'static CStr = match status {
+        0 => c"ok",
+        1 => c"null pointer argument",
+        2 => c"entropy source unsupported",
+        3 => c"entropy source failed",
+        4 => c"internal panic",
+        _ => c"unknown status",
+    };
+
+    description.as_ptr()
+}
@@ -0,0 +1,291 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::error::Error;
+
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+use crate::assess::{self, Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::erased::ErasedEntropySource;
+use crate::error::{Classify, ErrorKind};
+use crate::trace;
+use crate::EntropySource;
+
+/// Domain separation prefix for hash-based extraction.
+const HASH_DOMAIN: &[u8] = b"librypt-entropy combine v1";
+
+/// Size of the chunks read from every source per round.
+const CHUNK_SIZE: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombinerError {
+    /// The combiner has no sources, or every source failed.
+    AllFailed,
+    /// The mandatory source at the given index (in order of addition) failed, with the kind of its error.
+    MandatoryFailed(usize, ErrorKind),
+}
+
+impl core::fmt::Display for CombinerError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for CombinerError {}
+
+impl Classify for CombinerError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::AllFailed => ErrorKind::Exhausted,
+            Self::MandatoryFailed(_, kind) => *kind,
+        }
+    }
+}
+
+/// How the outputs of the sources are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombineMode {
+    /// XOR the outputs together. Secure as long as one source is good and the sources are independent.
+    Xor,
+    /// Hash every 32-byte block of all outputs (with the source index and status) through SHA-256.
+    ///
+    /// NOTE: Unlike XOR, this also holds up if a bad source can observe (or is correlated with) the other sources.
+    Hash,
+}
+
+struct Member {
+    source: Box<dyn ErasedEntropySource + Send + Sync>,
+    mandatory: bool,
+    assessment: Option<(f64, SourceClass)>,
+}
+
+/// Combines the output of multiple independent `EntropySource`s into one.
+///
+/// NOTE: Reads only fail if every source fails, or if any source added with `with_mandatory_source` fails.
+pub struct Combiner {
+    mode: CombineMode,
+    members: Vec<Member>,
+}
+
+impl Combiner {
+    /// Create an empty combiner.
+    pub fn new(mode: CombineMode) -> Self {
+        Self {
+            mode,
+            members: Vec::new(),
+        }
+    }
+
+    /// Add a source which is allowed to fail as long as another source succeeds.
+    ///
+    /// NOTE: Sources added without an assessment are credited with nothing by the combiner's `EntropyAssessment`.
+    pub fn with_source<S>(mut self, source: S) -> Self
+    where
+        S: EntropySource + Send + Sync + 'static,
+        S::EntropySourceError: Send + Sync + 'static,
+    {
+        self.members.push(Member {
+            source: Box::new(source),
+            mandatory: false,
+            assessment: None,
+        });
+        self
+    }
+
+    /// Add a source whose failure fails the whole read.
+    ///
+    /// NOTE: Pass `source.erase_classified()` for `MandatoryFailed` to carry the kind of its error.
+    pub fn with_mandatory_source<S>(mut self, source: S) -> Self
+    where
+        S: EntropySource + Send + Sync + 'static,
+        S::EntropySourceError: Send + Sync + 'static,
+    {
+        self.members.push(Member {
+            source: Box::new(source),
+            mandatory: true,
+            assessment: None,
+        });
+        self
+    }
+
+    /// Add a source as with `with_source`, keeping its assessment for the combiner's own.
+    pub fn with_assessed_source<S>(mut self, source: S) -> Self
+    where
+        S: EntropyAssessment + Send + Sync + 'static,
+        S::EntropySourceError: Send + Sync + 'static,
+    {
+        self.members.push(Member {
+            assessment: Some((source.min_entropy_per_byte(), source.provenance().class)),
+            source: Box::new(source),
+            mandatory: false,
+        });
+        self
+    }
+
+    /// Add a source as with `with_mandatory_source`, keeping its assessment for the combiner's own.
+    pub fn with_assessed_mandatory_source<S>(mut self, source: S) -> Self
+    where
+        S: EntropyAssessment + Send + Sync + 'static,
+        S::EntropySourceError: Send + Sync + 'static,
+    {
+        self.members.push(Member {
+            assessment: Some((source.min_entropy_per_byte(), source.provenance().class)),
+            source: Box::new(source),
+            mandatory: true,
+        });
+        self
+    }
+
+    /// Get the number of sources.
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Whether the combiner has no sources.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Read a chunk from every source into `outputs`, returning which sources succeeded.
+    fn read_all(&self, outputs: &mut [u8], length: usize, succeeded: &mut [bool]) -> Result<(), CombinerError> {
+        for (index, member) in self.members.iter().enumerate() {
+            let output = &mut outputs[index * length..(index + 1) * length];
+
+            succeeded[index] = match member.source.read_bytes_erased(output) {
+                Ok(_) => true,
+                Err(e) => {
+                    trace::event!(warn, index, error = ?e, "combined source failed");
+
+                    output.fill(0);
+
+                    if member.mandatory {
+                        return Err(CombinerError::MandatoryFailed(index, e.kind()));
+                    }
+
+                    false
+                }
+            };
+        }
+
+        match succeeded.contains(&true) {
+            true => Ok(()),
+            false => Err(CombinerError::AllFailed),
+        }
+    }
+
+    fn combine(&self, chunk: &mut [u8], outputs: &[u8], succeeded: &[bool], block_counter: &mut u64) {
+        let length = chunk.len();
+
+        match self.mode {
+            CombineMode::Xor => {
+                chunk.fill(0);
+
+                for output in outputs.chunks(length) {
+                    for (byte, other) in chunk.iter_mut().zip(output) {
+                        *byte ^= other;
+                    }
+                }
+            }
+            CombineMode::Hash => {
+                for (offset, block) in (0..length).step_by(32).zip(chunk.chunks_mut(32)) {
+                    let mut hasher = Sha256::new();
+
+                    hasher.update(HASH_DOMAIN);
+                    hasher.update(block_counter.to_be_bytes());
+
+                    for (index, output) in outputs.chunks(length).enumerate() {
+                        hasher.update((index as u32).to_be_bytes());
+                        hasher.update([succeeded[index] as u8]);
+                        hasher.update(&output[offset..offset + block.len()]);
+                    }
+
+                    let mut digest: [u8; 32] = hasher.finalize().into();
+
+                    block.copy_from_slice(&digest[..block.len()]);
+
+                    digest.zeroize();
+
+                    *block_counter += 1;
+                }
+            }
+        }
+    }
+}
+
+unsafe impl EntropySource for Combiner {
+    type EntropySourceError = CombinerError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        if self.members.is_empty() {
+            return Err(CombinerError::AllFailed);
+        }
+
+        let chunk_size = buffer.len().min(CHUNK_SIZE);
+        let mut outputs = vec![0u8; chunk_size * self.members.len()];
+        let mut succeeded = vec![false; self.members.len()];
+        let mut block_counter = 0;
+
+        let mut result = Ok(());
+
+        for chunk in buffer.chunks_mut(CHUNK_SIZE) {
+            let outputs = &mut outputs[..chunk.len() * self.members.len()];
+
+            result = self.read_all(outputs, chunk.len(), &mut succeeded);
+
+            if result.is_err() {
+                break;
+            }
+
+            self.combine(chunk, outputs, &succeeded, &mut block_counter);
+        }
+
+        outputs.zeroize();
+
+        result
+    }
+}
+
+/// NOTE: Only the sources every successful read is guaranteed to include are credited: the mandatory sources, or the
+/// weakest source if there are none, since a read succeeds as long as any one of them does.
+impl EntropyAssessment for Combiner {
+    fn min_entropy_per_byte(&self) -> f64 {
+        let rate = |member: &Member| member.assessment.map_or(0.0, |(rate, _)| rate);
+
+        let weakest = match self.members.is_empty() {
+            true => 0.0,
+            false => self.members.iter().map(rate).fold(8.0, f64::min),
+        };
+
+        let mandatory = self.members.iter().filter(|member| member.mandatory).map(rate);
+
+        match self.mode {
+            // NOTE: The XOR of independent sources has at least the min-entropy of the strongest of them.
+            CombineMode::Xor => mandatory.fold(weakest, f64::max),
+            CombineMode::Hash => {
+                let input = match self.members.iter().any(|member| member.mandatory) {
+                    true => mandatory.sum(),
+                    false => weakest,
+                };
+
+                assess::conditioned(input * 32.0, 32)
+            }
+        }
+    }
+
+    fn provenance(&self) -> Provenance {
+        let mut classes = self.members.iter().map(|member| member.assessment.map(|(_, class)| class));
+
+        let class = match classes.next() {
+            Some(Some(first)) if classes.all(|class| class == Some(first)) => first,
+            _ => SourceClass::Other,
+        };
+
+        Provenance {
+            class,
+            basis: Basis::Derived,
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use core::error::Error;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{Classify, ErrorKind};
+use crate::trace;
+use crate::EntropySource;
+
+#[derive(Debug)]
+pub enum FallbackError<P: Error, S: Error> {
+    /// The primary source failed and the policy did not allow falling back.
+    Primary(P),
+    /// Both the primary and the secondary source failed.
+    Both(P, S),
+}
+
+impl<P: Error, S: Error> core::fmt::Display for FallbackError<P, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<P: Error, S: Error> Error for FallbackError<P, S> {}
+
+/// NOTE: When both sources failed, the secondary's error (the last one) decides the kind.
+impl<P: Error + Classify, S: Error + Classify> Classify for FallbackError<P, S> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Primary(e) => e.kind(),
+            Self::Both(_, e) => e.kind(),
+        }
+    }
+}
+
+/// Whether a `Fallback` may use its secondary source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FallbackPolicy {
+    /// Fall back to the secondary source whenever the primary source fails.
+    #[default]
+    Allow,
+    /// Never fall back; for contexts that require the (presumably stronger) primary source.
+    SecureRequired,
+}
+
+/// Reads from a primary source, falling back to a secondary source on error.
+///
+/// Longer chains can be built with `or`, e.g. `Fallback::new(trng, rdrand).or(jitter)`.
+///
+/// NOTE: A buffer is always filled entirely by a single source; the secondary source refills it from scratch.
+pub struct Fallback<A: EntropySource, B: EntropySource> {
+    primary: A,
+    secondary: B,
+    policy: FallbackPolicy,
+}
+
+impl<A: EntropySource, B: EntropySource> Fallback<A, B> {
+    /// Create a fallback chain with the `Allow` policy.
+    pub fn new(primary: A, secondary: B) -> Self {
+        Self {
+            primary,
+            secondary,
+            policy: FallbackPolicy::default(),
+        }
+    }
+
+    /// Set the fallback policy.
+    pub fn with_policy(mut self, policy: FallbackPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Append another source to the chain, used when every previous source failed.
+    ///
+    /// NOTE: The new link inherits this chain's policy.
+    pub fn or<C: EntropySource>(self, next: C) -> Fallback<Self, C> {
+        let policy = self.policy;
+
+        Fallback {
+            primary: self,
+            secondary: next,
+            policy,
+        }
+    }
+
+    /// Get the fallback policy.
+    pub fn policy(&self) -> FallbackPolicy {
+        self.policy
+    }
+
+    /// Get a reference to the primary source.
+    pub fn primary(&self) -> &A {
+        &self.primary
+    }
+
+    /// Get a reference to the secondary source.
+    pub fn secondary(&self) -> &B {
+        &self.secondary
+    }
+}
+
+unsafe impl<A: EntropySource, B: EntropySource> EntropySource for Fallback<A, B> {
+    type EntropySourceError = FallbackError<A::EntropySourceError, B::EntropySourceError>;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        let primary = match self.primary.read_bytes(buffer) {
+            Ok(_) => return Ok(()),
+            Err(e) => e,
+        };
+
+        if self.policy == FallbackPolicy::SecureRequired {
+            trace::event!(warn, error = ?primary, "primary source failed, fallback not allowed");
+
+            return Err(FallbackError::Primary(primary));
+        }
+
+        trace::event!(warn, error = ?primary, "primary source failed, falling back to secondary");
+
+        match self.secondary.read_bytes(buffer) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(FallbackError::Both(primary, e)),
+        }
+    }
+}
+
+/// NOTE: Credited with the weaker of the two sources, unless the policy rules out falling back.
+impl<A: EntropyAssessment, B: EntropyAssessment> EntropyAssessment for Fallback<A, B> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        match self.policy {
+            FallbackPolicy::Allow => self.primary.min_entropy_per_byte().min(self.secondary.min_entropy_per_byte()),
+            FallbackPolicy::SecureRequired => self.primary.min_entropy_per_byte(),
+        }
+    }
+
+    fn provenance(&self) -> Provenance {
+        let primary = self.primary.provenance();
+
+        let class = match self.policy {
+            FallbackPolicy::Allow if self.secondary.provenance().class != primary.class => SourceClass::Other,
+            _ => primary.class,
+        };
+
+        Provenance {
+            class,
+            basis: Basis::Derived,
+        }
+    }
+}
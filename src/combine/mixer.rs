@@ -0,0 +1,104 @@
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+use crate::{Entropy, EntropySource};
+
+const MIX_DOMAIN: &[u8] = b"librypt-entropy mix v1";
+const EXPAND_DOMAIN: &[u8] = b"librypt-entropy mix expand v1";
+
+/// Size of the entropy read by `try_with_source`.
+const SOURCE_INPUT_LEN: usize = 32;
+
+/// Hashes several independent random inputs into one `Entropy<LENGTH>`.
+///
+/// Every input is length-prefixed and numbered, and the whole mix is bound to a caller-chosen context string, so
+/// different protocols (or different uses within one) never derive the same output from the same inputs. The output
+/// is as unpredictable as the strongest input, provided the inputs are independent.
+///
+/// NOTE: The order of inputs matters; mixing `a` then `b` gives a different result than `b` then `a`.
+pub struct Mixer {
+    hasher: Sha256,
+    inputs: u32,
+}
+
+impl Mixer {
+    /// Start a new mix bound to the given context.
+    pub fn new(context: &[u8]) -> Self {
+        let mut hasher = Sha256::new();
+
+        hasher.update(MIX_DOMAIN);
+        hasher.update((context.len() as u64).to_be_bytes());
+        hasher.update(context);
+
+        Self { hasher, inputs: 0 }
+    }
+
+    /// Mix in some input bytes.
+    pub fn with_input(mut self, input: &[u8]) -> Self {
+        self.hasher.update(self.inputs.to_be_bytes());
+        self.hasher.update((input.len() as u64).to_be_bytes());
+        self.hasher.update(input);
+
+        self.inputs += 1;
+        self
+    }
+
+    /// Attempt to mix in 32 fresh bytes read from the provided `EntropySource`.
+    pub fn try_with_source<S: EntropySource>(self, source: &S) -> Result<Self, S::EntropySourceError> {
+        let mut input = [0u8; SOURCE_INPUT_LEN];
+
+        if let Err(e) = source.read_bytes(&mut input) {
+            input.zeroize();
+
+            return Err(e);
+        }
+
+        let mixer = self.with_input(&input);
+
+        input.zeroize();
+
+        Ok(mixer)
+    }
+
+    /// Get the number of inputs mixed in so far.
+    pub fn inputs(&self) -> u32 {
+        self.inputs
+    }
+
+    /// Finish the mix, expanding it to `LENGTH` bytes.
+    pub fn finish<const LENGTH: usize>(self) -> Entropy<LENGTH> {
+        let mut key: [u8; 32] = self.hasher.finalize().into();
+        let mut entropy = Entropy { bytes: [0u8; LENGTH] };
+
+        for (counter, chunk) in entropy.bytes.chunks_mut(32).enumerate() {
+            let mut hasher = Sha256::new();
+
+            hasher.update(EXPAND_DOMAIN);
+            hasher.update(key);
+            hasher.update((counter as u32).to_be_bytes());
+
+            let mut block: [u8; 32] = hasher.finalize().into();
+
+            chunk.copy_from_slice(&block[..chunk.len()]);
+
+            block.zeroize();
+        }
+
+        key.zeroize();
+
+        entropy
+    }
+}
+
+impl<const LENGTH: usize> Entropy<LENGTH> {
+    /// Mix additional input into this entropy, replacing it with a hash of both.
+    ///
+    /// NOTE: The result is never weaker than the original bytes, so untrusted or low-quality input is safe to add.
+    pub fn mix_in(&mut self, other: &[u8]) {
+        let mut mixed = Mixer::new(b"Entropy::mix_in").with_input(&self.bytes).with_input(other).finish::<LENGTH>();
+
+        self.bytes.copy_from_slice(&mixed.bytes);
+
+        mixed.bytes.zeroize();
+    }
+}
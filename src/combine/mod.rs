@@ -0,0 +1,11 @@
+//! Combining multiple independent entropy sources.
+
+#[cfg(feature = "alloc")]
+mod combiner;
+mod fallback;
+mod mixer;
+
+#[cfg(feature = "alloc")]
+pub use combiner::{CombineMode, Combiner, CombinerError};
+pub use fallback::{Fallback, FallbackError, FallbackPolicy};
+pub use mixer::Mixer;
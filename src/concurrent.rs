@@ -0,0 +1,310 @@
+//! Entropy pools shared by many threads.
+
+use core::cell::Cell;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::vec::Vec;
+
+use zeroize::Zeroize;
+
+use crate::assess::{EntropyAssessment, Provenance};
+use crate::buffered::Buffer;
+use crate::fork;
+use crate::trace;
+use crate::EntropySource;
+
+/// Default buffer size per shard.
+const DEFAULT_CAPACITY: usize = 4096;
+
+/// Most shards created by default, however many CPUs there are.
+const MAX_DEFAULT_SHARDS: usize = 64;
+
+/// Time after which the refill thread checks the shards even without being woken.
+const REFILL_POLL: Duration = Duration::from_millis(100);
+
+/// Source of per-thread shard indices.
+static NEXT_SHARD: AtomicUsize = AtomicUsize::new(0);
+
+std::thread_local! {
+    static SHARD: Cell<usize> = const { Cell::new(usize::MAX) };
+}
+
+/// This thread's preferred shard (threads are assigned shards round-robin on first use).
+fn home_shard() -> usize {
+    SHARD.with(|shard| match shard.get() {
+        usize::MAX => {
+            let index = NEXT_SHARD.fetch_add(1, Ordering::Relaxed);
+
+            shard.set(index);
+
+            index
+        }
+        index => index,
+    })
+}
+
+/// Configuration of a `SharedPool`.
+#[derive(Debug, Clone, Copy)]
+pub struct SharedPoolConfig {
+    shards: usize,
+    capacity: usize,
+    low_water: usize,
+}
+
+impl SharedPoolConfig {
+    /// One shard per CPU (at most 64), with 4 KiB per shard refilled in the background below a quarter full.
+    pub fn new() -> Self {
+        let shards = std::thread::available_parallelism().map_or(1, |n| n.get());
+
+        Self {
+            shards: shards.min(MAX_DEFAULT_SHARDS),
+            capacity: DEFAULT_CAPACITY,
+            low_water: DEFAULT_CAPACITY / 4,
+        }
+    }
+
+    /// Set the number of shards (minimum 1).
+    pub fn with_shards(mut self, shards: usize) -> Self {
+        self.shards = shards.max(1);
+        self
+    }
+
+    /// Set the buffer size per shard (minimum 1), keeping the low-water mark at a quarter of it.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self.low_water = self.capacity / 4;
+        self
+    }
+
+    /// Set the number of remaining bytes below which a shard is refilled in the background (at most the capacity).
+    pub fn with_low_water(mut self, low_water: usize) -> Self {
+        self.low_water = low_water.min(self.capacity);
+        self
+    }
+
+    /// Get the number of shards.
+    pub fn shards(&self) -> usize {
+        self.shards
+    }
+
+    /// Get the buffer size per shard.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get the number of remaining bytes below which a shard is refilled in the background.
+    pub fn low_water(&self) -> usize {
+        self.low_water
+    }
+}
+
+impl Default for SharedPoolConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A shard, padded to its own cache lines so readers on different shards never contend.
+#[repr(align(128))]
+struct Shard {
+    buffer: Mutex<Buffer>,
+    low: AtomicBool,
+}
+
+impl Shard {
+    fn lock(&self) -> MutexGuard<'_, Buffer> {
+        self.buffer.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+struct Shared<S: EntropySource> {
+    source: S,
+    shards: Vec<Shard>,
+    config: SharedPoolConfig,
+    stop: AtomicBool,
+    wake: (Mutex<()>, Condvar),
+}
+
+impl<S: EntropySource> Shared<S> {
+    /// Serve `output` from a locked shard, asking for a background refill once it runs low.
+    fn serve(&self, shard: &Shard, buffer: &mut Buffer, output: &mut [u8]) -> Result<(), S::EntropySourceError> {
+        let result = buffer.read(&self.source, output, self.config.capacity);
+
+        if buffer.remaining() < self.config.low_water && !shard.low.swap(true, Ordering::Relaxed) {
+            self.wake.1.notify_one();
+        }
+
+        result
+    }
+
+    /// Refill every shard that ran low, reading from the source without holding any shard's lock.
+    fn refill(&self) {
+        let mut fresh = Vec::new();
+
+        for shard in &self.shards {
+            if !shard.low.load(Ordering::Relaxed) {
+                continue;
+            }
+
+            let fork_epoch = fork::epoch();
+
+            fresh.resize(self.config.capacity, 0);
+
+            if let Err(_e) = self.source.read_bytes(&mut fresh) {
+                trace::event!(warn, error = ?_e, "shared pool refill failed");
+
+                fresh.zeroize();
+
+                // NOTE: Readers refill the shard inline (and see the error themselves) until the next attempt.
+                return;
+            }
+
+            shard.lock().replace(&mut fresh, fork_epoch);
+            shard.low.store(false, Ordering::Relaxed);
+        }
+    }
+
+    fn run(&self) {
+        while !self.stop.load(Ordering::Relaxed) {
+            self.refill();
+
+            let guard = self.wake.0.lock().unwrap_or_else(|e| e.into_inner());
+
+            if self.stop.load(Ordering::Relaxed) || self.shards.iter().any(|shard| shard.low.load(Ordering::Relaxed)) {
+                continue;
+            }
+
+            let _ = self.wake.1.wait_timeout(guard, REFILL_POLL);
+        }
+    }
+}
+
+/// A sharded entropy pool over a shared source, refilled by a background thread, for servers calling it from many
+/// threads at once.
+///
+/// Each thread prefers its own shard, taken with an uncontended `try_lock`; only if it is busy does a read move on to
+/// the next shard. The hot path is then a thread-local lookup, two atomic operations and a copy, and the source is
+/// only read by the refill thread (or inline, when a shard runs dry before it is refilled).
+///
+/// NOTE: Reads at least as large as a shard bypass the pool. In a child process after `fork`, the buffered bytes are
+/// discarded and, since the refill thread does not survive the fork, shards are refilled inline instead.
+pub struct SharedPool<S: EntropySource + Send + Sync + 'static> {
+    shared: Arc<Shared<S>>,
+    refiller: Option<JoinHandle<()>>,
+}
+
+impl<S: EntropySource + Send + Sync + 'static> SharedPool<S> {
+    /// Create a pool over `source` with the default configuration, and start its refill thread.
+    pub fn new(source: S) -> Self {
+        Self::with_config(source, SharedPoolConfig::new())
+    }
+
+    /// Create a pool over `source` with a custom configuration, and start its refill thread.
+    pub fn with_config(source: S, config: SharedPoolConfig) -> Self {
+        let shards = (0..config.shards)
+            .map(|_| Shard {
+                buffer: Mutex::new(Buffer::new()),
+                low: AtomicBool::new(true),
+            })
+            .collect();
+
+        let shared = Arc::new(Shared {
+            source,
+            shards,
+            config,
+            stop: AtomicBool::new(false),
+            wake: (Mutex::new(()), Condvar::new()),
+        });
+
+        let refilling = shared.clone();
+        let refiller = std::thread::spawn(move || refilling.run());
+
+        Self {
+            shared,
+            refiller: Some(refiller),
+        }
+    }
+
+    /// Get the configuration.
+    pub fn config(&self) -> SharedPoolConfig {
+        self.shared.config
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.shared.source
+    }
+
+    /// Run `f` with this thread's shard locked, or the first other shard that is free.
+    fn with_shard<R>(&self, f: impl FnOnce(&Shard, &mut Buffer) -> R) -> R {
+        let shards = self.shared.shards.len();
+        let home = home_shard() % shards;
+
+        for offset in 0..shards {
+            let shard = &self.shared.shards[(home + offset) % shards];
+
+            if let Ok(mut state) = shard.buffer.try_lock() {
+                return f(shard, &mut state);
+            }
+        }
+
+        let shard = &self.shared.shards[home];
+
+        f(shard, &mut shard.lock())
+    }
+}
+
+unsafe impl<S: EntropySource + Send + Sync + 'static> EntropySource for SharedPool<S> {
+    type EntropySourceError = S::EntropySourceError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        let shared = &*self.shared;
+
+        if buffer.len() >= shared.config.capacity {
+            return shared.source.read_bytes(buffer);
+        }
+
+        self.with_shard(|shard, state| shared.serve(shard, state, buffer))
+    }
+
+    fn read_bytes_vectored(&self, buffers: &mut [std::io::IoSliceMut<'_>]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffers.iter().map(|buffer| buffer.len()).sum());
+
+        let shared = &*self.shared;
+
+        self.with_shard(|shard, state| {
+            buffers
+                .iter_mut()
+                .try_for_each(|buffer| shared.serve(shard, state, buffer))
+        })
+    }
+}
+
+impl<S: EntropyAssessment + Send + Sync + 'static> EntropyAssessment for SharedPool<S> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.shared.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.shared.source.provenance()
+    }
+}
+
+impl<S: EntropySource + Send + Sync + 'static> Drop for SharedPool<S> {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+
+        // NOTE: Taking the lock ensures the refill thread is either waiting or has yet to check `stop`.
+        drop(self.shared.wake.0.lock());
+
+        self.shared.wake.1.notify_one();
+
+        if let Some(refiller) = self.refiller.take() {
+            let _ = refiller.join();
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Conditioning of raw noise sources.
+
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::Aes128;
+use sha2::{Digest, Sha256};
+use sha3::Sha3_256;
+use zeroize::Zeroize;
+
+use crate::assess::{self, Basis, EntropyAssessment, Provenance};
+use crate::trace;
+use crate::EntropySource;
+
+/// Default number of raw input bytes consumed per output byte.
+pub const DEFAULT_RATIO: usize = 2;
+
+/// A vetted conditioning function from SP 800-90B section 3.1.5.1.1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conditioner {
+    /// SHA-256, with 32 byte output blocks.
+    Sha256,
+    /// SHA3-256, with 32 byte output blocks.
+    Sha3_256,
+    /// AES-128 CBC-MAC under the given key, with 16 byte output blocks.
+    CbcMac([u8; 16]),
+}
+
+impl Conditioner {
+    /// Get the size of one output block.
+    pub const fn output_len(&self) -> usize {
+        match self {
+            Self::Sha256 | Self::Sha3_256 => 32,
+            Self::CbcMac(_) => 16,
+        }
+    }
+}
+
+// NOTE: Only ever lives on the stack for the duration of one output block.
+#[allow(clippy::large_enum_variant)]
+enum State {
+    Sha256(Sha256),
+    Sha3_256(Sha3_256),
+    CbcMac(Aes128, [u8; 16]),
+}
+
+impl State {
+    fn new(conditioner: &Conditioner) -> Self {
+        match conditioner {
+            Conditioner::Sha256 => Self::Sha256(Sha256::new()),
+            Conditioner::Sha3_256 => Self::Sha3_256(Sha3_256::new()),
+            Conditioner::CbcMac(key) => Self::CbcMac(Aes128::new(key.into()), [0u8; 16]),
+        }
+    }
+
+    /// Absorb one chunk of `output_len` raw bytes.
+    fn update(&mut self, chunk: &[u8]) {
+        match self {
+            Self::Sha256(hasher) => hasher.update(chunk),
+            Self::Sha3_256(hasher) => hasher.update(chunk),
+            Self::CbcMac(cipher, block) => {
+                block.iter_mut().zip(chunk).for_each(|(b, c)| *b ^= c);
+
+                cipher.encrypt_block(block.into());
+            }
+        }
+    }
+
+    fn finalize(self, output: &mut [u8]) {
+        match self {
+            Self::Sha256(hasher) => output.copy_from_slice(&hasher.finalize()[..output.len()]),
+            Self::Sha3_256(hasher) => output.copy_from_slice(&hasher.finalize()[..output.len()]),
+            Self::CbcMac(_, mut block) => {
+                output.copy_from_slice(&block[..output.len()]);
+
+                block.zeroize();
+            }
+        }
+    }
+}
+
+/// Passes the output of a raw noise source through a vetted conditioning function.
+///
+/// Every output block is computed from `ratio` times its size in fresh raw bytes, so a source with at least
+/// `8 / ratio` bits of min-entropy per byte yields conditioned output with (close to) full entropy.
+///
+/// NOTE: SP 800-90B only credits full entropy when the input carries at least 64 bits more entropy than the
+/// output, so pick a ratio with some margin over the assessed min-entropy of the source.
+pub struct Conditioned<S: EntropySource> {
+    source: S,
+    conditioner: Conditioner,
+    ratio: usize,
+}
+
+impl<S: EntropySource> Conditioned<S> {
+    /// Condition a source with SHA-256 and the default ratio.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            conditioner: Conditioner::Sha256,
+            ratio: DEFAULT_RATIO,
+        }
+    }
+
+    /// Use a different conditioning function.
+    pub fn with_conditioner(mut self, conditioner: Conditioner) -> Self {
+        self.conditioner = conditioner;
+        self
+    }
+
+    /// Set the number of raw input bytes consumed per output byte (at least 1).
+    pub fn with_ratio(mut self, ratio: usize) -> Self {
+        self.ratio = ratio.max(1);
+        self
+    }
+
+    /// Get the conditioning function.
+    pub fn conditioner(&self) -> Conditioner {
+        self.conditioner
+    }
+
+    /// Get the number of raw input bytes consumed per output byte.
+    pub fn ratio(&self) -> usize {
+        self.ratio
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySource for Conditioned<S> {
+    type EntropySourceError = S::EntropySourceError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        let block_len = self.conditioner.output_len();
+
+        let mut raw = [0u8; 32];
+
+        let result = buffer.chunks_mut(block_len).try_for_each(|output| {
+            let mut state = State::new(&self.conditioner);
+
+            for _ in 0..self.ratio {
+                self.source.read_bytes(&mut raw[..block_len])?;
+
+                state.update(&raw[..block_len]);
+            }
+
+            state.finalize(output);
+
+            Ok(())
+        });
+
+        raw.zeroize();
+
+        result
+    }
+}
+
+impl<S: EntropyAssessment> EntropyAssessment for Conditioned<S> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        let block_len = self.conditioner.output_len();
+        let input = self.source.min_entropy_per_byte() * (self.ratio * block_len) as f64;
+
+        assess::conditioned(input, block_len)
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            basis: Basis::Derived,
+            ..self.source.provenance()
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use zeroize::Zeroize;
+
+use crate::assess::{self, Basis, EntropyAssessment, Provenance};
+use crate::csprng::keystream::Keystream;
+use crate::csprng::CsprngError;
+use crate::fork;
+use crate::lock::Lock;
+use crate::reseed::Reseedable;
+use crate::selftest::{known_answer, KatSource, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::{EntropySource, EntropySourceMut};
+
+/// Length of the key and nonce drawn from the source on every (re)seed.
+const SEED_LEN: usize = 32 + 12;
+
+/// Default number of bytes served between reseeds.
+const DEFAULT_RESEED_THRESHOLD: u64 = 1 << 20;
+
+/// Maximum number of bytes served between reseeds (the ChaCha20 keystream limit for a single key/nonce).
+const MAX_RESEED_THRESHOLD: u64 = 1 << 38;
+
+/// Expected first 32 output bytes after instantiating from `KatSource`.
+const KAT_OUTPUT: [u8; 32] = [
+    0x93, 0xd2, 0x57, 0xe4, 0x19, 0xda, 0xfa, 0x5e, 0x36, 0xcb, 0x8f, 0xc0, 0xcf, 0x38, 0x9f, 0x90,
+    0x30, 0xaf, 0x89, 0x71, 0x23, 0xfa, 0x5f, 0xd5, 0x16, 0xa5, 0xc3, 0x26, 0xcb, 0x25, 0x9d, 0x0e,
+];
+
+struct ChaChaState {
+    keystream: Keystream,
+    served: u64,
+    fork_epoch: u64,
+}
+
+impl ChaChaState {
+    fn seed<S: EntropySource>(source: &S) -> Result<Self, CsprngError<S::EntropySourceError>> {
+        let mut seed = [0u8; SEED_LEN];
+
+        if let Err(e) = source.read_bytes(&mut seed) {
+            return Err(CsprngError::Source(e));
+        }
+
+        let state = Self::from_seed(&seed);
+
+        seed.zeroize();
+
+        Ok(state)
+    }
+
+    fn from_seed(seed: &[u8]) -> Self {
+        trace::event!(debug, generator = "ChaCha20", "seeded");
+
+        let keystream = Keystream::new(&seed[..32], &seed[32..SEED_LEN]);
+
+        Self {
+            keystream,
+            served: 0,
+            fork_epoch: fork::epoch(),
+        }
+    }
+}
+
+/// A ChaCha20 keystream generator seeded from an `EntropySource`.
+///
+/// Large reads are generated 8 blocks at a time with AVX2 when the CPU supports it (detected at runtime), and 4 at a
+/// time with NEON on `aarch64`.
+///
+/// NOTE: The generator transparently reseeds from the source after serving the reseed threshold (1 MiB by default),
+/// and in a child process after `fork`.
+pub struct ChaChaSource<S: EntropySource> {
+    source: S,
+    state: Lock<ChaChaState>,
+    reseed_threshold: u64,
+}
+
+impl<S: EntropySource> ChaChaSource<S> {
+    /// Attempt to seed the generator from the provided `EntropySource`.
+    pub fn new(source: S) -> Result<Self, CsprngError<S::EntropySourceError>> {
+        let state = ChaChaState::seed(&source)?;
+
+        Ok(Self {
+            source,
+            state: Lock::new(state),
+            reseed_threshold: DEFAULT_RESEED_THRESHOLD,
+        })
+    }
+
+    /// Set the number of bytes served between reseeds.
+    ///
+    /// NOTE: The threshold is clamped to `1..=2^38`, the keystream limit of a single ChaCha20 key and nonce.
+    pub fn with_reseed_threshold(mut self, reseed_threshold: u64) -> Self {
+        self.reseed_threshold = reseed_threshold.clamp(1, MAX_RESEED_THRESHOLD);
+        self
+    }
+
+    /// Attempt to reseed the generator from its `EntropySource`.
+    pub fn reseed(&self) -> Result<(), CsprngError<S::EntropySourceError>> {
+        let state = ChaChaState::seed(&self.source)?;
+
+        self.state.with(|current| *current = state);
+
+        Ok(())
+    }
+
+    /// Get a reference to the underlying `EntropySource`.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Fill `buffer` from `state`, reseeding whenever the threshold is reached or after a `fork`.
+    fn generate(
+        source: &S,
+        reseed_threshold: u64,
+        state: &mut ChaChaState,
+        buffer: &mut [u8],
+    ) -> Result<(), CsprngError<S::EntropySourceError>> {
+        let mut remaining = buffer;
+
+        while !remaining.is_empty() {
+            if state.served >= reseed_threshold || state.fork_epoch != fork::epoch() {
+                *state = ChaChaState::seed(source)?;
+            }
+
+            let available = (reseed_threshold - state.served).min(remaining.len() as u64) as usize;
+            let (chunk, rest) = core::mem::take(&mut remaining).split_at_mut(available);
+
+            state.keystream.fill(chunk);
+            state.served += available as u64;
+
+            remaining = rest;
+        }
+
+        Ok(())
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySource for ChaChaSource<S> {
+    type EntropySourceError = CsprngError<S::EntropySourceError>;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.state
+            .with(|state| Self::generate(&self.source, self.reseed_threshold, state, buffer))
+    }
+
+    #[cfg(feature = "std")]
+    fn read_bytes_vectored(&self, buffers: &mut [std::io::IoSliceMut<'_>]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffers.iter().map(|buffer| buffer.len()).sum());
+
+        self.state.with(|state| {
+            buffers
+                .iter_mut()
+                .try_for_each(|buffer| Self::generate(&self.source, self.reseed_threshold, state, buffer))
+        })
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySourceMut for ChaChaSource<S> {
+    type EntropySourceError = CsprngError<S::EntropySourceError>;
+
+    fn read_bytes_mut(&mut self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        Self::generate(&self.source, self.reseed_threshold, self.state.get_mut(), buffer)
+    }
+}
+
+/// NOTE: Credited with full entropy only if every reseed (44 bytes of the source) carries 256 bits.
+impl<S: EntropyAssessment> EntropyAssessment for ChaChaSource<S> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        assess::seeded(self.source.min_entropy_per_byte(), SEED_LEN)
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            basis: Basis::Derived,
+            ..self.source.provenance()
+        }
+    }
+}
+
+impl<S: EntropySource> SelfTest for ChaChaSource<S> {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        let error = SelfTestError::KnownAnswer("ChaCha20");
+        let generator = ChaChaSource::new(KatSource).map_err(|_| error)?;
+        let mut output = [0u8; 32];
+
+        generator.read_bytes(&mut output).map_err(|_| error)?;
+
+        known_answer("ChaCha20", &output, &KAT_OUTPUT)
+    }
+}
+
+impl<S: EntropySource> Reseedable for ChaChaSource<S> {
+    const SEED_LEN: usize = SEED_LEN;
+
+    /// NOTE: This replaces the key and nonce with `entropy` and panics if it is not exactly 44 bytes.
+    fn reseed_with(&self, entropy: &[u8]) {
+        let state = ChaChaState::from_seed(entropy);
+
+        self.state.with(|current| *current = state);
+    }
+}
@@ -0,0 +1,262 @@
+use zeroize::Zeroize;
+
+use crate::assess::{self, Basis, EntropyAssessment, Provenance};
+use crate::csprng::keystream::Keystream;
+use crate::csprng::CsprngError;
+use crate::fork;
+use crate::lock::Lock;
+use crate::reseed::Reseedable;
+use crate::selftest::{known_answer, KatSource, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::{EntropySource, EntropySourceMut};
+
+/// Length of a ChaCha20 key, which is all that is drawn from the source on every (re)seed.
+const KEY_LEN: usize = 32;
+
+/// Keystream generated per refill of the buffer: the next key, then the bytes served from the buffer.
+const BATCH_LEN: usize = 768;
+
+/// Bytes generated under one key for requests served directly rather than through the buffer.
+const MAX_DIRECT: usize = 1 << 20;
+
+/// Default number of bytes served between reseeds.
+const DEFAULT_RESEED_THRESHOLD: u64 = 1 << 20;
+
+/// Every key generates a single keystream, so the nonce never needs to change.
+const NONCE: [u8; 12] = [0; 12];
+
+/// Expected first 32 output bytes after instantiating from `KatSource`.
+const KAT_OUTPUT: [u8; 32] = [
+    0x2b, 0x23, 0xcc, 0xe7, 0xa2, 0x60, 0x23, 0xab, 0x3f, 0x0e, 0xef, 0x69, 0x3a, 0xc8, 0x7f, 0x64,
+    0x25, 0x82, 0x35, 0xea, 0xb1, 0xf7, 0xa3, 0x2d, 0xc2, 0x27, 0x62, 0xa0, 0x48, 0x5b, 0x41, 0x0c,
+];
+
+struct FkeState {
+    key: [u8; KEY_LEN],
+    buffer: [u8; BATCH_LEN - KEY_LEN],
+    offset: usize,
+    served: u64,
+    fork_epoch: u64,
+}
+
+impl FkeState {
+    fn seed<S: EntropySource>(source: &S) -> Result<Self, CsprngError<S::EntropySourceError>> {
+        let mut key = [0u8; KEY_LEN];
+
+        if let Err(e) = source.read_bytes(&mut key) {
+            return Err(CsprngError::Source(e));
+        }
+
+        let state = Self::from_key(&key);
+
+        key.zeroize();
+
+        Ok(state)
+    }
+
+    fn from_key(key: &[u8]) -> Self {
+        trace::event!(debug, generator = "FastKeyErasure", "seeded");
+
+        let mut state = Self {
+            key: [0u8; KEY_LEN],
+            buffer: [0u8; BATCH_LEN - KEY_LEN],
+            offset: BATCH_LEN - KEY_LEN,
+            served: 0,
+            fork_epoch: fork::epoch(),
+        };
+
+        state.key.copy_from_slice(key);
+
+        state
+    }
+
+    /// Generate `output` under the current key, replacing the key with the first block of its keystream beforehand.
+    fn expand(&mut self, output: &mut [u8]) {
+        let mut keystream = Keystream::new(&self.key, &NONCE);
+
+        keystream.fill(&mut self.key);
+        keystream.fill(output);
+    }
+
+    fn fill(&mut self, output: &mut [u8]) {
+        let mut remaining = output;
+
+        while !remaining.is_empty() {
+            if self.offset == self.buffer.len() && remaining.len() >= self.buffer.len() {
+                let length = remaining.len().min(MAX_DIRECT);
+                let (chunk, rest) = core::mem::take(&mut remaining).split_at_mut(length);
+
+                self.expand(chunk);
+
+                remaining = rest;
+
+                continue;
+            }
+
+            if self.offset == self.buffer.len() {
+                let mut buffer = [0u8; BATCH_LEN - KEY_LEN];
+
+                self.expand(&mut buffer);
+
+                self.buffer = buffer;
+                self.offset = 0;
+
+                buffer.zeroize();
+            }
+
+            let length = (self.buffer.len() - self.offset).min(remaining.len());
+            let (chunk, rest) = core::mem::take(&mut remaining).split_at_mut(length);
+            let served = &mut self.buffer[self.offset..self.offset + length];
+
+            chunk.copy_from_slice(served);
+
+            // NOTE: Served bytes are wiped immediately, so a later memory disclosure reveals nothing already returned.
+            served.zeroize();
+
+            self.offset += length;
+
+            remaining = rest;
+        }
+    }
+}
+
+impl Drop for FkeState {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.buffer.zeroize();
+    }
+}
+
+/// A fast-key-erasure generator (Bernstein) over ChaCha20, seeded from an `EntropySource`.
+///
+/// Every batch of keystream starts with the key for the next batch, which replaces the current key before any output
+/// is served, and buffered output is wiped as soon as it is handed out. An attacker who reads the generator's memory
+/// therefore learns nothing about output it has already produced (forward secrecy).
+///
+/// NOTE: The generator reseeds from the source after serving the reseed threshold (1 MiB by default), and in a child
+/// process after `fork`. Unlike `ChaChaSource`, only a 32-byte key is drawn per reseed.
+pub struct FastKeyErasure<S: EntropySource> {
+    source: S,
+    state: Lock<FkeState>,
+    reseed_threshold: u64,
+}
+
+impl<S: EntropySource> FastKeyErasure<S> {
+    /// Attempt to seed the generator from the provided `EntropySource`.
+    pub fn new(source: S) -> Result<Self, CsprngError<S::EntropySourceError>> {
+        let state = FkeState::seed(&source)?;
+
+        Ok(Self {
+            source,
+            state: Lock::new(state),
+            reseed_threshold: DEFAULT_RESEED_THRESHOLD,
+        })
+    }
+
+    /// Set the number of bytes served between reseeds (at least 1).
+    ///
+    /// NOTE: Since the key is replaced on every batch, this only bounds how long a compromised state predicts future
+    /// output; it is not a keystream limit.
+    pub fn with_reseed_threshold(mut self, reseed_threshold: u64) -> Self {
+        self.reseed_threshold = reseed_threshold.max(1);
+        self
+    }
+
+    /// Attempt to reseed the generator from its `EntropySource`.
+    pub fn reseed(&self) -> Result<(), CsprngError<S::EntropySourceError>> {
+        let state = FkeState::seed(&self.source)?;
+
+        self.state.with(|current| *current = state);
+
+        Ok(())
+    }
+
+    /// Get a reference to the underlying `EntropySource`.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Fill `buffer` from `state`, reseeding whenever the threshold is reached or after a `fork`.
+    fn generate(
+        source: &S,
+        reseed_threshold: u64,
+        state: &mut FkeState,
+        buffer: &mut [u8],
+    ) -> Result<(), CsprngError<S::EntropySourceError>> {
+        let mut remaining = buffer;
+
+        while !remaining.is_empty() {
+            if state.served >= reseed_threshold || state.fork_epoch != fork::epoch() {
+                *state = FkeState::seed(source)?;
+            }
+
+            let available = (reseed_threshold - state.served).min(remaining.len() as u64) as usize;
+            let (chunk, rest) = core::mem::take(&mut remaining).split_at_mut(available);
+
+            state.fill(chunk);
+            state.served += available as u64;
+
+            remaining = rest;
+        }
+
+        Ok(())
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySource for FastKeyErasure<S> {
+    type EntropySourceError = CsprngError<S::EntropySourceError>;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.state
+            .with(|state| Self::generate(&self.source, self.reseed_threshold, state, buffer))
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySourceMut for FastKeyErasure<S> {
+    type EntropySourceError = CsprngError<S::EntropySourceError>;
+
+    fn read_bytes_mut(&mut self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        Self::generate(&self.source, self.reseed_threshold, self.state.get_mut(), buffer)
+    }
+}
+
+/// NOTE: Credited with full entropy only if every reseed (a 32-byte key from the source) carries 256 bits.
+impl<S: EntropyAssessment> EntropyAssessment for FastKeyErasure<S> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        assess::seeded(self.source.min_entropy_per_byte(), KEY_LEN)
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            basis: Basis::Derived,
+            ..self.source.provenance()
+        }
+    }
+}
+
+impl<S: EntropySource> SelfTest for FastKeyErasure<S> {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        let error = SelfTestError::KnownAnswer("FastKeyErasure");
+        let generator = FastKeyErasure::new(KatSource).map_err(|_| error)?;
+        let mut output = [0u8; 32];
+
+        generator.read_bytes(&mut output).map_err(|_| error)?;
+
+        known_answer("FastKeyErasure", &output, &KAT_OUTPUT)
+    }
+}
+
+impl<S: EntropySource> Reseedable for FastKeyErasure<S> {
+    const SEED_LEN: usize = KEY_LEN;
+
+    /// NOTE: This replaces the key with `entropy` and panics if it is not exactly 32 bytes.
+    fn reseed_with(&self, entropy: &[u8]) {
+        let state = FkeState::from_key(entropy);
+
+        self.state.with(|current| *current = state);
+    }
+}
@@ -0,0 +1,375 @@
+use zeroize::Zeroize;
+
+/// Size of a ChaCha20 block.
+const BLOCK: usize = 64;
+
+/// `"expand 32-byte k"`.
+const CONSTANTS: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+/// A ChaCha20 (RFC 8439) keystream, generated several blocks at a time with AVX2 (detected at runtime) or NEON where
+/// available.
+///
+/// NOTE: The 32-bit block counter wraps after 256 GiB, which callers must never reach with one key and nonce.
+pub(crate) struct Keystream {
+    state: [u32; 16],
+    leftover: [u8; BLOCK],
+    offset: usize,
+}
+
+impl Keystream {
+    /// Start the keystream for a 32-byte key and a 12-byte nonce at block 0.
+    pub(crate) fn new(key: &[u8], nonce: &[u8]) -> Self {
+        let mut state = [0u32; 16];
+
+        state[..4].copy_from_slice(&CONSTANTS);
+
+        for (word, bytes) in state[4..12].iter_mut().zip(key.chunks_exact(4)) {
+            *word = u32::from_le_bytes(bytes.try_into().unwrap());
+        }
+
+        for (word, bytes) in state[13..].iter_mut().zip(nonce.chunks_exact(4)) {
+            *word = u32::from_le_bytes(bytes.try_into().unwrap());
+        }
+
+        Self {
+            state,
+            leftover: [0; BLOCK],
+            offset: BLOCK,
+        }
+    }
+
+    /// Overwrite `output` with the next bytes of the keystream.
+    pub(crate) fn fill(&mut self, output: &mut [u8]) {
+        let buffered = (BLOCK - self.offset).min(output.len());
+
+        output[..buffered].copy_from_slice(&self.leftover[self.offset..self.offset + buffered]);
+
+        self.leftover[self.offset..self.offset + buffered].zeroize();
+        self.offset += buffered;
+
+        // NOTE: Only the SIMD backends advance `output`, and none may be compiled for the target.
+        #[allow(unused_mut)]
+        let mut output = &mut output[buffered..];
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if output.len() >= avx2::BLOCKS * BLOCK && avx2::detected() {
+            while output.len() >= avx2::BLOCKS * BLOCK {
+                let (blocks, rest) = core::mem::take(&mut output).split_at_mut(avx2::BLOCKS * BLOCK);
+
+                // SAFETY: AVX2 support was detected above.
+                unsafe { avx2::blocks(&self.state, blocks) };
+
+                self.state[12] = self.state[12].wrapping_add(avx2::BLOCKS as u32);
+
+                output = rest;
+            }
+        }
+
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon", target_endian = "little"))]
+        while output.len() >= neon::BLOCKS * BLOCK {
+            let (blocks, rest) = core::mem::take(&mut output).split_at_mut(neon::BLOCKS * BLOCK);
+
+            // SAFETY: NEON is enabled at compile time (it is part of the baseline of every `aarch64` target).
+            unsafe { neon::blocks(&self.state, blocks) };
+
+            self.state[12] = self.state[12].wrapping_add(neon::BLOCKS as u32);
+
+            output = rest;
+        }
+
+        let mut blocks = output.chunks_exact_mut(BLOCK);
+
+        for block in &mut blocks {
+            self.block(block);
+        }
+
+        let tail = blocks.into_remainder();
+
+        if !tail.is_empty() {
+            let mut leftover = [0u8; BLOCK];
+
+            self.block(&mut leftover);
+
+            self.leftover = leftover;
+            self.offset = tail.len();
+
+            tail.copy_from_slice(&self.leftover[..tail.len()]);
+
+            self.leftover[..tail.len()].zeroize();
+            leftover.zeroize();
+        }
+    }
+
+    /// Write the next block with the portable implementation.
+    fn block(&mut self, output: &mut [u8]) {
+        let mut x = self.state;
+
+        for _ in 0..10 {
+            quarter_round(&mut x, 0, 4, 8, 12);
+            quarter_round(&mut x, 1, 5, 9, 13);
+            quarter_round(&mut x, 2, 6, 10, 14);
+            quarter_round(&mut x, 3, 7, 11, 15);
+            quarter_round(&mut x, 0, 5, 10, 15);
+            quarter_round(&mut x, 1, 6, 11, 12);
+            quarter_round(&mut x, 2, 7, 8, 13);
+            quarter_round(&mut x, 3, 4, 9, 14);
+        }
+
+        for ((bytes, word), initial) in output.chunks_exact_mut(4).zip(x.iter()).zip(self.state.iter()) {
+            bytes.copy_from_slice(&word.wrapping_add(*initial).to_le_bytes());
+        }
+
+        x.zeroize();
+
+        self.state[12] = self.state[12].wrapping_add(1);
+    }
+}
+
+impl Drop for Keystream {
+    fn drop(&mut self) {
+        self.state.zeroize();
+        self.leftover.zeroize();
+    }
+}
+
+fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    x[a] = x[a].wrapping_add(x[b]);
+    x[d] = (x[d] ^ x[a]).rotate_left(16);
+    x[c] = x[c].wrapping_add(x[d]);
+    x[b] = (x[b] ^ x[c]).rotate_left(12);
+    x[a] = x[a].wrapping_add(x[b]);
+    x[d] = (x[d] ^ x[a]).rotate_left(8);
+    x[c] = x[c].wrapping_add(x[d]);
+    x[b] = (x[b] ^ x[c]).rotate_left(7);
+}
+
+/// Eight blocks at a time, one state word of every block per 256-bit register.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod avx2 {
+    use core::sync::atomic::{AtomicU8, Ordering};
+
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86 as arch;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64 as arch;
+
+    use arch::__m256i;
+
+    pub(super) const BLOCKS: usize = 8;
+
+    /// Detection result: 0 if not yet detected, 1 if unsupported, 2 if supported.
+    static DETECTED: AtomicU8 = AtomicU8::new(0);
+
+    /// Whether the CPU supports AVX2 and the OS saves the YMM registers.
+    pub(super) fn detected() -> bool {
+        match DETECTED.load(Ordering::Relaxed) {
+            0 => {
+                let supported = detect();
+
+                DETECTED.store(if supported { 2 } else { 1 }, Ordering::Relaxed);
+
+                supported
+            }
+            state => state == 2,
+        }
+    }
+
+    // NOTE: `__cpuid` only became a safe function in newer toolchains, so the `unsafe` blocks are kept for older ones.
+    #[allow(unused_unsafe)]
+    fn detect() -> bool {
+        // SAFETY: `cpuid` is available on every CPU capable of running Rust's x86 targets.
+        let max_leaf = unsafe { arch::__cpuid(0) }.eax;
+
+        // SAFETY: As above.
+        let features = unsafe { arch::__cpuid(1) }.ecx;
+
+        let osxsave = features & (1 << 27) != 0;
+        let avx = features & (1 << 28) != 0;
+
+        if max_leaf < 7 || !osxsave || !avx {
+            return false;
+        }
+
+        // SAFETY: `OSXSAVE` is set, so `xgetbv` is enabled.
+        let xcr0 = unsafe { xgetbv() };
+
+        // SAFETY: As for leaf 1, and leaf 7 is within the supported range.
+        let avx2 = unsafe { arch::__cpuid_count(7, 0) }.ebx & (1 << 5) != 0;
+
+        xcr0 & 0b110 == 0b110 && avx2
+    }
+
+    #[target_feature(enable = "xsave")]
+    unsafe fn xgetbv() -> u64 {
+        arch::_xgetbv(0)
+    }
+
+    #[inline(always)]
+    unsafe fn rotate<const LEFT: i32, const RIGHT: i32>(x: __m256i) -> __m256i {
+        arch::_mm256_or_si256(arch::_mm256_slli_epi32::<LEFT>(x), arch::_mm256_srli_epi32::<RIGHT>(x))
+    }
+
+    #[inline(always)]
+    unsafe fn quarter_round(x: &mut [__m256i; 16], a: usize, b: usize, c: usize, d: usize, r16: __m256i, r8: __m256i) {
+        x[a] = arch::_mm256_add_epi32(x[a], x[b]);
+        x[d] = arch::_mm256_shuffle_epi8(arch::_mm256_xor_si256(x[d], x[a]), r16);
+        x[c] = arch::_mm256_add_epi32(x[c], x[d]);
+        x[b] = rotate::<12, 20>(arch::_mm256_xor_si256(x[b], x[c]));
+        x[a] = arch::_mm256_add_epi32(x[a], x[b]);
+        x[d] = arch::_mm256_shuffle_epi8(arch::_mm256_xor_si256(x[d], x[a]), r8);
+        x[c] = arch::_mm256_add_epi32(x[c], x[d]);
+        x[b] = rotate::<7, 25>(arch::_mm256_xor_si256(x[b], x[c]));
+    }
+
+    /// Transpose eight vectors of one word per block into eight rows of eight consecutive words per block, and store
+    /// them at `offset` within each block of `output`.
+    #[inline(always)]
+    unsafe fn store(words: &[__m256i], output: &mut [u8], offset: usize) {
+        let t0 = arch::_mm256_unpacklo_epi32(words[0], words[1]);
+        let t1 = arch::_mm256_unpackhi_epi32(words[0], words[1]);
+        let t2 = arch::_mm256_unpacklo_epi32(words[2], words[3]);
+        let t3 = arch::_mm256_unpackhi_epi32(words[2], words[3]);
+        let t4 = arch::_mm256_unpacklo_epi32(words[4], words[5]);
+        let t5 = arch::_mm256_unpackhi_epi32(words[4], words[5]);
+        let t6 = arch::_mm256_unpacklo_epi32(words[6], words[7]);
+        let t7 = arch::_mm256_unpackhi_epi32(words[6], words[7]);
+
+        // NOTE: The low 128-bit lane of `u[i]` holds block `i`, the high lane block `i + 4`.
+        let low = [
+            arch::_mm256_unpacklo_epi64(t0, t2),
+            arch::_mm256_unpackhi_epi64(t0, t2),
+            arch::_mm256_unpacklo_epi64(t1, t3),
+            arch::_mm256_unpackhi_epi64(t1, t3),
+        ];
+
+        let high = [
+            arch::_mm256_unpacklo_epi64(t4, t6),
+            arch::_mm256_unpackhi_epi64(t4, t6),
+            arch::_mm256_unpacklo_epi64(t5, t7),
+            arch::_mm256_unpackhi_epi64(t5, t7),
+        ];
+
+        for block in 0..4 {
+            let first = arch::_mm256_permute2x128_si256(low[block], high[block], 0x20);
+            let second = arch::_mm256_permute2x128_si256(low[block], high[block], 0x31);
+
+            let first_at = output[block * 64 + offset..].as_mut_ptr().cast::<__m256i>();
+            let second_at = output[(block + 4) * 64 + offset..].as_mut_ptr().cast::<__m256i>();
+
+            arch::_mm256_storeu_si256(first_at, first);
+            arch::_mm256_storeu_si256(second_at, second);
+        }
+    }
+
+    /// Write eight consecutive blocks starting at the state's counter to `output` (`BLOCKS * 64` bytes).
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn blocks(state: &[u32; 16], output: &mut [u8]) {
+        let r16 = arch::_mm256_set_epi8(
+            13, 12, 15, 14, 9, 8, 11, 10, 5, 4, 7, 6, 1, 0, 3, 2, 13, 12, 15, 14, 9, 8, 11, 10, 5, 4, 7, 6, 1, 0, 3, 2,
+        );
+        let r8 = arch::_mm256_set_epi8(
+            14, 13, 12, 15, 10, 9, 8, 11, 6, 5, 4, 7, 2, 1, 0, 3, 14, 13, 12, 15, 10, 9, 8, 11, 6, 5, 4, 7, 2, 1, 0, 3,
+        );
+
+        let mut initial = [arch::_mm256_setzero_si256(); 16];
+
+        for (vector, word) in initial.iter_mut().zip(state.iter()) {
+            *vector = arch::_mm256_set1_epi32(*word as i32);
+        }
+
+        initial[12] = arch::_mm256_add_epi32(initial[12], arch::_mm256_set_epi32(7, 6, 5, 4, 3, 2, 1, 0));
+
+        let mut x = initial;
+
+        for _ in 0..10 {
+            quarter_round(&mut x, 0, 4, 8, 12, r16, r8);
+            quarter_round(&mut x, 1, 5, 9, 13, r16, r8);
+            quarter_round(&mut x, 2, 6, 10, 14, r16, r8);
+            quarter_round(&mut x, 3, 7, 11, 15, r16, r8);
+            quarter_round(&mut x, 0, 5, 10, 15, r16, r8);
+            quarter_round(&mut x, 1, 6, 11, 12, r16, r8);
+            quarter_round(&mut x, 2, 7, 8, 13, r16, r8);
+            quarter_round(&mut x, 3, 4, 9, 14, r16, r8);
+        }
+
+        for (vector, initial) in x.iter_mut().zip(initial.iter()) {
+            *vector = arch::_mm256_add_epi32(*vector, *initial);
+        }
+
+        store(&x[..8], output, 0);
+        store(&x[8..], output, 32);
+    }
+}
+
+/// Four blocks at a time, one state word of every block per 128-bit register.
+#[cfg(all(target_arch = "aarch64", target_feature = "neon", target_endian = "little"))]
+mod neon {
+    use core::arch::aarch64::*;
+
+    pub(super) const BLOCKS: usize = 4;
+
+    #[inline(always)]
+    unsafe fn rotate<const LEFT: i32, const RIGHT: i32>(x: uint32x4_t) -> uint32x4_t {
+        vsliq_n_u32::<LEFT>(vshrq_n_u32::<RIGHT>(x), x)
+    }
+
+    #[inline(always)]
+    unsafe fn quarter_round(x: &mut [uint32x4_t; 16], a: usize, b: usize, c: usize, d: usize) {
+        x[a] = vaddq_u32(x[a], x[b]);
+        x[d] = vreinterpretq_u32_u16(vrev32q_u16(vreinterpretq_u16_u32(veorq_u32(x[d], x[a]))));
+        x[c] = vaddq_u32(x[c], x[d]);
+        x[b] = rotate::<12, 20>(veorq_u32(x[b], x[c]));
+        x[a] = vaddq_u32(x[a], x[b]);
+        x[d] = rotate::<8, 24>(veorq_u32(x[d], x[a]));
+        x[c] = vaddq_u32(x[c], x[d]);
+        x[b] = rotate::<7, 25>(veorq_u32(x[b], x[c]));
+    }
+
+    /// Write four consecutive blocks starting at the state's counter to `output` (`BLOCKS * 64` bytes).
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn blocks(state: &[u32; 16], output: &mut [u8]) {
+        let mut initial = [vdupq_n_u32(0); 16];
+
+        for (vector, word) in initial.iter_mut().zip(state.iter()) {
+            *vector = vdupq_n_u32(*word);
+        }
+
+        let lanes = [0u32, 1, 2, 3];
+
+        initial[12] = vaddq_u32(initial[12], vld1q_u32(lanes.as_ptr()));
+
+        let mut x = initial;
+
+        for _ in 0..10 {
+            quarter_round(&mut x, 0, 4, 8, 12);
+            quarter_round(&mut x, 1, 5, 9, 13);
+            quarter_round(&mut x, 2, 6, 10, 14);
+            quarter_round(&mut x, 3, 7, 11, 15);
+            quarter_round(&mut x, 0, 5, 10, 15);
+            quarter_round(&mut x, 1, 6, 11, 12);
+            quarter_round(&mut x, 2, 7, 8, 13);
+            quarter_round(&mut x, 3, 4, 9, 14);
+        }
+
+        for (vector, initial) in x.iter_mut().zip(initial.iter()) {
+            *vector = vaddq_u32(*vector, *initial);
+        }
+
+        // NOTE: Each group of four words is a 4x4 transpose: `ab.0 = [a0 b0 a2 b2]`, `ab.1 = [a1 b1 a3 b3]`.
+        for group in 0..4 {
+            let ab = vtrnq_u32(x[group * 4], x[group * 4 + 1]);
+            let cd = vtrnq_u32(x[group * 4 + 2], x[group * 4 + 3]);
+
+            let rows = [
+                vcombine_u32(vget_low_u32(ab.0), vget_low_u32(cd.0)),
+                vcombine_u32(vget_low_u32(ab.1), vget_low_u32(cd.1)),
+                vcombine_u32(vget_high_u32(ab.0), vget_high_u32(cd.0)),
+                vcombine_u32(vget_high_u32(ab.1), vget_high_u32(cd.1)),
+            ];
+
+            for (block, row) in rows.iter().enumerate() {
+                vst1q_u8(output[block * 64 + group * 16..].as_mut_ptr(), vreinterpretq_u8_u32(*row));
+            }
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Userspace CSPRNGs seeded from an `EntropySource`.
+
+use core::error::Error;
+
+use crate::error::{Classify, ErrorKind};
+
+mod chacha;
+mod fke;
+mod keystream;
+
+pub use chacha::ChaChaSource;
+pub use fke::FastKeyErasure;
+
+#[derive(Debug)]
+pub enum CsprngError<E: Error> {
+    /// The entropy source failed while (re)seeding the generator.
+    Source(E),
+}
+
+impl<E: Error> core::fmt::Display for CsprngError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<E: Error> Error for CsprngError<E> {}
+
+impl<E: Error + Classify> Classify for CsprngError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Source(e) => e.kind(),
+        }
+    }
+}
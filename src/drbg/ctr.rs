@@ -0,0 +1,404 @@
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::Aes256;
+use zeroize::Zeroize;
+
+use crate::assess::{self, Basis, EntropyAssessment, Provenance};
+use crate::drbg::{Drbg, DrbgError, MAX_REQUEST, MAX_RESEED_INTERVAL};
+use crate::fork;
+use crate::lock::Lock;
+use crate::reseed::Reseedable;
+use crate::selftest::{known_answer, KatSource, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::{EntropySource, EntropySourceMut};
+
+/// Key length (and security strength) of AES-256 in bytes.
+const KEYLEN: usize = 32;
+
+/// Block length of AES in bytes.
+const BLOCKLEN: usize = 16;
+
+/// Seed length (`KEYLEN + BLOCKLEN`) in bytes.
+const SEEDLEN: usize = KEYLEN + BLOCKLEN;
+
+/// Length of the nonce drawn alongside the entropy input at instantiation.
+const NONCE_LEN: usize = KEYLEN / 2;
+
+/// Expected first 32 output bytes after instantiating from `KatSource`.
+const KAT_OUTPUT: [u8; 32] = [
+    0x7a, 0xd7, 0xf0, 0x61, 0x2b, 0x3e, 0xef, 0x3e, 0x51, 0xf8, 0xb3, 0x51, 0x7d, 0xec, 0xa5, 0x8d,
+    0xf1, 0xdb, 0xb9, 0x77, 0x83, 0xe8, 0xb2, 0x93, 0x03, 0x34, 0xc5, 0xc7, 0x6c, 0xd7, 0x16, 0x12,
+];
+
+fn encrypt(cipher: &Aes256, block: &mut [u8; BLOCKLEN]) {
+    cipher.encrypt_block(block.into());
+}
+
+/// Streaming BCC (CBC-MAC) used by the derivation function.
+struct Bcc<'a> {
+    cipher: &'a Aes256,
+    chaining_value: [u8; BLOCKLEN],
+    position: usize,
+}
+
+impl<'a> Bcc<'a> {
+    fn new(cipher: &'a Aes256) -> Self {
+        Self {
+            cipher,
+            chaining_value: [0u8; BLOCKLEN],
+            position: 0,
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.chaining_value[self.position] ^= byte;
+            self.position += 1;
+
+            if self.position == BLOCKLEN {
+                encrypt(self.cipher, &mut self.chaining_value);
+                self.position = 0;
+            }
+        }
+    }
+
+    /// Pad the input with `0x80` followed by zeros to a block boundary.
+    fn finalize(mut self) -> [u8; BLOCKLEN] {
+        self.update(&[0x80]);
+
+        while self.position != 0 {
+            self.update(&[0x00]);
+        }
+
+        self.chaining_value
+    }
+}
+
+/// The Block_Cipher_df derivation function, returning `SEEDLEN` bytes.
+fn derive(input: &[&[u8]]) -> [u8; SEEDLEN] {
+    let length = input.iter().map(|data| data.len()).sum::<usize>() as u32;
+
+    let mut key = [0u8; KEYLEN];
+
+    for (i, byte) in key.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+
+    let initial = Aes256::new(&key.into());
+    let mut temp = [0u8; SEEDLEN];
+
+    for (i, block) in temp.chunks_mut(BLOCKLEN).enumerate() {
+        let mut bcc = Bcc::new(&initial);
+
+        let mut iv = [0u8; BLOCKLEN];
+        iv[..4].copy_from_slice(&(i as u32).to_be_bytes());
+
+        bcc.update(&iv);
+        bcc.update(&length.to_be_bytes());
+        bcc.update(&(SEEDLEN as u32).to_be_bytes());
+
+        for data in input {
+            bcc.update(data);
+        }
+
+        block.copy_from_slice(&bcc.finalize());
+    }
+
+    let cipher = Aes256::new_from_slice(&temp[..KEYLEN]).unwrap();
+
+    let mut x = [0u8; BLOCKLEN];
+    x.copy_from_slice(&temp[KEYLEN..]);
+
+    for block in temp.chunks_mut(BLOCKLEN) {
+        encrypt(&cipher, &mut x);
+        block.copy_from_slice(&x);
+    }
+
+    key.zeroize();
+    x.zeroize();
+
+    temp
+}
+
+struct CtrDrbgState {
+    key: [u8; KEYLEN],
+    value: [u8; BLOCKLEN],
+    reseed_counter: u64,
+    fork_epoch: u64,
+}
+
+impl CtrDrbgState {
+    fn new(seed_material: &[&[u8]]) -> Self {
+        let mut state = Self {
+            key: [0u8; KEYLEN],
+            value: [0u8; BLOCKLEN],
+            reseed_counter: 1,
+            fork_epoch: fork::epoch(),
+        };
+
+        let mut seed = derive(seed_material);
+
+        state.update(&seed);
+
+        seed.zeroize();
+
+        state
+    }
+
+    fn increment(&mut self) {
+        let value = u128::from_be_bytes(self.value).wrapping_add(1);
+
+        self.value = value.to_be_bytes();
+    }
+
+    /// The CTR_DRBG update function.
+    fn update(&mut self, provided_data: &[u8; SEEDLEN]) {
+        let cipher = Aes256::new(&self.key.into());
+        let mut temp = [0u8; SEEDLEN];
+
+        for block in temp.chunks_mut(BLOCKLEN) {
+            self.increment();
+
+            let mut output = self.value;
+
+            encrypt(&cipher, &mut output);
+
+            block.copy_from_slice(&output);
+        }
+
+        for (t, p) in temp.iter_mut().zip(provided_data) {
+            *t ^= p;
+        }
+
+        self.key.copy_from_slice(&temp[..KEYLEN]);
+        self.value.copy_from_slice(&temp[KEYLEN..]);
+
+        temp.zeroize();
+    }
+
+    fn reseed(&mut self, seed_material: &[&[u8]]) {
+        let mut seed = derive(seed_material);
+
+        self.update(&seed);
+        self.reseed_counter = 1;
+        self.fork_epoch = fork::epoch();
+
+        seed.zeroize();
+
+        trace::event!(debug, generator = "CTR_DRBG", "reseeded");
+    }
+
+    /// NOTE: `output` must not exceed `MAX_REQUEST` bytes.
+    fn generate(&mut self, output: &mut [u8], additional_input: &[&[u8]]) {
+        let mut additional = [0u8; SEEDLEN];
+
+        if additional_input.iter().any(|data| !data.is_empty()) {
+            additional = derive(additional_input);
+
+            self.update(&additional);
+        }
+
+        let cipher = Aes256::new(&self.key.into());
+
+        for chunk in output.chunks_mut(BLOCKLEN) {
+            self.increment();
+
+            let mut block = self.value;
+
+            encrypt(&cipher, &mut block);
+
+            chunk.copy_from_slice(&block[..chunk.len()]);
+
+            block.zeroize();
+        }
+
+        self.update(&additional);
+        self.reseed_counter += 1;
+
+        additional.zeroize();
+    }
+}
+
+impl Drop for CtrDrbgState {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.value.zeroize();
+    }
+}
+
+/// CTR_DRBG (SP 800-90A) using AES-256 with the derivation function, seeded from an `EntropySource`.
+///
+/// NOTE: The generator reseeds itself from the source once the reseed interval is reached.
+pub struct CtrDrbg<S: EntropySource> {
+    source: S,
+    state: Lock<CtrDrbgState>,
+    reseed_interval: u64,
+    prediction_resistance: bool,
+}
+
+impl<S: EntropySource> CtrDrbg<S> {
+    fn reseed_state(
+        source: &S,
+        state: &mut CtrDrbgState,
+        additional_input: &[u8],
+    ) -> Result<(), DrbgError<S::EntropySourceError>> {
+        let mut entropy_input = [0u8; KEYLEN];
+
+        if let Err(e) = source.read_bytes(&mut entropy_input) {
+            return Err(DrbgError::Source(e));
+        }
+
+        state.reseed(&[&entropy_input, additional_input]);
+
+        entropy_input.zeroize();
+
+        Ok(())
+    }
+
+    /// Fill `output` from `state`, reseeding first with prediction resistance, whenever the interval is reached, or
+    /// after a `fork`.
+    fn generate_state(
+        source: &S,
+        reseed_interval: u64,
+        prediction_resistance: bool,
+        state: &mut CtrDrbgState,
+        output: &mut [u8],
+        additional_input: &[u8],
+    ) -> Result<(), DrbgError<S::EntropySourceError>> {
+        for chunk in output.chunks_mut(MAX_REQUEST) {
+            let mut additional_input = additional_input;
+
+            if prediction_resistance || state.reseed_counter > reseed_interval || state.fork_epoch != fork::epoch() {
+                Self::reseed_state(source, state, additional_input)?;
+
+                additional_input = &[];
+            }
+
+            state.generate(chunk, &[additional_input]);
+        }
+
+        Ok(())
+    }
+}
+
+impl<S: EntropySource> Drbg for CtrDrbg<S> {
+    type Source = S;
+
+    fn instantiate_with_personalization(
+        source: S,
+        personalization: &[u8],
+    ) -> Result<Self, DrbgError<S::EntropySourceError>> {
+        let mut seed = [0u8; KEYLEN + NONCE_LEN];
+
+        if let Err(e) = source.read_bytes(&mut seed) {
+            return Err(DrbgError::Source(e));
+        }
+
+        let state = CtrDrbgState::new(&[&seed, personalization]);
+
+        seed.zeroize();
+
+        Ok(Self {
+            source,
+            state: Lock::new(state),
+            reseed_interval: MAX_RESEED_INTERVAL,
+            prediction_resistance: false,
+        })
+    }
+
+    fn with_reseed_interval(mut self, reseed_interval: u64) -> Self {
+        self.reseed_interval = reseed_interval.clamp(1, MAX_RESEED_INTERVAL);
+        self
+    }
+
+    fn with_prediction_resistance(mut self, prediction_resistance: bool) -> Self {
+        self.prediction_resistance = prediction_resistance;
+        self
+    }
+
+    fn reseed_with_additional_input(&self, additional_input: &[u8]) -> Result<(), DrbgError<S::EntropySourceError>> {
+        self.state
+            .with(|state| Self::reseed_state(&self.source, state, additional_input))
+    }
+
+    fn generate_with_additional_input(
+        &self,
+        output: &mut [u8],
+        additional_input: &[u8],
+    ) -> Result<(), DrbgError<S::EntropySourceError>> {
+        self.state.with(|state| {
+            Self::generate_state(
+                &self.source,
+                self.reseed_interval,
+                self.prediction_resistance,
+                state,
+                output,
+                additional_input,
+            )
+        })
+    }
+
+    fn source(&self) -> &S {
+        &self.source
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySource for CtrDrbg<S> {
+    type EntropySourceError = DrbgError<S::EntropySourceError>;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.generate(buffer)
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySourceMut for CtrDrbg<S> {
+    type EntropySourceError = DrbgError<S::EntropySourceError>;
+
+    fn read_bytes_mut(&mut self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        Self::generate_state(
+            &self.source,
+            self.reseed_interval,
+            self.prediction_resistance,
+            self.state.get_mut(),
+            buffer,
+            &[],
+        )
+    }
+}
+
+/// NOTE: Each reseed reads a 32-byte entropy input, which must carry 256 bits for the output to be credited at all.
+impl<S: EntropyAssessment> EntropyAssessment for CtrDrbg<S> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        assess::seeded(self.source.min_entropy_per_byte(), KEYLEN)
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            basis: Basis::Derived,
+            ..self.source.provenance()
+        }
+    }
+}
+
+impl<S: EntropySource> SelfTest for CtrDrbg<S> {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        let error = SelfTestError::KnownAnswer("CTR_DRBG");
+        let drbg = CtrDrbg::<KatSource>::instantiate(KatSource).map_err(|_| error)?;
+        let mut output = [0u8; 32];
+
+        drbg.generate(&mut output).map_err(|_| error)?;
+
+        known_answer("CTR_DRBG", &output, &KAT_OUTPUT)
+    }
+}
+
+impl<S: EntropySource> Reseedable for CtrDrbg<S> {
+    const SEED_LEN: usize = KEYLEN;
+
+    fn reseed_with(&self, entropy: &[u8]) {
+        self.state.with(|state| state.reseed(&[entropy]));
+    }
+}
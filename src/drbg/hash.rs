@@ -0,0 +1,368 @@
+use sha2::{Digest, Sha256, Sha512};
+use zeroize::Zeroize;
+
+use crate::assess::{self, Basis, EntropyAssessment, Provenance};
+use crate::drbg::{Drbg, DrbgError, MAX_REQUEST, MAX_RESEED_INTERVAL};
+use crate::fork;
+use crate::lock::Lock;
+use crate::reseed::Reseedable;
+use crate::selftest::{known_answer, KatSource, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::{EntropySource, EntropySourceMut};
+
+/// Security strength of every supported digest in bytes.
+const STRENGTH: usize = 32;
+
+/// Length of the nonce drawn alongside the entropy input at instantiation.
+const NONCE_LEN: usize = STRENGTH / 2;
+
+/// Largest seed length across the supported digests.
+const MAX_SEEDLEN: usize = 111;
+
+/// Expected first 32 output bytes of Hash_DRBG with SHA-256 after instantiating from `KatSource`.
+const KAT_OUTPUT_SHA256: [u8; 32] = [
+    0x48, 0xf1, 0xbd, 0x75, 0x5b, 0x6b, 0x06, 0x25, 0x15, 0x5a, 0x44, 0x04, 0x83, 0x34, 0x0d, 0x86,
+    0x90, 0x17, 0x95, 0xfb, 0x5f, 0x80, 0x4e, 0x0e, 0x5e, 0x27, 0x20, 0xd8, 0xc1, 0x69, 0x29, 0x12,
+];
+
+/// Expected first 32 output bytes of Hash_DRBG with SHA-512 after instantiating from `KatSource`.
+const KAT_OUTPUT_SHA512: [u8; 32] = [
+    0xfe, 0xac, 0x63, 0x5d, 0xa7, 0xa9, 0xc7, 0xd9, 0xf0, 0x13, 0x56, 0x45, 0xd5, 0x9b, 0x15, 0x7f,
+    0x1a, 0x12, 0xae, 0xc7, 0x02, 0x7d, 0x23, 0xf2, 0xe7, 0x28, 0xe5, 0x72, 0x3e, 0xca, 0xb0, 0xe7,
+];
+
+/// A hash function usable with `HashDrbg`, along with its SP 800-90A seed length.
+pub trait HashDrbgDigest: Digest {
+    /// Seed length in bytes.
+    const SEEDLEN: usize;
+}
+
+impl HashDrbgDigest for Sha256 {
+    const SEEDLEN: usize = 55;
+}
+
+impl HashDrbgDigest for Sha512 {
+    const SEEDLEN: usize = 111;
+}
+
+/// Add `addend` (big-endian) into `value` (big-endian), modulo `2^(8 * value.len())`.
+fn add_into(value: &mut [u8], addend: &[u8]) {
+    let mut carry = 0u16;
+
+    for (i, byte) in value.iter_mut().rev().enumerate() {
+        let other = match addend.len().checked_sub(i + 1) {
+            Some(index) => addend[index] as u16,
+            None => 0,
+        };
+
+        let sum = *byte as u16 + other + carry;
+
+        *byte = sum as u8;
+        carry = sum >> 8;
+    }
+}
+
+/// The Hash_df derivation function, filling `output`.
+fn derive<'a, D: HashDrbgDigest>(input: impl IntoIterator<Item = &'a [u8]> + Clone, output: &mut [u8]) {
+    let bits = (output.len() as u32) * 8;
+
+    for (counter, chunk) in output.chunks_mut(<D as Digest>::output_size()).enumerate() {
+        let mut hasher = D::new();
+
+        hasher.update([(counter + 1) as u8]);
+        hasher.update(bits.to_be_bytes());
+
+        for data in input.clone() {
+            hasher.update(data);
+        }
+
+        chunk.copy_from_slice(&hasher.finalize()[..chunk.len()]);
+    }
+}
+
+struct HashDrbgState<D: HashDrbgDigest> {
+    value: [u8; MAX_SEEDLEN],
+    constant: [u8; MAX_SEEDLEN],
+    reseed_counter: u64,
+    fork_epoch: u64,
+    digest: core::marker::PhantomData<D>,
+}
+
+impl<D: HashDrbgDigest> HashDrbgState<D> {
+    fn new(seed_material: &[&[u8]]) -> Self {
+        let mut state = Self {
+            value: [0u8; MAX_SEEDLEN],
+            constant: [0u8; MAX_SEEDLEN],
+            reseed_counter: 1,
+            fork_epoch: fork::epoch(),
+            digest: core::marker::PhantomData,
+        };
+
+        derive::<D>(seed_material.iter().copied(), &mut state.value[..D::SEEDLEN]);
+
+        state.derive_constant();
+
+        state
+    }
+
+    fn derive_constant(&mut self) {
+        derive::<D>([&[0x00][..], &self.value[..D::SEEDLEN]], &mut self.constant[..D::SEEDLEN]);
+    }
+
+    fn reseed(&mut self, seed_material: &[&[u8]]) {
+        let mut seed = [0u8; MAX_SEEDLEN];
+
+        let input = [&[0x01][..], &self.value[..D::SEEDLEN]];
+
+        derive::<D>(input.into_iter().chain(seed_material.iter().copied()), &mut seed[..D::SEEDLEN]);
+
+        self.value = seed;
+        self.derive_constant();
+        self.reseed_counter = 1;
+        self.fork_epoch = fork::epoch();
+
+        seed.zeroize();
+
+        trace::event!(debug, generator = "Hash_DRBG", "reseeded");
+    }
+
+    fn hash(prefix: u8, value: &[u8], additional: &[&[u8]]) -> sha2::digest::Output<D> {
+        let mut hasher = D::new();
+
+        hasher.update([prefix]);
+        hasher.update(value);
+
+        for data in additional {
+            hasher.update(data);
+        }
+
+        hasher.finalize()
+    }
+
+    /// NOTE: `output` must not exceed `MAX_REQUEST` bytes.
+    fn generate(&mut self, output: &mut [u8], additional_input: &[&[u8]]) {
+        let seedlen = D::SEEDLEN;
+
+        if additional_input.iter().any(|data| !data.is_empty()) {
+            let w = Self::hash(0x02, &self.value[..seedlen], additional_input);
+
+            add_into(&mut self.value[..seedlen], &w);
+        }
+
+        // Hashgen.
+        let mut data = self.value;
+
+        for chunk in output.chunks_mut(<D as Digest>::output_size()) {
+            chunk.copy_from_slice(&D::digest(&data[..seedlen])[..chunk.len()]);
+
+            add_into(&mut data[..seedlen], &[0x01]);
+        }
+
+        data.zeroize();
+
+        let h = Self::hash(0x03, &self.value[..seedlen], &[]);
+
+        add_into(&mut self.value[..seedlen], &h);
+        add_into(&mut self.value[..seedlen], &self.constant[..seedlen]);
+        add_into(&mut self.value[..seedlen], &self.reseed_counter.to_be_bytes());
+
+        self.reseed_counter += 1;
+    }
+}
+
+impl<D: HashDrbgDigest> Drop for HashDrbgState<D> {
+    fn drop(&mut self) {
+        self.value.zeroize();
+        self.constant.zeroize();
+    }
+}
+
+/// Hash_DRBG (SP 800-90A) using SHA-256 (default) or SHA-512, seeded from an `EntropySource`.
+///
+/// NOTE: The generator reseeds itself from the source once the reseed interval is reached.
+pub struct HashDrbg<S: EntropySource, D: HashDrbgDigest = Sha256> {
+    source: S,
+    state: Lock<HashDrbgState<D>>,
+    reseed_interval: u64,
+    prediction_resistance: bool,
+}
+
+impl<S: EntropySource, D: HashDrbgDigest> HashDrbg<S, D> {
+    fn reseed_state(
+        source: &S,
+        state: &mut HashDrbgState<D>,
+        additional_input: &[u8],
+    ) -> Result<(), DrbgError<S::EntropySourceError>> {
+        let mut entropy_input = [0u8; STRENGTH];
+
+        if let Err(e) = source.read_bytes(&mut entropy_input) {
+            return Err(DrbgError::Source(e));
+        }
+
+        state.reseed(&[&entropy_input, additional_input]);
+
+        entropy_input.zeroize();
+
+        Ok(())
+    }
+
+    /// Fill `output` from `state`, reseeding first with prediction resistance, whenever the interval is reached, or
+    /// after a `fork`.
+    fn generate_state(
+        source: &S,
+        reseed_interval: u64,
+        prediction_resistance: bool,
+        state: &mut HashDrbgState<D>,
+        output: &mut [u8],
+        additional_input: &[u8],
+    ) -> Result<(), DrbgError<S::EntropySourceError>> {
+        for chunk in output.chunks_mut(MAX_REQUEST) {
+            let mut additional_input = additional_input;
+
+            if prediction_resistance || state.reseed_counter > reseed_interval || state.fork_epoch != fork::epoch() {
+                Self::reseed_state(source, state, additional_input)?;
+
+                additional_input = &[];
+            }
+
+            state.generate(chunk, &[additional_input]);
+        }
+
+        Ok(())
+    }
+}
+
+impl<S: EntropySource, D: HashDrbgDigest> Drbg for HashDrbg<S, D> {
+    type Source = S;
+
+    fn instantiate_with_personalization(
+        source: S,
+        personalization: &[u8],
+    ) -> Result<Self, DrbgError<S::EntropySourceError>> {
+        let mut seed = [0u8; STRENGTH + NONCE_LEN];
+
+        if let Err(e) = source.read_bytes(&mut seed) {
+            return Err(DrbgError::Source(e));
+        }
+
+        let state = HashDrbgState::new(&[&seed, personalization]);
+
+        seed.zeroize();
+
+        Ok(Self {
+            source,
+            state: Lock::new(state),
+            reseed_interval: MAX_RESEED_INTERVAL,
+            prediction_resistance: false,
+        })
+    }
+
+    fn with_reseed_interval(mut self, reseed_interval: u64) -> Self {
+        self.reseed_interval = reseed_interval.clamp(1, MAX_RESEED_INTERVAL);
+        self
+    }
+
+    fn with_prediction_resistance(mut self, prediction_resistance: bool) -> Self {
+        self.prediction_resistance = prediction_resistance;
+        self
+    }
+
+    fn reseed_with_additional_input(&self, additional_input: &[u8]) -> Result<(), DrbgError<S::EntropySourceError>> {
+        self.state
+            .with(|state| Self::reseed_state(&self.source, state, additional_input))
+    }
+
+    fn generate_with_additional_input(
+        &self,
+        output: &mut [u8],
+        additional_input: &[u8],
+    ) -> Result<(), DrbgError<S::EntropySourceError>> {
+        self.state.with(|state| {
+            Self::generate_state(
+                &self.source,
+                self.reseed_interval,
+                self.prediction_resistance,
+                state,
+                output,
+                additional_input,
+            )
+        })
+    }
+
+    fn source(&self) -> &S {
+        &self.source
+    }
+}
+
+unsafe impl<S: EntropySource, D: HashDrbgDigest> EntropySource for HashDrbg<S, D> {
+    type EntropySourceError = DrbgError<S::EntropySourceError>;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.generate(buffer)
+    }
+}
+
+unsafe impl<S: EntropySource, D: HashDrbgDigest> EntropySourceMut for HashDrbg<S, D> {
+    type EntropySourceError = DrbgError<S::EntropySourceError>;
+
+    fn read_bytes_mut(&mut self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        Self::generate_state(
+            &self.source,
+            self.reseed_interval,
+            self.prediction_resistance,
+            self.state.get_mut(),
+            buffer,
+            &[],
+        )
+    }
+}
+
+/// NOTE: Credited like the other DRBGs: full entropy if every 32-byte reseed carries the security strength.
+impl<S: EntropyAssessment, D: HashDrbgDigest> EntropyAssessment for HashDrbg<S, D> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        assess::seeded(self.source.min_entropy_per_byte(), STRENGTH)
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            basis: Basis::Derived,
+            ..self.source.provenance()
+        }
+    }
+}
+
+impl<S: EntropySource> SelfTest for HashDrbg<S, Sha256> {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        let error = SelfTestError::KnownAnswer("Hash_DRBG-SHA256");
+        let drbg = HashDrbg::<KatSource, Sha256>::instantiate(KatSource).map_err(|_| error)?;
+        let mut output = [0u8; 32];
+
+        drbg.generate(&mut output).map_err(|_| error)?;
+
+        known_answer("Hash_DRBG-SHA256", &output, &KAT_OUTPUT_SHA256)
+    }
+}
+
+impl<S: EntropySource> SelfTest for HashDrbg<S, Sha512> {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        let error = SelfTestError::KnownAnswer("Hash_DRBG-SHA512");
+        let drbg = HashDrbg::<KatSource, Sha512>::instantiate(KatSource).map_err(|_| error)?;
+        let mut output = [0u8; 32];
+
+        drbg.generate(&mut output).map_err(|_| error)?;
+
+        known_answer("Hash_DRBG-SHA512", &output, &KAT_OUTPUT_SHA512)
+    }
+}
+
+impl<S: EntropySource, D: HashDrbgDigest> Reseedable for HashDrbg<S, D> {
+    const SEED_LEN: usize = STRENGTH;
+
+    fn reseed_with(&self, entropy: &[u8]) {
+        self.state.with(|state| state.reseed(&[entropy]));
+    }
+}
@@ -0,0 +1,289 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use zeroize::Zeroize;
+
+use crate::assess::{self, Basis, EntropyAssessment, Provenance};
+use crate::drbg::{Drbg, DrbgError, MAX_REQUEST, MAX_RESEED_INTERVAL};
+use crate::fork;
+use crate::lock::Lock;
+use crate::reseed::Reseedable;
+use crate::selftest::{known_answer, KatSource, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::{EntropySource, EntropySourceMut};
+
+/// Output length (and security strength) of HMAC-SHA-256 in bytes.
+const OUTLEN: usize = 32;
+
+/// Length of the nonce drawn alongside the entropy input at instantiation.
+const NONCE_LEN: usize = OUTLEN / 2;
+
+/// Expected first 32 output bytes after instantiating from `KatSource`.
+const KAT_OUTPUT: [u8; 32] = [
+    0x0f, 0xfb, 0x80, 0x87, 0x5a, 0x3e, 0x90, 0x22, 0xa4, 0x94, 0x1a, 0x3f, 0xa1, 0xb0, 0xd3, 0x61,
+    0x1d, 0xf1, 0x4e, 0x1c, 0xf6, 0x51, 0xa7, 0x3c, 0xe9, 0x22, 0x9b, 0x9f, 0x3a, 0xd5, 0x68, 0x87,
+];
+
+struct HmacDrbgState {
+    key: [u8; OUTLEN],
+    value: [u8; OUTLEN],
+    reseed_counter: u64,
+    fork_epoch: u64,
+}
+
+impl HmacDrbgState {
+    fn new(seed_material: &[&[u8]]) -> Self {
+        let mut state = Self {
+            key: [0x00; OUTLEN],
+            value: [0x01; OUTLEN],
+            reseed_counter: 1,
+            fork_epoch: fork::epoch(),
+        };
+
+        state.update(seed_material);
+
+        state
+    }
+
+    fn mac(&self) -> Hmac<Sha256> {
+        Hmac::<Sha256>::new_from_slice(&self.key).unwrap()
+    }
+
+    /// The HMAC_DRBG update function.
+    fn update(&mut self, provided_data: &[&[u8]]) {
+        for round in [0x00u8, 0x01] {
+            let mut mac = self.mac();
+
+            mac.update(&self.value);
+            mac.update(&[round]);
+
+            for data in provided_data {
+                mac.update(data);
+            }
+
+            self.key = mac.finalize().into_bytes().into();
+
+            let mut mac = self.mac();
+
+            mac.update(&self.value);
+
+            self.value = mac.finalize().into_bytes().into();
+
+            if provided_data.iter().all(|data| data.is_empty()) {
+                break;
+            }
+        }
+    }
+
+    fn reseed(&mut self, seed_material: &[&[u8]]) {
+        self.update(seed_material);
+        self.reseed_counter = 1;
+        self.fork_epoch = fork::epoch();
+
+        trace::event!(debug, generator = "HMAC_DRBG", "reseeded");
+    }
+
+    /// NOTE: `output` must not exceed `MAX_REQUEST` bytes.
+    fn generate(&mut self, output: &mut [u8], additional_input: &[&[u8]]) {
+        if additional_input.iter().any(|data| !data.is_empty()) {
+            self.update(additional_input);
+        }
+
+        for chunk in output.chunks_mut(OUTLEN) {
+            let mut mac = self.mac();
+
+            mac.update(&self.value);
+
+            self.value = mac.finalize().into_bytes().into();
+
+            chunk.copy_from_slice(&self.value[..chunk.len()]);
+        }
+
+        self.update(additional_input);
+        self.reseed_counter += 1;
+    }
+}
+
+impl Drop for HmacDrbgState {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.value.zeroize();
+    }
+}
+
+/// HMAC_DRBG (SP 800-90A) using HMAC-SHA-256, seeded from an `EntropySource`.
+///
+/// NOTE: The generator reseeds itself from the source once the reseed interval is reached.
+pub struct HmacDrbg<S: EntropySource> {
+    source: S,
+    state: Lock<HmacDrbgState>,
+    reseed_interval: u64,
+    prediction_resistance: bool,
+}
+
+impl<S: EntropySource> HmacDrbg<S> {
+    fn reseed_state(
+        source: &S,
+        state: &mut HmacDrbgState,
+        additional_input: &[u8],
+    ) -> Result<(), DrbgError<S::EntropySourceError>> {
+        let mut entropy_input = [0u8; OUTLEN];
+
+        if let Err(e) = source.read_bytes(&mut entropy_input) {
+            return Err(DrbgError::Source(e));
+        }
+
+        state.reseed(&[&entropy_input, additional_input]);
+
+        entropy_input.zeroize();
+
+        Ok(())
+    }
+
+    /// Fill `output` from `state`, reseeding first with prediction resistance, whenever the interval is reached, or
+    /// after a `fork`.
+    fn generate_state(
+        source: &S,
+        reseed_interval: u64,
+        prediction_resistance: bool,
+        state: &mut HmacDrbgState,
+        output: &mut [u8],
+        additional_input: &[u8],
+    ) -> Result<(), DrbgError<S::EntropySourceError>> {
+        for chunk in output.chunks_mut(MAX_REQUEST) {
+            let mut additional_input = additional_input;
+
+            if prediction_resistance || state.reseed_counter > reseed_interval || state.fork_epoch != fork::epoch() {
+                Self::reseed_state(source, state, additional_input)?;
+
+                additional_input = &[];
+            }
+
+            state.generate(chunk, &[additional_input]);
+        }
+
+        Ok(())
+    }
+}
+
+impl<S: EntropySource> Drbg for HmacDrbg<S> {
+    type Source = S;
+
+    fn instantiate_with_personalization(
+        source: S,
+        personalization: &[u8],
+    ) -> Result<Self, DrbgError<S::EntropySourceError>> {
+        let mut seed = [0u8; OUTLEN + NONCE_LEN];
+
+        if let Err(e) = source.read_bytes(&mut seed) {
+            return Err(DrbgError::Source(e));
+        }
+
+        let state = HmacDrbgState::new(&[&seed, personalization]);
+
+        seed.zeroize();
+
+        Ok(Self {
+            source,
+            state: Lock::new(state),
+            reseed_interval: MAX_RESEED_INTERVAL,
+            prediction_resistance: false,
+        })
+    }
+
+    fn with_reseed_interval(mut self, reseed_interval: u64) -> Self {
+        self.reseed_interval = reseed_interval.clamp(1, MAX_RESEED_INTERVAL);
+        self
+    }
+
+    fn with_prediction_resistance(mut self, prediction_resistance: bool) -> Self {
+        self.prediction_resistance = prediction_resistance;
+        self
+    }
+
+    fn reseed_with_additional_input(&self, additional_input: &[u8]) -> Result<(), DrbgError<S::EntropySourceError>> {
+        self.state
+            .with(|state| Self::reseed_state(&self.source, state, additional_input))
+    }
+
+    fn generate_with_additional_input(
+        &self,
+        output: &mut [u8],
+        additional_input: &[u8],
+    ) -> Result<(), DrbgError<S::EntropySourceError>> {
+        self.state.with(|state| {
+            Self::generate_state(
+                &self.source,
+                self.reseed_interval,
+                self.prediction_resistance,
+                state,
+                output,
+                additional_input,
+            )
+        })
+    }
+
+    fn source(&self) -> &S {
+        &self.source
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySource for HmacDrbg<S> {
+    type EntropySourceError = DrbgError<S::EntropySourceError>;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.generate(buffer)
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySourceMut for HmacDrbg<S> {
+    type EntropySourceError = DrbgError<S::EntropySourceError>;
+
+    fn read_bytes_mut(&mut self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        Self::generate_state(
+            &self.source,
+            self.reseed_interval,
+            self.prediction_resistance,
+            self.state.get_mut(),
+            buffer,
+            &[],
+        )
+    }
+}
+
+/// NOTE: Credited with full entropy only if every reseed (32 bytes of the source) carries the full security strength.
+impl<S: EntropyAssessment> EntropyAssessment for HmacDrbg<S> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        assess::seeded(self.source.min_entropy_per_byte(), OUTLEN)
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            basis: Basis::Derived,
+            ..self.source.provenance()
+        }
+    }
+}
+
+impl<S: EntropySource> SelfTest for HmacDrbg<S> {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        let error = SelfTestError::KnownAnswer("HMAC_DRBG");
+        let drbg = HmacDrbg::<KatSource>::instantiate(KatSource).map_err(|_| error)?;
+        let mut output = [0u8; 32];
+
+        drbg.generate(&mut output).map_err(|_| error)?;
+
+        known_answer("HMAC_DRBG", &output, &KAT_OUTPUT)
+    }
+}
+
+impl<S: EntropySource> Reseedable for HmacDrbg<S> {
+    const SEED_LEN: usize = OUTLEN;
+
+    fn reseed_with(&self, entropy: &[u8]) {
+        self.state.with(|state| state.reseed(&[entropy]));
+    }
+}
@@ -0,0 +1,110 @@
+//! Deterministic random bit generators (SP 800-90A) seeded from an `EntropySource`.
+
+use core::error::Error;
+
+use crate::error::{Classify, ErrorKind};
+use crate::EntropySource;
+
+mod ctr;
+mod hash;
+mod hmac;
+
+pub use ctr::CtrDrbg;
+pub use hash::{HashDrbg, HashDrbgDigest};
+pub use hmac::HmacDrbg;
+pub use sha2::{Sha256, Sha512};
+
+/// Maximum (and default) number of generate requests between reseeds.
+const MAX_RESEED_INTERVAL: u64 = 1 << 48;
+
+/// Maximum number of bytes per generate request.
+const MAX_REQUEST: usize = 1 << 16;
+
+#[derive(Debug)]
+pub enum DrbgError<E: Error> {
+    /// The entropy source failed while (re)seeding the generator.
+    Source(E),
+}
+
+impl<E: Error> core::fmt::Display for DrbgError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<E: Error> Error for DrbgError<E> {}
+
+impl<E: Error + Classify> Classify for DrbgError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Source(e) => e.kind(),
+        }
+    }
+}
+
+/// The common instantiate/generate/reseed interface shared by all DRBG constructions.
+///
+/// NOTE: Every `Drbg` is also an `EntropySource`, where `read_bytes` is equivalent to `generate`. The built-in
+/// generators detect `fork` and reseed in the child before generating, so it never replays the parent's output.
+pub trait Drbg: EntropySource + Sized {
+    type Source: EntropySource;
+
+    /// Attempt to instantiate the generator from the provided `EntropySource`.
+    fn instantiate(
+        source: Self::Source,
+    ) -> Result<Self, DrbgError<<Self::Source as EntropySource>::EntropySourceError>> {
+        Self::instantiate_with_personalization(source, &[])
+    }
+
+    /// Attempt to instantiate the generator with a personalization string mixed into its seed.
+    ///
+    /// NOTE: Instances with distinct strings (e.g. one per subsystem) are domain-separated even if their sources were
+    /// to return the same bytes. The string need not be secret, but should be unique to its user.
+    fn instantiate_with_personalization(
+        source: Self::Source,
+        personalization: &[u8],
+    ) -> Result<Self, DrbgError<<Self::Source as EntropySource>::EntropySourceError>>;
+
+    /// Set the number of generate requests allowed between automatic reseeds.
+    ///
+    /// NOTE: The interval is clamped to `1..=2^48` as required by SP 800-90A.
+    fn with_reseed_interval(self, reseed_interval: u64) -> Self;
+
+    /// Enable (or disable) prediction resistance: every generate call first reseeds from the `EntropySource`.
+    ///
+    /// NOTE: Every request (and every 64 KiB of a larger one) then reads fresh entropy, so the source must be live
+    /// and fast enough for the request rate; a failing source fails the request instead of falling back.
+    fn with_prediction_resistance(self, prediction_resistance: bool) -> Self;
+
+    /// Attempt to reseed the generator from its `EntropySource`.
+    fn reseed(&self) -> Result<(), DrbgError<<Self::Source as EntropySource>::EntropySourceError>> {
+        self.reseed_with_additional_input(&[])
+    }
+
+    /// Attempt to reseed the generator from its `EntropySource`, mixing `additional_input` into the new seed.
+    fn reseed_with_additional_input(
+        &self,
+        additional_input: &[u8],
+    ) -> Result<(), DrbgError<<Self::Source as EntropySource>::EntropySourceError>>;
+
+    /// Attempt to fill `output` with generated bytes, reseeding first if the reseed interval has been reached.
+    fn generate(
+        &self,
+        output: &mut [u8],
+    ) -> Result<(), DrbgError<<Self::Source as EntropySource>::EntropySourceError>> {
+        self.generate_with_additional_input(output, &[])
+    }
+
+    /// Attempt to fill `output` with generated bytes bound to `additional_input` (e.g. context data for the request).
+    ///
+    /// NOTE: Requests are split into generate calls of at most 64 KiB, each taking the additional input. A call that
+    /// reseeds first mixes the additional input into the reseed instead, as SP 800-90A specifies.
+    fn generate_with_additional_input(
+        &self,
+        output: &mut [u8],
+        additional_input: &[u8],
+    ) -> Result<(), DrbgError<<Self::Source as EntropySource>::EntropySourceError>>;
+
+    /// Get a reference to the underlying `EntropySource`.
+    fn source(&self) -> &Self::Source;
+}
@@ -0,0 +1,114 @@
+//! Entropy helpers for microcontroller projects without a TRNG peripheral.
+
+use core::convert::Infallible;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::condition::Conditioned;
+use crate::health::{HealthError, Monitored, MonitoredError};
+use crate::lock::Lock;
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+/// The raw bits: the least significant bit of 8 consecutive ADC readings per byte.
+struct AdcSamples<F: FnMut() -> u16> {
+    read: Lock<F>,
+    min_entropy: u8,
+}
+
+unsafe impl<F: FnMut() -> u16> EntropySource for AdcSamples<F> {
+    type EntropySourceError = Infallible;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        self.read.with(|read| {
+            for byte in buffer.iter_mut() {
+                *byte = (0..8).fold(0u8, |byte, _| (byte << 1) | (read() & 1) as u8);
+            }
+        });
+
+        Ok(())
+    }
+}
+
+impl<F: FnMut() -> u16> EntropyAssessment for AdcSamples<F> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.min_entropy as f64
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Noise,
+            basis: Basis::Claimed,
+        }
+    }
+}
+
+/// Entropy from the noise in repeated ADC readings, such as a floating pin or the LSBs of an internal temperature
+/// sensor.
+///
+/// The least significant bit of every reading is collected into raw bytes, health tested against the claimed
+/// min-entropy, and conditioned with SHA-256 using `16 / min_entropy` raw bytes per output byte.
+///
+/// NOTE: The claim has to come from an assessment of the actual board (e.g. with `estimate` on raw readings taken under
+/// the expected operating conditions). ADC noise depends heavily on layout, supply noise and temperature, and a
+/// pin that picks up a periodic signal can look random to the health tests while being predictable.
+pub struct AdcNoise<F: FnMut() -> u16> {
+    source: Conditioned<Monitored<AdcSamples<F>>>,
+}
+
+impl<F: FnMut() -> u16> AdcNoise<F> {
+    /// Sample `read` (one ADC conversion per call), claiming `min_entropy` bits (1 to 8) per 8 readings.
+    pub fn new(read: F, min_entropy: u8) -> Self {
+        let min_entropy = min_entropy.clamp(1, 8);
+
+        let raw = AdcSamples {
+            read: Lock::new(read),
+            min_entropy,
+        };
+        let ratio = 16usize.div_ceil(min_entropy as usize);
+
+        Self {
+            source: Conditioned::new(Monitored::new(raw, min_entropy)).with_ratio(ratio),
+        }
+    }
+
+    /// Get the number of raw bytes (of 8 readings each) consumed per output byte.
+    pub fn ratio(&self) -> usize {
+        self.source.ratio()
+    }
+
+    /// Get the health test failure latched on the raw bits, if any.
+    pub fn failure(&self) -> Option<HealthError> {
+        self.source.source().failure()
+    }
+}
+
+unsafe impl<F: FnMut() -> u16> EntropySource for AdcNoise<F> {
+    type EntropySourceError = HealthError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        match self.source.read_bytes(buffer) {
+            Ok(()) => Ok(()),
+            Err(MonitoredError::Health(e)) => Err(e),
+            Err(MonitoredError::Source(e)) => match e {},
+        }
+    }
+}
+
+impl<F: FnMut() -> u16> EntropyAssessment for AdcNoise<F> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.source.provenance()
+    }
+}
+
+impl<F: FnMut() -> u16> SelfTest for AdcNoise<F> {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
@@ -0,0 +1,139 @@
+//! Hex and Base64 encoding for `Entropy` and `EntropyVec`.
+//!
+//! NOTE: Encoding and decoding are constant-time (using `base16ct`/`base64ct`) since the bytes are usually secret.
+
+use core::error::Error;
+
+use crate::Entropy;
+#[cfg(feature = "alloc")]
+use crate::EntropyVec;
+
+#[derive(Debug)]
+pub enum EncodingError {
+    /// The input contained characters outside of the encoding's alphabet (or invalid padding).
+    InvalidEncoding,
+    /// The input did not decode to exactly `LENGTH` bytes (or had an invalid length for the encoding).
+    InvalidLength,
+}
+
+impl core::fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for EncodingError {}
+
+#[cfg(feature = "hex")]
+impl From<base16ct::Error> for EncodingError {
+    fn from(error: base16ct::Error) -> Self {
+        match error {
+            base16ct::Error::InvalidLength => Self::InvalidLength,
+            _ => Self::InvalidEncoding,
+        }
+    }
+}
+
+#[cfg(feature = "base64")]
+impl From<base64ct::Error> for EncodingError {
+    fn from(error: base64ct::Error) -> Self {
+        match error {
+            base64ct::Error::InvalidLength => Self::InvalidLength,
+            _ => Self::InvalidEncoding,
+        }
+    }
+}
+
+#[cfg(feature = "hex")]
+impl<const LENGTH: usize> Entropy<LENGTH> {
+    /// Encode the bytes as lowercase hex.
+    #[cfg(feature = "alloc")]
+    pub fn to_hex(&self) -> alloc::string::String {
+        base16ct::lower::encode_string(&self.bytes)
+    }
+
+    /// Attempt to decode entropy from hex (either case).
+    pub fn from_hex(hex: &str) -> Result<Self, EncodingError> {
+        if hex.len() != LENGTH * 2 {
+            return Err(EncodingError::InvalidLength);
+        }
+
+        let mut entropy = Self { bytes: [0u8; LENGTH] };
+
+        base16ct::mixed::decode(hex, &mut entropy.bytes)?;
+
+        Ok(entropy)
+    }
+}
+
+#[cfg(feature = "base64")]
+impl<const LENGTH: usize> Entropy<LENGTH> {
+    /// Encode the bytes as standard (padded) Base64.
+    #[cfg(feature = "alloc")]
+    pub fn to_base64(&self) -> alloc::string::String {
+        use base64ct::Encoding;
+
+        base64ct::Base64::encode_string(&self.bytes)
+    }
+
+    /// Attempt to decode entropy from standard (padded) Base64.
+    pub fn from_base64(base64: &str) -> Result<Self, EncodingError> {
+        use base64ct::Encoding;
+
+        let mut entropy = Self { bytes: [0u8; LENGTH] };
+
+        match base64ct::Base64::decode(base64, &mut entropy.bytes)?.len() {
+            length if length == LENGTH => Ok(entropy),
+            _ => Err(EncodingError::InvalidLength),
+        }
+    }
+}
+
+#[cfg(all(feature = "hex", feature = "alloc"))]
+impl EntropyVec {
+    /// Encode the bytes as lowercase hex.
+    pub fn to_hex(&self) -> alloc::string::String {
+        base16ct::lower::encode_string(&self.bytes)
+    }
+
+    /// Attempt to decode entropy of any length from hex (either case).
+    pub fn from_hex(hex: &str) -> Result<Self, EncodingError> {
+        if hex.len() % 2 != 0 {
+            return Err(EncodingError::InvalidLength);
+        }
+
+        let mut entropy = Self {
+            bytes: alloc::vec![0u8; hex.len() / 2],
+        };
+
+        base16ct::mixed::decode(hex, &mut entropy.bytes)?;
+
+        Ok(entropy)
+    }
+}
+
+#[cfg(all(feature = "base64", feature = "alloc"))]
+impl EntropyVec {
+    /// Encode the bytes as standard (padded) Base64.
+    pub fn to_base64(&self) -> alloc::string::String {
+        use base64ct::Encoding;
+
+        base64ct::Base64::encode_string(&self.bytes)
+    }
+
+    /// Attempt to decode entropy of any length from standard (padded) Base64.
+    pub fn from_base64(base64: &str) -> Result<Self, EncodingError> {
+        use base64ct::Encoding;
+
+        let mut entropy = Self {
+            bytes: alloc::vec![0u8; base64.len() / 4 * 3],
+        };
+
+        let length = base64ct::Base64::decode(base64, &mut entropy.bytes)?.len();
+
+        // NOTE: Truncating keeps the allocation, so the padding bytes are still wiped on drop.
+        entropy.bytes.truncate(length);
+
+        Ok(entropy)
+    }
+}
@@ -0,0 +1,148 @@
+//! Type-erased entropy sources for runtime selection and trait objects.
+
+use alloc::boxed::Box;
+use core::error::Error;
+
+use crate::error::{Classify, EntropyError, ErrorKind};
+use crate::EntropySource;
+
+/// The error of an erased source: the original error, boxed, and its kind if it was erased with `erase_classified`.
+#[derive(Debug)]
+pub struct ErasedError {
+    error: Box<dyn Error + Send + Sync + 'static>,
+    kind: Option<ErrorKind>,
+}
+
+impl ErasedError {
+    /// Box the original error of a source.
+    ///
+    /// NOTE: An `ErasedError` is returned as is rather than boxed again, so erasing an erased source is harmless.
+    pub fn new(error: impl Error + Send + Sync + 'static) -> Self {
+        let error: Box<dyn Error + Send + Sync + 'static> = Box::new(error);
+
+        match error.downcast::<Self>() {
+            Ok(erased) => *erased,
+            Err(error) => Self { error, kind: None },
+        }
+    }
+
+    /// Box the original error of a source, recording its kind.
+    pub fn classified(error: impl Classify + Error + Send + Sync + 'static) -> Self {
+        let kind = error.kind();
+
+        Self {
+            kind: Some(kind),
+            ..Self::new(error)
+        }
+    }
+
+    /// Attempt to get a reference to the original error as a concrete type.
+    pub fn downcast_ref<E: Error + 'static>(&self) -> Option<&E> {
+        self.error.downcast_ref()
+    }
+
+    /// Unwrap the original error.
+    pub fn into_inner(self) -> Box<dyn Error + Send + Sync + 'static> {
+        self.error
+    }
+}
+
+impl core::fmt::Display for ErasedError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for ErasedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.error)
+    }
+}
+
+/// NOTE: Errors erased without their kind are only classified if the original error is an `EntropyError` (e.g. from a
+/// `Unified` source); anything else is a `Backend` failure.
+impl Classify for ErasedError {
+    fn kind(&self) -> ErrorKind {
+        if let Some(kind) = self.kind {
+            return kind;
+        }
+
+        if let Some(e) = self.downcast_ref::<EntropyError>() {
+            return e.kind();
+        }
+
+        ErrorKind::Backend(0)
+    }
+}
+
+/// An object-safe counterpart to `EntropySource`, implemented for every source whose error is `Send + Sync`.
+///
+/// Sources of different types can then be stored together (e.g. `Vec<Box<dyn ErasedEntropySource>>`), chosen at
+/// runtime, or passed across plugin boundaries; boxed erased sources implement `EntropySource` again.
+///
+/// NOTE: Implementing this trait is equivalent to implementing `EntropySource`: the source must be cryptographically
+/// secure.
+pub trait ErasedEntropySource {
+    /// Fill the buffer with random bytes, boxing any error.
+    fn read_bytes_erased(&self, buffer: &mut [u8]) -> Result<(), ErasedError>;
+
+    /// Box the source as a thread-safe trait object.
+    fn erase<'a>(self) -> Box<dyn ErasedEntropySource + Send + Sync + 'a>
+    where
+        Self: Sized + Send + Sync + 'a,
+    {
+        Box::new(self)
+    }
+
+    /// Box the source as a thread-safe trait object, recording the kind of its errors (see `ErasedError::classified`).
+    fn erase_classified<'a>(self) -> Box<dyn ErasedEntropySource + Send + Sync + 'a>
+    where
+        Self: EntropySource + Sized + Send + Sync + 'a,
+        Self::EntropySourceError: Classify + Send + Sync + 'static,
+    {
+        Box::new(Classified(self))
+    }
+}
+
+impl<S: EntropySource + ?Sized> ErasedEntropySource for S
+where
+    S::EntropySourceError: Send + Sync + 'static,
+{
+    fn read_bytes_erased(&self, buffer: &mut [u8]) -> Result<(), ErasedError> {
+        match self.read_bytes(buffer) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(ErasedError::new(e)),
+        }
+    }
+}
+
+/// Erases a source whose errors are classified, as returned by `erase_classified`.
+struct Classified<S>(S);
+
+impl<S: EntropySource> ErasedEntropySource for Classified<S>
+where
+    S::EntropySourceError: Classify + Send + Sync + 'static,
+{
+    fn read_bytes_erased(&self, buffer: &mut [u8]) -> Result<(), ErasedError> {
+        match self.0.read_bytes(buffer) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(ErasedError::classified(e)),
+        }
+    }
+}
+
+unsafe impl EntropySource for Box<dyn ErasedEntropySource + '_> {
+    type EntropySourceError = ErasedError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        (**self).read_bytes_erased(buffer)
+    }
+}
+
+unsafe impl EntropySource for Box<dyn ErasedEntropySource + Send + Sync + '_> {
+    type EntropySourceError = ErasedError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        (**self).read_bytes_erased(buffer)
+    }
+}
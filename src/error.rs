@@ -0,0 +1,184 @@
+//! A unified error for the built-in sources and wrappers, classified by what a caller can do about the failure.
+
+use core::convert::Infallible;
+use core::error::Error;
+
+use crate::assess::{EntropyAssessment, Provenance};
+use crate::trace;
+use crate::EntropySource;
+
+/// What kind of failure an `EntropyError` is, for fallback and retry logic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The source is not available on this platform or configuration (e.g. a missing instruction or device); fall
+    /// back to another source rather than retry.
+    Unsupported,
+    /// The failure is temporary (e.g. interrupted, or the TPM asked to be retried); retrying may succeed.
+    Transient,
+    /// The source ran out of entropy (e.g. `RDSEED` underflow, or an unseeded pool); retry later or fall back.
+    Exhausted,
+    /// A health or known-answer test failed, so the output must not be trusted until the source recovers.
+    HealthCheckFailed,
+    /// The read did not complete in time.
+    Timeout,
+    /// Any other failure of the backend, with its raw error code (e.g. an `errno` or `NTSTATUS`), or 0 if it has none.
+    Backend(i32),
+}
+
+/// Errors of the built-in sources and wrappers, which can be classified into an `ErrorKind`.
+pub trait Classify {
+    /// Get the kind of failure.
+    fn kind(&self) -> ErrorKind;
+}
+
+impl Classify for Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+/// A classified error from any built-in source or wrapper, so one `match` on `kind` covers every backend.
+///
+/// NOTE: With the `alloc` feature the original error is kept (see `Error::source` and `downcast_ref`), and only
+/// errors which are `Send + Sync + 'static` convert into an `EntropyError`; without it only the kind is kept.
+#[derive(Debug)]
+pub struct EntropyError {
+    kind: ErrorKind,
+    #[cfg(feature = "alloc")]
+    source: Option<alloc::boxed::Box<dyn Error + Send + Sync + 'static>>,
+}
+
+impl EntropyError {
+    /// Create an error of the given kind, without an original error.
+    pub fn new(kind: ErrorKind) -> Self {
+        Self {
+            kind,
+            #[cfg(feature = "alloc")]
+            source: None,
+        }
+    }
+
+    /// Get the kind of failure.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Attempt to get a reference to the original error as a concrete type.
+    #[cfg(feature = "alloc")]
+    pub fn downcast_ref<E: Error + 'static>(&self) -> Option<&E> {
+        self.source.as_ref()?.downcast_ref()
+    }
+
+    /// Unwrap the original error, if any.
+    #[cfg(feature = "alloc")]
+    pub fn into_inner(self) -> Option<alloc::boxed::Box<dyn Error + Send + Sync + 'static>> {
+        self.source
+    }
+}
+
+impl core::fmt::Display for EntropyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for EntropyError {
+    #[cfg(feature = "alloc")]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.source {
+            Some(source) => Some(&**source),
+            None => None,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<E: Classify + Error + Send + Sync + 'static> From<E> for EntropyError {
+    fn from(error: E) -> Self {
+        Self {
+            kind: error.kind(),
+            source: Some(alloc::boxed::Box::new(error)),
+        }
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl<E: Classify> From<E> for EntropyError {
+    fn from(error: E) -> Self {
+        Self::new(error.kind())
+    }
+}
+
+/// Reports the errors of the wrapped source as `EntropyError`.
+pub struct Unified<S: EntropySource> {
+    source: S,
+}
+
+impl<S: EntropySource> Unified<S> {
+    /// Wrap a source.
+    pub fn new(source: S) -> Self {
+        Self { source }
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Unwrap the source.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySource for Unified<S>
+where
+    EntropyError: From<S::EntropySourceError>,
+{
+    type EntropySourceError = EntropyError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        match self.source.read_bytes(buffer) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(EntropyError::from(e)),
+        }
+    }
+}
+
+impl<S: EntropyAssessment> EntropyAssessment for Unified<S>
+where
+    EntropyError: From<S::EntropySourceError>,
+{
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.source.provenance()
+    }
+}
+
+/// Classify an I/O error by its `std::io::ErrorKind`.
+#[cfg(any(
+    feature = "af-alg",
+    feature = "camera",
+    feature = "egd",
+    feature = "export",
+    feature = "feeder",
+    feature = "hwrng",
+    feature = "net-jitter",
+    feature = "tpm"
+))]
+pub(crate) fn io_kind(error: &std::io::Error) -> ErrorKind {
+    use std::io::ErrorKind as Io;
+
+    match error.kind() {
+        Io::Unsupported | Io::NotFound | Io::PermissionDenied => ErrorKind::Unsupported,
+        Io::Interrupted | Io::WouldBlock | Io::OutOfMemory => ErrorKind::Transient,
+        Io::TimedOut => ErrorKind::Timeout,
+        Io::UnexpectedEof => ErrorKind::Exhausted,
+        _ => ErrorKind::Backend(error.raw_os_error().unwrap_or(0)),
+    }
+}
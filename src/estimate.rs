@@ -0,0 +1,300 @@
+//! SP 800-90B min-entropy estimators for characterizing raw noise sources.
+//!
+//! NOTE: Collect at least 1,000,000 raw (unconditioned) samples for a meaningful estimate. The collision, Markov, and
+//! compression estimators are only defined for binary data, so non-binary samples are also estimated as a bitstring.
+
+use std::error::Error;
+use std::vec;
+use std::vec::Vec;
+
+use crate::error::{Classify, ErrorKind};
+use crate::EntropySource;
+
+/// Upper bound of the 99% confidence interval (`Z_(1 - 0.005)`).
+const Z_ALPHA: f64 = 2.576;
+
+/// Compression estimator block size in bits.
+const COMPRESSION_BLOCK: usize = 6;
+
+/// Compression estimator dictionary initialization blocks.
+const COMPRESSION_DICTIONARY: usize = 1000;
+
+/// Number of bisection steps when solving for a probability.
+const SOLVER_STEPS: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EstimateError {
+    /// Too few samples to run every estimator.
+    TooFewSamples,
+    /// Bits per sample must be between 1 and 8.
+    InvalidBitsPerSample,
+}
+
+impl core::fmt::Display for EstimateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for EstimateError {}
+
+/// NOTE: Both variants are misconfigurations of the estimate, which no retry fixes.
+impl Classify for EstimateError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::TooFewSamples | Self::InvalidBitsPerSample => ErrorKind::Unsupported,
+        }
+    }
+}
+
+/// The results of every estimator; `min_entropy` is the figure to use.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntropyEstimate {
+    /// Most common value estimate, in bits per sample.
+    pub most_common_value: f64,
+    /// Collision estimate over the bitstring, in bits per bit.
+    pub collision: f64,
+    /// Markov estimate over the bitstring, in bits per bit.
+    pub markov: f64,
+    /// Compression estimate over the bitstring, in bits per bit.
+    pub compression: f64,
+    /// The final min-entropy estimate, in bits per sample.
+    pub min_entropy: f64,
+}
+
+/// Collect raw samples (one byte each) from a source.
+pub fn collect<S: EntropySource>(source: &S, samples: usize) -> Result<Vec<u8>, S::EntropySourceError> {
+    let mut buffer = vec![0u8; samples];
+
+    match source.read_bytes(&mut buffer) {
+        Ok(_) => Ok(buffer),
+        Err(e) => Err(e),
+    }
+}
+
+/// Run every estimator over samples holding `bits_per_sample` bits each (higher bits are ignored).
+pub fn estimate(samples: &[u8], bits_per_sample: u8) -> Result<EntropyEstimate, EstimateError> {
+    if !(1..=8).contains(&bits_per_sample) {
+        return Err(EstimateError::InvalidBitsPerSample);
+    }
+
+    let mask = (0xffu16 >> (8 - bits_per_sample)) as u8;
+    let samples: Vec<u8> = samples.iter().map(|sample| sample & mask).collect();
+
+    let bits: Vec<u8> = samples
+        .iter()
+        .flat_map(|sample| (0..bits_per_sample).rev().map(move |i| (sample >> i) & 1))
+        .collect();
+
+    if bits.len() < COMPRESSION_BLOCK * (COMPRESSION_DICTIONARY + 2) {
+        return Err(EstimateError::TooFewSamples);
+    }
+
+    let most_common_value = self::most_common_value(&samples);
+    let collision = self::collision(&bits);
+    let markov = self::markov(&bits);
+    let compression = self::compression(&bits);
+
+    let bitstring = match bits_per_sample {
+        1 => most_common_value,
+        _ => self::most_common_value(&bits),
+    };
+
+    let bitstring = bitstring.min(collision).min(markov).min(compression);
+
+    Ok(EntropyEstimate {
+        most_common_value,
+        collision,
+        markov,
+        compression,
+        min_entropy: most_common_value.min(bits_per_sample as f64 * bitstring),
+    })
+}
+
+/// The most common value estimate (SP 800-90B 6.3.1), in bits per sample.
+pub fn most_common_value(samples: &[u8]) -> f64 {
+    if samples.len() < 2 {
+        return 0.0;
+    }
+
+    let mut counts = [0usize; 256];
+
+    for sample in samples {
+        counts[*sample as usize] += 1;
+    }
+
+    let length = samples.len() as f64;
+    let p = *counts.iter().max().unwrap_or(&0) as f64 / length;
+    let p_u = (p + Z_ALPHA * (p * (1.0 - p) / (length - 1.0)).sqrt()).min(1.0);
+
+    -p_u.log2()
+}
+
+/// The collision estimate (SP 800-90B 6.3.2) over binary samples (each 0 or 1), in bits per bit.
+pub fn collision(bits: &[u8]) -> f64 {
+    let mut times = Vec::new();
+    let mut index = 0;
+
+    while index + 1 < bits.len() {
+        let time = match bits[index] == bits[index + 1] {
+            true => 2,
+            false if index + 2 < bits.len() => 3,
+            false => break,
+        };
+
+        times.push(time as f64);
+        index += time;
+    }
+
+    if times.len() < 2 {
+        return 0.0;
+    }
+
+    let v = times.len() as f64;
+    let mean = times.iter().sum::<f64>() / v;
+    let deviation = (times.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (v - 1.0)).sqrt();
+    let mean = mean - Z_ALPHA * deviation / v.sqrt();
+
+    // NOTE: The expected collision time falls from 2.5 (p = 0.5) to 2 (p = 1), using `F(q) = 2q^3 + 2q^2 + q`.
+    let expected = |p: f64| {
+        let q = 1.0 - p;
+        let f = 2.0 * q.powi(3) + 2.0 * q.powi(2) + q;
+
+        p / q.powi(2) * (1.0 + 0.5 * (1.0 / p - 1.0 / q)) * f - p / q * 0.5 * (1.0 / p - 1.0 / q)
+    };
+
+    match solve(expected, mean, 0.5, 1.0) {
+        Some(p) => -p.log2(),
+        None => 1.0,
+    }
+}
+
+/// The Markov estimate (SP 800-90B 6.3.3) over binary samples (each 0 or 1), in bits per bit.
+pub fn markov(bits: &[u8]) -> f64 {
+    if bits.len() < 2 {
+        return 0.0;
+    }
+
+    let mut transitions = [[0usize; 2]; 2];
+
+    for pair in bits.windows(2) {
+        transitions[pair[0] as usize][pair[1] as usize] += 1;
+    }
+
+    let ones = bits.iter().filter(|&&bit| bit == 1).count();
+    let p1 = ones as f64 / bits.len() as f64;
+    let p0 = 1.0 - p1;
+
+    let transition = |from: usize, to: usize| match transitions[from][0] + transitions[from][1] {
+        0 => 0.0,
+        total => transitions[from][to] as f64 / total as f64,
+    };
+
+    let (p00, p01, p10, p11) = (transition(0, 0), transition(0, 1), transition(1, 0), transition(1, 1));
+
+    // NOTE: Log-probabilities of the most likely 128-bit sequences, to avoid underflow.
+    let log = |p: f64| p.log2();
+    let sequences = [
+        log(p0) + 127.0 * log(p00),
+        log(p0) + 64.0 * log(p01) + 63.0 * log(p10),
+        log(p0) + log(p01) + 126.0 * log(p11),
+        log(p1) + log(p10) + 126.0 * log(p00),
+        log(p1) + 64.0 * log(p10) + 63.0 * log(p01),
+        log(p1) + 127.0 * log(p11),
+    ];
+
+    let p_max = sequences.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    (-p_max / 128.0).min(1.0)
+}
+
+/// The compression estimate (SP 800-90B 6.3.4) over binary samples (each 0 or 1), in bits per bit.
+pub fn compression(bits: &[u8]) -> f64 {
+    let blocks: Vec<usize> = bits
+        .chunks_exact(COMPRESSION_BLOCK)
+        .map(|block| block.iter().fold(0, |value, bit| (value << 1) | *bit as usize))
+        .collect();
+
+    if blocks.len() < COMPRESSION_DICTIONARY + 2 {
+        return 0.0;
+    }
+
+    let mut dictionary = [0usize; 1 << COMPRESSION_BLOCK];
+
+    for (i, block) in blocks[..COMPRESSION_DICTIONARY].iter().enumerate() {
+        dictionary[*block] = i + 1;
+    }
+
+    let mut distances = Vec::with_capacity(blocks.len() - COMPRESSION_DICTIONARY);
+
+    for (i, block) in blocks.iter().enumerate().skip(COMPRESSION_DICTIONARY) {
+        let i = i + 1;
+
+        distances.push(match dictionary[*block] {
+            0 => i,
+            last => i - last,
+        });
+
+        dictionary[*block] = i;
+    }
+
+    let n = blocks.len();
+    let v = distances.len() as f64;
+    let logs: Vec<f64> = distances.iter().map(|d| (*d as f64).log2()).collect();
+    let mean = logs.iter().sum::<f64>() / v;
+    let deviation = 0.5907 * (logs.iter().map(|l| l * l).sum::<f64>() / (v - 1.0) - mean * mean).max(0.0).sqrt();
+    let mean = mean - Z_ALPHA * deviation / v.sqrt();
+
+    // NOTE: `G(z)` with the double sum over `t` and `u` reordered to a single pass over `u`.
+    let g = |z: f64| {
+        let mut sum = 0.0;
+        let mut power = 1.0;
+
+        for u in 1..=n {
+            let log = (u as f64).log2();
+
+            if u < n {
+                sum += log * z * z * power * (n - u.max(COMPRESSION_DICTIONARY)) as f64;
+            }
+
+            if u > COMPRESSION_DICTIONARY {
+                sum += log * z * power;
+            }
+
+            power *= 1.0 - z;
+
+            // NOTE: The remaining terms are negligible, and subnormal arithmetic is very slow.
+            if power < f64::MIN_POSITIVE {
+                break;
+            }
+        }
+
+        sum / v
+    };
+
+    let symbols = ((1 << COMPRESSION_BLOCK) - 1) as f64;
+    let expected = |p: f64| g(p) + symbols * g((1.0 - p) / symbols);
+
+    match solve(expected, mean, 1.0 / (symbols + 1.0), 1.0) {
+        Some(p) => -p.log2() / COMPRESSION_BLOCK as f64,
+        None => 1.0,
+    }
+}
+
+/// Find `p` in `[low, high]` where the decreasing function `f(p)` equals `target`, or `None` if `f(low) < target`.
+fn solve(f: impl Fn(f64) -> f64, target: f64, mut low: f64, mut high: f64) -> Option<f64> {
+    if f(low) < target {
+        return None;
+    }
+
+    for _ in 0..SOLVER_STEPS {
+        let mid = (low + high) / 2.0;
+
+        match f(mid) > target {
+            true => low = mid,
+            false => high = mid,
+        }
+    }
+
+    Some((low + high) / 2.0)
+}
@@ -0,0 +1,322 @@
+//! Raw sample export for offline certification tooling: the NIST STS, dieharder, and the SP 800-90B `ea_*` tools.
+//!
+//! NOTE: Exported output is public by definition; never use a capture (or the bytes read for it) as key material.
+
+use core::convert::Infallible;
+use core::fmt::Write as _;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, ErrorKind, Write};
+use std::path::Path;
+use std::string::String;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::vec::Vec;
+
+use crate::error::{self, io_kind, Classify};
+use crate::json::Json;
+use crate::EntropySource;
+
+/// Bytes read from the source per call while capturing.
+const CHUNK_SIZE: usize = 65536;
+
+/// Bits written per line in the `AsciiBits` format.
+const ASCII_LINE_BITS: u64 = 64;
+
+#[derive(Debug)]
+pub enum ExportError<E: Error = Infallible> {
+    /// Writing the capture or its metadata failed, or the capture does not match its format.
+    Io(std::io::Error),
+    /// The entropy source failed while capturing.
+    Source(E),
+}
+
+impl<E: Error> core::fmt::Display for ExportError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<E: Error> Error for ExportError<E> {}
+
+impl<E: Error + Classify> Classify for ExportError<E> {
+    fn kind(&self) -> error::ErrorKind {
+        match self {
+            Self::Io(e) => io_kind(e),
+            Self::Source(e) => e.kind(),
+        }
+    }
+}
+
+impl<E: Error> From<std::io::Error> for ExportError<E> {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// The file format of a capture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Raw bytes: NIST STS input mode 1, `dieharder -g 201`, or the SP 800-90B tools at 8 bits per sample.
+    Binary,
+    /// One ASCII `0` or `1` per bit (most significant first), 64 to a line: NIST STS input mode 0.
+    AsciiBits,
+    /// The dieharder `file_input` format (`-g 202`): a header, then one unsigned 32-bit integer per line.
+    ///
+    /// NOTE: The header records `count` integers, so exactly `4 * count` bytes must be written.
+    Dieharder { count: u64 },
+    /// One sample per byte keeping only the low bits (1 to 8): `ea_non_iid` and `ea_iid` at that many bits per symbol.
+    Samples(u8),
+}
+
+impl Format {
+    /// Get the bits per sample as the analysis tools count them.
+    pub fn bits_per_sample(&self) -> u8 {
+        match *self {
+            Self::Binary => 8,
+            Self::AsciiBits => 1,
+            Self::Dieharder { .. } => 32,
+            Self::Samples(bits) => bits.clamp(1, 8),
+        }
+    }
+
+    /// Get an example invocation of the analysis tools for a capture in this format.
+    pub fn usage(&self) -> String {
+        match *self {
+            Self::Binary => {
+                String::from("assess <bits> (input mode 1); dieharder -a -g 201 -f <file>; ea_non_iid <file> 8")
+            }
+            Self::AsciiBits => String::from("assess <bits> (input mode 0)"),
+            Self::Dieharder { .. } => String::from("dieharder -a -g 202 -f <file>"),
+            Self::Samples(_) => std::format!("ea_non_iid <file> {}", self.bits_per_sample()),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Binary => "binary",
+            Self::AsciiBits => "ascii-bits",
+            Self::Dieharder { .. } => "dieharder",
+            Self::Samples(_) => "samples",
+        }
+    }
+}
+
+/// A description of a capture, for the sidecar file handed to the analysis tools alongside it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Metadata {
+    /// The type name of the source, if captured with `SampleWriter::capture`.
+    pub source: Option<&'static str>,
+    pub format: Format,
+    /// Number of bytes read from the source.
+    pub bytes: u64,
+    /// Number of samples written, as the analysis tools count them.
+    pub samples: u64,
+    /// When the capture was started, in seconds since the Unix epoch.
+    pub started: u64,
+    /// The version of this crate.
+    pub crate_version: &'static str,
+}
+
+impl Metadata {
+    /// Serialize the metadata as a JSON object.
+    pub fn to_json(&self) -> String {
+        let source = match self.source {
+            Some(source) => std::format!("{}", Json(source)),
+            None => String::from("null"),
+        };
+
+        let mut json = String::new();
+
+        // NOTE: Writing to a `String` cannot fail.
+        let _ = write!(json, "{{\"source\":{},\"format\":{},", source, Json(self.format.name()));
+        let _ = write!(json, "\"bits_per_sample\":{},", self.format.bits_per_sample());
+        let _ = write!(json, "\"bytes\":{},\"samples\":{},", self.bytes, self.samples);
+        let _ = write!(json, "\"started\":{},", self.started);
+        let _ = write!(json, "\"crate_version\":{},", Json(self.crate_version));
+        let _ = write!(json, "\"usage\":{}}}", Json(self.format.usage()));
+
+        json
+    }
+}
+
+/// Streams raw source output into one of the formats expected by offline analysis tools.
+///
+/// NOTE: Call `finish` when done; it completes the last line and checks the capture matches its format.
+pub struct SampleWriter<W: Write> {
+    writer: W,
+    format: Format,
+    source: Option<&'static str>,
+    bytes: u64,
+    word: [u8; 4],
+    started: SystemTime,
+    header: bool,
+}
+
+impl<W: Write> SampleWriter<W> {
+    /// Write a capture in the given format.
+    pub fn new(writer: W, format: Format) -> Self {
+        Self {
+            writer,
+            format,
+            source: None,
+            bytes: 0,
+            word: [0; 4],
+            started: SystemTime::now(),
+            header: false,
+        }
+    }
+
+    /// Read `bytes` bytes from a source and write them.
+    pub fn capture<S: EntropySource>(
+        &mut self,
+        source: &S,
+        bytes: u64,
+    ) -> Result<(), ExportError<S::EntropySourceError>> {
+        self.source = Some(core::any::type_name::<S>());
+
+        let mut buffer = std::vec![0u8; (CHUNK_SIZE as u64).min(bytes) as usize];
+        let mut remaining = bytes;
+
+        while remaining > 0 {
+            let chunk = &mut buffer[..(CHUNK_SIZE as u64).min(remaining) as usize];
+
+            if let Err(e) = source.read_bytes(chunk) {
+                return Err(ExportError::Source(e));
+            }
+
+            self.write(chunk)?;
+
+            remaining -= chunk.len() as u64;
+        }
+
+        Ok(())
+    }
+
+    /// Write raw source output obtained elsewhere.
+    pub fn write(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        if !self.header {
+            self.header = true;
+
+            if let Format::Dieharder { count } = self.format {
+                write!(
+                    self.writer,
+                    "#==================================================================\n\
+                     # generator librypt-entropy\n\
+                     #==================================================================\n\
+                     type: d\ncount: {}\nnumbit: 32\n",
+                    count
+                )?;
+            }
+        }
+
+        if let Format::Dieharder { count } = self.format {
+            if self.bytes + bytes.len() as u64 > count * 4 {
+                return Err(std::io::Error::new(
+                    ErrorKind::InvalidInput,
+                    "more integers than the header records",
+                ));
+            }
+        }
+
+        let mut output = Vec::with_capacity(bytes.len() * 9);
+
+        for &byte in bytes {
+            match self.format {
+                Format::Binary => output.push(byte),
+                Format::AsciiBits => {
+                    output.extend((0..8).rev().map(|i| b'0' + ((byte >> i) & 1)));
+
+                    if (self.bytes + 1) * 8 % ASCII_LINE_BITS == 0 {
+                        output.push(b'\n');
+                    }
+                }
+                Format::Dieharder { .. } => {
+                    self.word[(self.bytes % 4) as usize] = byte;
+
+                    if self.bytes % 4 == 3 {
+                        // NOTE: Writing to a `Vec` cannot fail.
+                        let _ = writeln!(output, "{}", u32::from_be_bytes(self.word));
+                    }
+                }
+                Format::Samples(_) => output.push(byte & (0xffu16 >> (8 - self.format.bits_per_sample())) as u8),
+            }
+
+            self.bytes += 1;
+        }
+
+        self.writer.write_all(&output)
+    }
+
+    /// Get the number of bytes written so far.
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Get the metadata of the capture so far.
+    pub fn metadata(&self) -> Metadata {
+        let samples = match self.format {
+            Format::Binary | Format::Samples(_) => self.bytes,
+            Format::AsciiBits => self.bytes * 8,
+            Format::Dieharder { .. } => self.bytes / 4,
+        };
+
+        Metadata {
+            source: self.source,
+            format: self.format,
+            bytes: self.bytes,
+            samples,
+            started: self
+                .started
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |started| started.as_secs()),
+            crate_version: env!("CARGO_PKG_VERSION"),
+        }
+    }
+
+    /// Complete the capture and flush it, returning the writer.
+    ///
+    /// NOTE: A dieharder capture fails unless exactly the number of integers in its header was written.
+    pub fn finish(mut self) -> std::io::Result<W> {
+        match self.format {
+            Format::AsciiBits if self.bytes * 8 % ASCII_LINE_BITS != 0 => self.writer.write_all(b"\n")?,
+            Format::Dieharder { count } if self.bytes != count * 4 => {
+                return Err(std::io::Error::new(
+                    ErrorKind::InvalidData,
+                    "fewer integers than the header records",
+                ))
+            }
+            Format::Dieharder { .. } if !self.header => self.write(&[])?,
+            _ => {}
+        }
+
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+/// Capture `bytes` bytes from a source into a file, with its metadata in a JSON sidecar at the same path plus `.json`.
+pub fn capture_file<S: EntropySource>(
+    source: &S,
+    path: impl AsRef<Path>,
+    format: Format,
+    bytes: u64,
+) -> Result<Metadata, ExportError<S::EntropySourceError>> {
+    let path = path.as_ref();
+
+    let mut writer = SampleWriter::new(BufWriter::new(File::create(path)?), format);
+
+    writer.capture(source, bytes)?;
+
+    let metadata = writer.metadata();
+
+    writer.finish()?;
+
+    let mut sidecar = std::ffi::OsString::from(path);
+
+    sidecar.push(".json");
+
+    std::fs::write(sidecar, metadata.to_json())?;
+
+    Ok(metadata)
+}
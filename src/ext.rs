@@ -0,0 +1,220 @@
+//! Convenience methods for every `EntropySource`.
+
+use core::ops::{Bound, RangeBounds};
+
+use crate::{Entropy, EntropySource};
+
+/// An unsigned integer type that `EntropySourceExt::gen_range` can sample.
+pub trait UniformInt: Copy + PartialOrd {
+    const MIN: Self;
+    const MAX: Self;
+
+    fn to_u128(self) -> u128;
+
+    /// NOTE: Only called with values that fit in `Self`.
+    fn from_u128(value: u128) -> Self;
+}
+
+macro_rules! uniform_int {
+    ($($t:ty),*) => {
+        $(
+            impl UniformInt for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn to_u128(self) -> u128 {
+                    self as u128
+                }
+
+                fn from_u128(value: u128) -> Self {
+                    value as $t
+                }
+            }
+        )*
+    };
+}
+
+uniform_int!(u8, u16, u32, u64, u128, usize);
+
+/// Extension methods implemented for every `EntropySource`.
+pub trait EntropySourceExt: EntropySource {
+    /// Iterate over successive `Entropy<LENGTH>` values generated from this source.
+    ///
+    /// NOTE: The iterator never ends; a failed generation yields an `Err` item and the next item tries again.
+    fn iter<const LENGTH: usize>(&self) -> EntropyIter<'_, Self, LENGTH>
+    where
+        Self: Sized,
+    {
+        EntropyIter { source: self }
+    }
+
+    /// Attempt to generate a uniformly random `u32`.
+    fn next_u32(&self) -> Result<u32, Self::EntropySourceError> {
+        let mut bytes = [0u8; 4];
+
+        self.read_bytes(&mut bytes)?;
+
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    /// Attempt to generate a uniformly random `u64`.
+    fn next_u64(&self) -> Result<u64, Self::EntropySourceError> {
+        let mut bytes = [0u8; 8];
+
+        self.read_bytes(&mut bytes)?;
+
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    /// Attempt to generate a uniformly random `u128`.
+    fn next_u128(&self) -> Result<u128, Self::EntropySourceError> {
+        let mut bytes = [0u8; 16];
+
+        self.read_bytes(&mut bytes)?;
+
+        Ok(u128::from_le_bytes(bytes))
+    }
+
+    /// Attempt to generate a uniformly random integer in the given range (e.g. `0..n` or `1..=6`).
+    ///
+    /// NOTE: Uses rejection sampling, so there is no modulo bias. This function will panic if the range is empty.
+    fn gen_range<T: UniformInt>(&self, range: impl RangeBounds<T>) -> Result<T, Self::EntropySourceError>
+    where
+        Self: Sized,
+    {
+        let low = match range.start_bound() {
+            Bound::Included(low) => low.to_u128(),
+            Bound::Excluded(low) => low.to_u128().checked_add(1).expect("empty range"),
+            Bound::Unbounded => T::MIN.to_u128(),
+        };
+
+        let high = match range.end_bound() {
+            Bound::Included(high) => high.to_u128(),
+            Bound::Excluded(high) => high.to_u128().checked_sub(1).expect("empty range"),
+            Bound::Unbounded => T::MAX.to_u128(),
+        };
+
+        assert!(low <= high, "empty range");
+
+        let offset = match (high - low).checked_add(1) {
+            None => self.next_u128()?,
+            Some(span) if span <= 1 << 64 => uniform_u64(self, span as u64)? as u128,
+            Some(span) => uniform_u128(self, span)?,
+        };
+
+        Ok(T::from_u128(low + offset))
+    }
+
+    /// Attempt to generate `true` with probability `p`.
+    ///
+    /// NOTE: This function will panic if `p` is not between 0 and 1.
+    fn gen_bool(&self, p: f64) -> Result<bool, Self::EntropySourceError> {
+        assert!((0.0..=1.0).contains(&p), "probability must be between 0 and 1");
+
+        if p == 1.0 {
+            return Ok(true);
+        }
+
+        // NOTE: `p < 1`, so the threshold fits in a `u64`.
+        let threshold = (p * 18446744073709551616.0) as u64;
+
+        Ok(self.next_u64()? < threshold)
+    }
+
+    /// Attempt to shuffle the items in place (Fisher-Yates), every permutation being equally likely.
+    fn shuffle<T>(&self, items: &mut [T]) -> Result<(), Self::EntropySourceError>
+    where
+        Self: Sized,
+    {
+        for index in (1..items.len()).rev() {
+            items.swap(index, self.gen_range(0..=index)?);
+        }
+
+        Ok(())
+    }
+
+    /// Attempt to choose one of the items uniformly (`None` if there are none).
+    fn choose<'a, T>(&self, items: &'a [T]) -> Result<Option<&'a T>, Self::EntropySourceError>
+    where
+        Self: Sized,
+    {
+        match items.len() {
+            0 => Ok(None),
+            length => Ok(Some(&items[self.gen_range(0..length)?])),
+        }
+    }
+
+    /// Attempt to choose `amount` distinct items uniformly (or all of them, if there are fewer), in random order.
+    #[cfg(feature = "alloc")]
+    fn choose_multiple<'a, T>(
+        &self,
+        items: &'a [T],
+        amount: usize,
+    ) -> Result<alloc::vec::Vec<&'a T>, Self::EntropySourceError>
+    where
+        Self: Sized,
+    {
+        let amount = amount.min(items.len());
+
+        let mut indices: alloc::vec::Vec<usize> = (0..items.len()).collect();
+
+        // NOTE: A partial Fisher-Yates shuffle; only the first `amount` positions are settled.
+        for index in 0..amount {
+            indices.swap(index, self.gen_range(index..items.len())?);
+        }
+
+        Ok(indices[..amount].iter().map(|index| &items[*index]).collect())
+    }
+}
+
+/// Sample uniformly from `0..span`, where `span` may be `2^64` (passed as `0`).
+fn uniform_u64<S: EntropySource>(source: &S, span: u64) -> Result<u64, S::EntropySourceError> {
+    if span == 0 {
+        return source.next_u64();
+    }
+
+    // NOTE: Values below `2^64 mod span` are rejected, leaving a multiple of `span` equally likely values.
+    let threshold = span.wrapping_neg() % span;
+
+    loop {
+        let value = source.next_u64()?;
+
+        if value >= threshold {
+            return Ok(value % span);
+        }
+    }
+}
+
+/// Sample uniformly from `0..span`.
+fn uniform_u128<S: EntropySource>(source: &S, span: u128) -> Result<u128, S::EntropySourceError> {
+    let threshold = span.wrapping_neg() % span;
+
+    loop {
+        let value = source.next_u128()?;
+
+        if value >= threshold {
+            return Ok(value % span);
+        }
+    }
+}
+
+impl<S: EntropySource> EntropySourceExt for S {}
+
+/// An endless iterator of `Entropy<LENGTH>` values, created by `EntropySourceExt::iter`.
+pub struct EntropyIter<'a, S: EntropySource, const LENGTH: usize> {
+    source: &'a S,
+}
+
+impl<S: EntropySource, const LENGTH: usize> Iterator for EntropyIter<'_, S, LENGTH> {
+    type Item = Result<Entropy<LENGTH>, S::EntropySourceError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(Entropy::try_generate(self.source))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl<S: EntropySource, const LENGTH: usize> core::iter::FusedIterator for EntropyIter<'_, S, LENGTH> {}
@@ -0,0 +1,266 @@
+//! Randomness extractors for biased raw bit streams.
+
+use core::error::Error;
+
+use zeroize::Zeroize;
+
+use crate::assess::{self, Basis, EntropyAssessment, Provenance};
+use crate::error::{Classify, ErrorKind};
+use crate::trace;
+use crate::EntropySource;
+
+/// Default limit on raw input bytes consumed per output byte before an extractor gives up.
+pub const DEFAULT_MAX_RATIO: usize = 64;
+
+/// Size of the chunks read from the raw source.
+const CHUNK_SIZE: usize = 64;
+
+#[derive(Debug)]
+pub enum ExtractError<E: Error> {
+    /// The raw source failed.
+    Source(E),
+    /// The raw source produced too few usable bits (e.g. a stuck-at fault).
+    Stalled,
+    /// The Toeplitz seed has the wrong length.
+    InvalidSeed,
+}
+
+impl<E: Error> core::fmt::Display for ExtractError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<E: Error> Error for ExtractError<E> {}
+
+impl<E: Error + Classify> Classify for ExtractError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Source(e) => e.kind(),
+            Self::Stalled => ErrorKind::HealthCheckFailed,
+            Self::InvalidSeed => ErrorKind::Backend(0),
+        }
+    }
+}
+
+fn bit(bytes: &[u8], index: usize) -> u8 {
+    (bytes[index / 8] >> (index % 8)) & 1
+}
+
+/// The von Neumann extractor: turns independent but biased bits into unbiased ones.
+///
+/// Raw bits are taken in pairs; `01` yields `0`, `10` yields `1`, and equal pairs are discarded. At best a quarter
+/// of the raw bits survive.
+///
+/// NOTE: This removes bias, not correlation. Sources whose bits depend on each other (most ring oscillators
+/// sampled too fast) still need conditioning afterwards.
+pub struct VonNeumann<S: EntropySource> {
+    source: S,
+    max_ratio: usize,
+}
+
+impl<S: EntropySource> VonNeumann<S> {
+    /// Debias a raw source.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            max_ratio: DEFAULT_MAX_RATIO,
+        }
+    }
+
+    /// Set the number of raw bytes per output byte after which reading fails with `Stalled` (at least 4).
+    pub fn with_max_ratio(mut self, max_ratio: usize) -> Self {
+        self.max_ratio = max_ratio.max(4);
+        self
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySource for VonNeumann<S> {
+    type EntropySourceError = ExtractError<S::EntropySourceError>;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        let limit = buffer.len().saturating_mul(self.max_ratio);
+
+        let mut raw = [0u8; CHUNK_SIZE];
+        let mut consumed = 0;
+        let mut written = 0;
+        let mut current = 0u8;
+        let mut bits = 0;
+
+        buffer.fill(0);
+
+        let result = loop {
+            if written == buffer.len() {
+                break Ok(());
+            }
+
+            if consumed >= limit {
+                break Err(ExtractError::Stalled);
+            }
+
+            if let Err(e) = self.source.read_bytes(&mut raw) {
+                break Err(ExtractError::Source(e));
+            }
+
+            consumed += CHUNK_SIZE;
+
+            for pair in 0..CHUNK_SIZE * 4 {
+                let (a, b) = (bit(&raw, pair * 2), bit(&raw, pair * 2 + 1));
+
+                if a == b {
+                    continue;
+                }
+
+                current |= a << bits;
+                bits += 1;
+
+                if bits == 8 {
+                    buffer[written] = current;
+
+                    written += 1;
+                    current = 0;
+                    bits = 0;
+
+                    if written == buffer.len() {
+                        break;
+                    }
+                }
+            }
+        };
+
+        raw.zeroize();
+        current.zeroize();
+
+        if result.is_err() {
+            buffer.fill(0);
+        }
+
+        result
+    }
+}
+
+/// NOTE: An output byte consumes 4 raw bytes on average even from an unbiased source (more from a biased one), so it
+/// is credited with the entropy of 4 raw bytes, capped at full entropy. This assumes the raw bits are independent, as
+/// the extractor itself does.
+impl<S: EntropyAssessment> EntropyAssessment for VonNeumann<S> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        (4.0 * self.source.min_entropy_per_byte()).min(8.0)
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            basis: Basis::Derived,
+            ..self.source.provenance()
+        }
+    }
+}
+
+/// A Toeplitz hashing extractor, compressing `INPUT` raw bytes into `OUTPUT` bytes per block.
+///
+/// The matrix is defined by a seed of `INPUT + OUTPUT` bytes, which must be uniformly random and independent of the
+/// raw source, but need not be secret. By the leftover hash lemma, each output block is close to uniform as long as
+/// its raw input carries comfortably more than `8 * OUTPUT` bits of min-entropy.
+pub struct Toeplitz<S: EntropySource, const INPUT: usize, const OUTPUT: usize> {
+    source: S,
+    input_seed: [u8; INPUT],
+    output_seed: [u8; OUTPUT],
+}
+
+impl<S: EntropySource, const INPUT: usize, const OUTPUT: usize> Toeplitz<S, INPUT, OUTPUT> {
+    /// Attempt to create a new extractor from a seed of exactly `INPUT + OUTPUT` bytes.
+    pub fn new(source: S, seed: &[u8]) -> Result<Self, ExtractError<S::EntropySourceError>> {
+        if INPUT == 0 || OUTPUT == 0 || seed.len() != INPUT + OUTPUT {
+            return Err(ExtractError::InvalidSeed);
+        }
+
+        let mut input_seed = [0u8; INPUT];
+        let mut output_seed = [0u8; OUTPUT];
+
+        input_seed.copy_from_slice(&seed[..INPUT]);
+        output_seed.copy_from_slice(&seed[INPUT..]);
+
+        Ok(Self {
+            source,
+            input_seed,
+            output_seed,
+        })
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Get bit `index` of the diagonal sequence defining the matrix.
+    fn diagonal(&self, index: usize) -> u8 {
+        match index.checked_sub(INPUT * 8) {
+            Some(index) => bit(&self.output_seed, index),
+            None => bit(&self.input_seed, index),
+        }
+    }
+
+    /// Multiply one block of raw input by the matrix.
+    fn extract(&self, input: &[u8; INPUT], output: &mut [u8; OUTPUT]) {
+        let n = INPUT * 8;
+
+        output.fill(0);
+
+        for i in 0..OUTPUT * 8 {
+            let mut parity = 0;
+
+            for j in 0..n {
+                parity ^= self.diagonal(i + n - 1 - j) & bit(input, j);
+            }
+
+            output[i / 8] |= parity << (i % 8);
+        }
+    }
+}
+
+unsafe impl<S: EntropySource, const INPUT: usize, const OUTPUT: usize> EntropySource for Toeplitz<S, INPUT, OUTPUT> {
+    type EntropySourceError = S::EntropySourceError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        let mut input = [0u8; INPUT];
+        let mut output = [0u8; OUTPUT];
+
+        let result = buffer.chunks_mut(OUTPUT).try_for_each(|chunk| {
+            self.source.read_bytes(&mut input)?;
+
+            self.extract(&input, &mut output);
+
+            chunk.copy_from_slice(&output[..chunk.len()]);
+
+            Ok(())
+        });
+
+        input.zeroize();
+        output.zeroize();
+
+        result
+    }
+}
+
+/// NOTE: Each output block is credited like the output of a conditioning function fed the entropy of its `INPUT` raw
+/// bytes: full entropy with a 64-bit margin, otherwise no more than went in.
+impl<S: EntropyAssessment, const INPUT: usize, const OUTPUT: usize> EntropyAssessment for Toeplitz<S, INPUT, OUTPUT> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        assess::conditioned(self.source.min_entropy_per_byte() * INPUT as f64, OUTPUT)
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            basis: Basis::Derived,
+            ..self.source.provenance()
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! Feeding entropy from an `EntropySource` into the Linux kernel pool.
+
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+use std::os::fd::AsRawFd;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::vec;
+
+use crate::error::{io_kind, Classify, ErrorKind};
+use crate::EntropySource;
+
+/// `RNDADDENTROPY`: `_IOW('R', 0x03, int[2])`.
+const RNDADDENTROPY: libc::c_ulong = 0x4008_5203;
+
+/// Default number of bytes fed per interval.
+const DEFAULT_CHUNK_SIZE: usize = 64;
+
+/// Default time between feeds.
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Granularity at which a background feeder checks whether it has been stopped.
+const STOP_POLL: Duration = Duration::from_millis(100);
+
+#[derive(Debug)]
+pub enum FeederError<E: Error> {
+    /// The entropy source failed.
+    Source(E),
+    /// Opening `/dev/random` or the `RNDADDENTROPY` ioctl failed (which requires `CAP_SYS_ADMIN`).
+    Io(std::io::Error),
+}
+
+impl<E: Error> std::fmt::Display for FeederError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<E: Error> Error for FeederError<E> {}
+
+impl<E: Error + Classify> Classify for FeederError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Source(e) => e.kind(),
+            Self::Io(e) => io_kind(e),
+        }
+    }
+}
+
+/// Credits entropy from an `EntropySource` to the kernel pool via the `RNDADDENTROPY` ioctl, in the style of `rngd`.
+///
+/// NOTE: Each feed reads `chunk_size` bytes and credits `credit_per_byte` bits of entropy per byte (8 by default).
+/// Crediting more entropy than the source really provides weakens the kernel pool, so be conservative with noise
+/// sources.
+pub struct KernelFeeder<S: EntropySource> {
+    source: S,
+    device: File,
+    chunk_size: usize,
+    credit_per_byte: f64,
+    interval: Duration,
+}
+
+impl<S: EntropySource> KernelFeeder<S> {
+    /// Attempt to open `/dev/random` for feeding entropy from the provided `EntropySource`.
+    pub fn new(source: S) -> Result<Self, FeederError<S::EntropySourceError>> {
+        let device = match OpenOptions::new().write(true).open("/dev/random") {
+            Ok(device) => device,
+            Err(e) => return Err(FeederError::Io(e)),
+        };
+
+        Ok(Self {
+            source,
+            device,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            credit_per_byte: 8.0,
+            interval: DEFAULT_INTERVAL,
+        })
+    }
+
+    /// Set the number of bytes fed per interval (64 by default).
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Set the bits of entropy credited per byte fed, clamped to `0.0..=8.0` (8 by default).
+    pub fn with_credit_per_byte(mut self, credit_per_byte: f64) -> Self {
+        self.credit_per_byte = credit_per_byte.clamp(0.0, 8.0);
+        self
+    }
+
+    /// Set the time between feeds of a background feeder (1 second by default).
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Attempt to feed (and credit) a single chunk to the kernel.
+    pub fn feed_once(&self) -> Result<(), FeederError<S::EntropySourceError>> {
+        // `struct rand_pool_info { int entropy_count; int buf_size; __u32 buf[]; }`
+        let mut info = vec![0u32; 2 + self.chunk_size.div_ceil(4)];
+
+        // SAFETY: The words after the header are plain memory of at least `chunk_size` bytes.
+        let data = unsafe { core::slice::from_raw_parts_mut(info[2..].as_mut_ptr().cast::<u8>(), self.chunk_size) };
+
+        if let Err(e) = self.source.read_bytes(data) {
+            return Err(FeederError::Source(e));
+        }
+
+        info[0] = (self.chunk_size as f64 * self.credit_per_byte) as u32;
+        info[1] = self.chunk_size as u32;
+
+        // SAFETY: `info` is a valid `rand_pool_info` whose buffer holds `buf_size` bytes.
+        let result = unsafe { libc::ioctl(self.device.as_raw_fd(), RNDADDENTROPY as _, info.as_ptr()) };
+
+        info.fill(0);
+
+        match result {
+            0 => Ok(()),
+            _ => Err(FeederError::Io(std::io::Error::last_os_error())),
+        }
+    }
+
+    /// Feed the kernel every interval on a background thread until stopped or a feed fails.
+    pub fn spawn(self) -> FeederHandle<S::EntropySourceError>
+    where
+        S: Send + 'static,
+        S::EntropySourceError: Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+
+        let thread = std::thread::spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                self.feed_once()?;
+
+                let mut waited = Duration::ZERO;
+
+                while waited < self.interval && !stopped.load(Ordering::Relaxed) {
+                    let step = STOP_POLL.min(self.interval - waited);
+
+                    std::thread::sleep(step);
+
+                    waited += step;
+                }
+            }
+
+            Ok(())
+        });
+
+        FeederHandle { stop, thread }
+    }
+}
+
+/// A handle to a background `KernelFeeder`.
+pub struct FeederHandle<E: Error> {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<Result<(), FeederError<E>>>,
+}
+
+impl<E: Error> FeederHandle<E> {
+    /// Whether the feeder has stopped (because it was asked to, or because a feed failed).
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// Stop the feeder and wait for it, returning the error that stopped it (if any).
+    ///
+    /// NOTE: This function will panic if the feeder thread panicked.
+    pub fn stop(self) -> Result<(), FeederError<E>> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.thread.join().unwrap()
+    }
+}
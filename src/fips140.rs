@@ -0,0 +1,120 @@
+//! Statistical smoke tests for entropy sources.
+
+use crate::EntropySource;
+
+/// Number of bytes in a FIPS 140-2 test block (20,000 bits).
+pub const FIPS140_BLOCK_SIZE: usize = 2500;
+
+/// Accepted run counts for run lengths 1 to 5 and 6+, in either bit value.
+const RUN_INTERVALS: [(usize, usize); 6] = [(2315, 2685), (1114, 1386), (527, 723), (240, 384), (103, 209), (103, 209)];
+
+/// Minimum run length failing the long-run test.
+const LONG_RUN: usize = 26;
+
+/// The outcome of each FIPS 140-2 test over a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fips140Report {
+    pub monobit: bool,
+    pub poker: bool,
+    pub runs: bool,
+    pub long_run: bool,
+}
+
+impl Fips140Report {
+    /// Whether every test passed.
+    pub fn passed(&self) -> bool {
+        self.monobit && self.poker && self.runs && self.long_run
+    }
+}
+
+/// The FIPS 140-2 (Change Notice 1) power-up statistical tests over a 20,000-bit block.
+///
+/// NOTE: These were removed from FIPS 140-2 in 2002 and only detect gross failures (e.g. a stuck-at fault); a pass
+/// says nothing about the actual entropy of a source.
+pub struct Fips140;
+
+impl Fips140 {
+    /// Monobit test: the number of ones must be between 9,726 and 10,274.
+    pub fn monobit(block: &[u8; FIPS140_BLOCK_SIZE]) -> bool {
+        let ones: u32 = block.iter().map(|byte| byte.count_ones()).sum();
+
+        (9726..=10274).contains(&ones)
+    }
+
+    /// Poker test: the statistic over the 5,000 4-bit segments must be between 2.16 and 46.17.
+    pub fn poker(block: &[u8; FIPS140_BLOCK_SIZE]) -> bool {
+        let mut counts = [0u64; 16];
+
+        for byte in block {
+            counts[(byte >> 4) as usize] += 1;
+            counts[(byte & 0x0f) as usize] += 1;
+        }
+
+        // NOTE: `X = 16 / 5000 * sum(f^2) - 5000`, scaled by 5000 to stay in integers.
+        let x = 16 * counts.iter().map(|count| count * count).sum::<u64>() as i64 - 5000 * 5000;
+
+        10800 < x && x < 230850
+    }
+
+    /// Runs test: the number of runs of each length (1 to 5, and 6+) of both zeros and ones must be within bounds.
+    pub fn runs(block: &[u8; FIPS140_BLOCK_SIZE]) -> bool {
+        let mut counts = [[0usize; 6]; 2];
+
+        Self::for_each_run(block, |bit, length| counts[bit as usize][length.min(6) - 1] += 1);
+
+        counts
+            .iter()
+            .all(|counts| counts.iter().zip(RUN_INTERVALS).all(|(count, (low, high))| (low..=high).contains(count)))
+    }
+
+    /// Long-run test: there must be no run of 26 or more identical bits.
+    pub fn long_run(block: &[u8; FIPS140_BLOCK_SIZE]) -> bool {
+        let mut longest = 0;
+
+        Self::for_each_run(block, |_, length| longest = longest.max(length));
+
+        longest < LONG_RUN
+    }
+
+    /// Run every test over a block.
+    pub fn evaluate(block: &[u8; FIPS140_BLOCK_SIZE]) -> Fips140Report {
+        Fips140Report {
+            monobit: Self::monobit(block),
+            poker: Self::poker(block),
+            runs: Self::runs(block),
+            long_run: Self::long_run(block),
+        }
+    }
+
+    /// Attempt to sample a block from the provided `EntropySource` and run every test over it.
+    pub fn run_against<S: EntropySource>(source: &S) -> Result<Fips140Report, S::EntropySourceError> {
+        let mut block = [0u8; FIPS140_BLOCK_SIZE];
+
+        source.read_bytes(&mut block)?;
+
+        let report = Self::evaluate(&block);
+
+        block.fill(0);
+
+        Ok(report)
+    }
+
+    /// Call `f(bit, length)` for every maximal run of identical bits (most significant bit first).
+    fn for_each_run(block: &[u8; FIPS140_BLOCK_SIZE], mut f: impl FnMut(u8, usize)) {
+        let mut bits = block.iter().flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1));
+        let mut current = bits.next().unwrap_or(0);
+        let mut length = 1;
+
+        for bit in bits {
+            if bit == current {
+                length += 1;
+            } else {
+                f(current, length);
+                current = bit;
+                length = 1;
+            }
+        }
+
+        f(current, length);
+    }
+}
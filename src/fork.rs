@@ -0,0 +1,106 @@
+//! Fork detection for stateful generators.
+//!
+//! NOTE: On Linux/Android a marker page mapped with `MADV_WIPEONFORK` turns the check into a single load; elsewhere
+//! on Unix (or on kernels before 4.14) the pid is compared instead. Other targets have no `fork` and never report one.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::trace;
+
+/// Incremented whenever a fork is detected in the current process.
+static EPOCH: AtomicUsize = AtomicUsize::new(0);
+
+/// Get the current fork epoch, which changes in a child process after every `fork`.
+///
+/// NOTE: Stateful generators record the epoch when seeded and must reseed (or wipe their state) when it changes.
+pub(crate) fn epoch() -> u64 {
+    match detect() {
+        true => {
+            trace::event!(info, "fork detected, generator state will be discarded");
+
+            EPOCH.fetch_add(1, Ordering::Relaxed) as u64 + 1
+        }
+        false => EPOCH.load(Ordering::Relaxed) as u64,
+    }
+}
+
+#[cfg(unix)]
+fn detect() -> bool {
+    use core::sync::atomic::AtomicI32;
+
+    static PID: AtomicI32 = AtomicI32::new(0);
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    if let Some(marker) = wipe_on_fork::marker() {
+        // NOTE: The kernel zeroes the page in the child; setting it again re-arms detection for the next fork.
+        return marker.swap(1, Ordering::Relaxed) == 0;
+    }
+
+    let pid = unsafe { libc::getpid() };
+    let previous = PID.swap(pid, Ordering::Relaxed);
+
+    previous != 0 && previous != pid
+}
+
+#[cfg(not(unix))]
+fn detect() -> bool {
+    false
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod wipe_on_fork {
+    use core::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+
+    /// Address of the marker page (0 before the first check, 1 if `MADV_WIPEONFORK` is unavailable).
+    static PAGE: AtomicUsize = AtomicUsize::new(0);
+
+    const UNAVAILABLE: usize = 1;
+
+    /// Get the marker byte (1 in the process that mapped it, 0 in a forked child), mapping it on first use.
+    pub(super) fn marker() -> Option<&'static AtomicU8> {
+        let page = match PAGE.load(Ordering::Acquire) {
+            0 => map(),
+            page => page,
+        };
+
+        match page {
+            UNAVAILABLE => None,
+            page => Some(unsafe { &*(page as *const AtomicU8) }),
+        }
+    }
+
+    fn map() -> usize {
+        let size = match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+            size if size > 0 => size as usize,
+            _ => 4096,
+        };
+
+        let flags = libc::MAP_PRIVATE | libc::MAP_ANONYMOUS;
+        let page = unsafe { libc::mmap(core::ptr::null_mut(), size, libc::PROT_READ | libc::PROT_WRITE, flags, -1, 0) };
+
+        let page = match page {
+            libc::MAP_FAILED => UNAVAILABLE,
+            page if unsafe { libc::madvise(page, size, libc::MADV_WIPEONFORK) } != 0 => {
+                unsafe { libc::munmap(page, size) };
+
+                UNAVAILABLE
+            }
+            page => {
+                unsafe { (*(page as *const AtomicU8)).store(1, Ordering::Relaxed) };
+
+                page as usize
+            }
+        };
+
+        match PAGE.compare_exchange(0, page, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => page,
+            Err(existing) => {
+                if page != UNAVAILABLE {
+                    unsafe { libc::munmap(page as *mut libc::c_void, size) };
+                }
+
+                existing
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! The process-wide default source, for code that cannot take a source parameter.
+
+use std::boxed::Box;
+use std::error::Error;
+use std::sync::OnceLock;
+
+use crate::erased::{ErasedEntropySource, ErasedError};
+use crate::error::{Classify, ErrorKind};
+use crate::trace;
+use crate::EntropySource;
+
+static DEFAULT: OnceLock<Box<dyn ErasedEntropySource + Send + Sync>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefaultSourceError {
+    /// The default source was already set, or already used (which fixes it to `OsEntropy`).
+    AlreadySet,
+    /// No default source was set, and without the `os` feature there is nothing to fall back to.
+    Unset,
+}
+
+impl core::fmt::Display for DefaultSourceError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for DefaultSourceError {}
+
+impl Classify for DefaultSourceError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::AlreadySet => ErrorKind::Backend(0),
+            Self::Unset => ErrorKind::Unsupported,
+        }
+    }
+}
+
+/// Set the process-wide default source, which can only be done once and before it is first used.
+///
+/// NOTE: Applications should set it early in `main`; libraries should only read it through `default_source`. Pass
+/// `source.erase_classified()` to keep the `ErrorKind` of its errors.
+pub fn set_default_source<S>(source: S) -> Result<(), DefaultSourceError>
+where
+    S: EntropySource + Send + Sync + 'static,
+    S::EntropySourceError: Send + Sync + 'static,
+{
+    match DEFAULT.set(Box::new(source)) {
+        Ok(()) => {
+            trace::event!(debug, source = core::any::type_name::<S>(), "default source set");
+
+            Ok(())
+        }
+        Err(_) => Err(DefaultSourceError::AlreadySet),
+    }
+}
+
+/// Get a handle to the process-wide default source.
+pub fn default_source() -> DefaultSource {
+    DefaultSource
+}
+
+/// A handle to the source set with `set_default_source`, or `OsEntropy` (with the `os` feature) if none was set
+/// before its first read.
+///
+/// NOTE: Without the `os` feature, reads fail with `DefaultSourceError::Unset` until a source is set.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultSource;
+
+impl DefaultSource {
+    fn get(&self) -> Option<&'static (dyn ErasedEntropySource + Send + Sync)> {
+        #[cfg(any(feature = "os", feature = "os-native"))]
+        let source = DEFAULT.get_or_init(|| crate::os::OsEntropy.erase_classified());
+
+        #[cfg(not(any(feature = "os", feature = "os-native")))]
+        let source = DEFAULT.get()?;
+
+        Some(&**source)
+    }
+}
+
+unsafe impl EntropySource for DefaultSource {
+    type EntropySourceError = ErasedError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        match self.get() {
+            Some(source) => source.read_bytes_erased(buffer),
+            None => Err(ErasedError::classified(DefaultSourceError::Unset)),
+        }
+    }
+}
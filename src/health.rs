@@ -0,0 +1,291 @@
+//! SP 800-90B continuous health tests.
+
+use core::error::Error;
+
+use crate::assess::{EntropyAssessment, Provenance};
+use crate::error::{Classify, ErrorKind};
+use crate::lock::Lock;
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+/// Repetition Count Test cutoffs for 1 to 8 bits of min-entropy per sample (`alpha = 2^-20`).
+const RCT_CUTOFFS: [usize; 8] = [21, 11, 8, 6, 5, 5, 4, 4];
+
+/// Adaptive Proportion Test window size for non-binary samples.
+const APT_WINDOW: usize = 1024;
+
+/// Adaptive Proportion Test cutoffs for 1 to 8 bits of min-entropy per sample (`alpha = 2^-20`, `W = 1024`).
+const APT_CUTOFFS: [usize; 8] = [589, 325, 182, 105, 63, 39, 26, 18];
+
+/// Repetition Count Test cutoff for timing noise sources (1 bit of min-entropy per sample, `alpha = 2^-30`).
+const TIMING_RCT_CUTOFF: usize = 31;
+
+/// Adaptive Proportion Test window size for timing noise sources.
+const TIMING_APT_WINDOW: usize = 512;
+
+/// Adaptive Proportion Test cutoff for timing noise sources (1 bit of min-entropy per sample, `alpha = 2^-30`,
+/// `W = 512`), as in jitterentropy's `JENT_APT_CUTOFF`.
+const TIMING_APT_CUTOFF: usize = 325;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthError {
+    /// The Repetition Count Test failed: the same sample was seen too many times in a row.
+    RepetitionCount,
+    /// The Adaptive Proportion Test failed: a single sample value dominated the test window.
+    AdaptiveProportion,
+}
+
+impl core::fmt::Display for HealthError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for HealthError {}
+
+impl Classify for HealthError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::HealthCheckFailed
+    }
+}
+
+/// Cutoff values for the continuous health tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthConfig {
+    rct_cutoff: usize,
+    apt_window: usize,
+    apt_cutoff: usize,
+}
+
+impl HealthConfig {
+    /// Create a configuration with custom cutoffs.
+    ///
+    /// NOTE: Cutoffs are clamped to at least 2, and the window to at least the APT cutoff.
+    pub const fn new(rct_cutoff: usize, apt_window: usize, apt_cutoff: usize) -> Self {
+        let rct_cutoff = if rct_cutoff < 2 { 2 } else { rct_cutoff };
+        let apt_cutoff = if apt_cutoff < 2 { 2 } else { apt_cutoff };
+        let apt_window = if apt_window < apt_cutoff { apt_cutoff } else { apt_window };
+
+        Self {
+            rct_cutoff,
+            apt_window,
+            apt_cutoff,
+        }
+    }
+
+    /// Create the SP 800-90B configuration for a claimed min-entropy per sample (in bits, clamped to 1..=8).
+    pub const fn for_min_entropy(bits: u8) -> Self {
+        let index = match bits {
+            0 => 0,
+            9.. => 7,
+            bits => bits as usize - 1,
+        };
+
+        Self::new(RCT_CUTOFFS[index], APT_WINDOW, APT_CUTOFFS[index])
+    }
+
+    /// Create the configuration for CPU timing noise sources such as `JitterSource`, which claim 1 bit of
+    /// min-entropy per measurement and use jitterentropy's false positive rate (`alpha = 2^-30`).
+    pub const fn for_timing_noise() -> Self {
+        Self::new(TIMING_RCT_CUTOFF, TIMING_APT_WINDOW, TIMING_APT_CUTOFF)
+    }
+
+    /// Get the Repetition Count Test cutoff.
+    pub fn rct_cutoff(&self) -> usize {
+        self.rct_cutoff
+    }
+
+    /// Get the Adaptive Proportion Test window size.
+    pub fn apt_window(&self) -> usize {
+        self.apt_window
+    }
+
+    /// Get the Adaptive Proportion Test cutoff.
+    pub fn apt_cutoff(&self) -> usize {
+        self.apt_cutoff
+    }
+}
+
+/// The state of the Repetition Count and Adaptive Proportion tests over a stream of samples.
+///
+/// NOTE: A failure is latched; every later sample fails with the same error.
+#[derive(Debug, Clone)]
+pub struct HealthTests {
+    config: HealthConfig,
+    last: Option<u64>,
+    rct_count: usize,
+    apt_base: u64,
+    apt_count: usize,
+    apt_samples: usize,
+    failure: Option<HealthError>,
+}
+
+impl HealthTests {
+    /// Create a fresh test state.
+    pub const fn new(config: HealthConfig) -> Self {
+        Self {
+            config,
+            last: None,
+            rct_count: 0,
+            apt_base: 0,
+            apt_count: 0,
+            apt_samples: 0,
+            failure: None,
+        }
+    }
+
+    /// Run both tests on a single sample.
+    pub fn test(&mut self, sample: u64) -> Result<(), HealthError> {
+        if let Some(e) = self.failure {
+            return Err(e);
+        }
+
+        if self.last == Some(sample) {
+            self.rct_count += 1;
+
+            if self.rct_count >= self.config.rct_cutoff {
+                self.failure = Some(HealthError::RepetitionCount);
+            }
+        } else {
+            self.rct_count = 1;
+        }
+
+        self.last = Some(sample);
+
+        if self.apt_samples == 0 {
+            self.apt_base = sample;
+            self.apt_count = 1;
+        } else if sample == self.apt_base {
+            self.apt_count += 1;
+
+            if self.apt_count >= self.config.apt_cutoff {
+                self.failure = Some(HealthError::AdaptiveProportion);
+            }
+        }
+
+        self.apt_samples = (self.apt_samples + 1) % self.config.apt_window;
+
+        match self.failure {
+            Some(e) => {
+                trace::event!(error, test = ?e, "health test failed");
+
+                Err(e)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Get the latched failure, if any.
+    pub fn failure(&self) -> Option<HealthError> {
+        self.failure
+    }
+
+    /// Get the configuration.
+    pub fn config(&self) -> HealthConfig {
+        self.config
+    }
+}
+
+#[derive(Debug)]
+pub enum MonitoredError<E: Error> {
+    /// The wrapped source failed.
+    Source(E),
+    /// A health test failed, now or during an earlier read.
+    Health(HealthError),
+}
+
+impl<E: Error> core::fmt::Display for MonitoredError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<E: Error> Error for MonitoredError<E> {}
+
+impl<E: Error + Classify> Classify for MonitoredError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Source(e) => e.kind(),
+            Self::Health(e) => e.kind(),
+        }
+    }
+}
+
+/// Runs the SP 800-90B continuous health tests over every byte read from the wrapped source.
+///
+/// NOTE: A failed test is latched, and the buffer of the failing read is zeroed. Wrap the raw noise source rather
+/// than a conditioned one; the tests cannot detect failures hidden behind a hash or DRBG.
+pub struct Monitored<S: EntropySource> {
+    source: S,
+    tests: Lock<HealthTests>,
+}
+
+impl<S: EntropySource> Monitored<S> {
+    /// Wrap a source claiming the given min-entropy per byte (in bits, clamped to 1..=8).
+    pub fn new(source: S, min_entropy: u8) -> Self {
+        Self::with_config(source, HealthConfig::for_min_entropy(min_entropy))
+    }
+
+    /// Wrap a source with custom test cutoffs.
+    pub fn with_config(source: S, config: HealthConfig) -> Self {
+        Self {
+            source,
+            tests: Lock::new(HealthTests::new(config)),
+        }
+    }
+
+    /// Get the latched failure, if any.
+    pub fn failure(&self) -> Option<HealthError> {
+        self.tests.with(|tests| tests.failure())
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySource for Monitored<S> {
+    type EntropySourceError = MonitoredError<S::EntropySourceError>;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        if let Some(e) = self.failure() {
+            return Err(MonitoredError::Health(e));
+        }
+
+        if let Err(e) = self.source.read_bytes(buffer) {
+            return Err(MonitoredError::Source(e));
+        }
+
+        let result = self.tests.with(|tests| buffer.iter().try_for_each(|byte| tests.test(*byte as u64)));
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                buffer.fill(0);
+
+                Err(MonitoredError::Health(e))
+            }
+        }
+    }
+}
+
+/// NOTE: The health tests do not change the rate of the wrapped source, only stop it from being used once it fails.
+impl<S: EntropyAssessment> EntropyAssessment for Monitored<S> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.source.provenance()
+    }
+}
+
+impl<S: EntropySource> SelfTest for Monitored<S> {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
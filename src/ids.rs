@@ -0,0 +1,123 @@
+//! Random identifiers.
+
+use core::fmt;
+
+use crate::EntropySource;
+
+/// Crockford's base32 alphabet, as used by ULIDs.
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// A random (version 4) UUID, as specified by RFC 9562.
+///
+/// NOTE: `Display` gives the usual hyphenated lowercase form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Uuid4 {
+    bytes: [u8; 16],
+}
+
+impl Uuid4 {
+    /// Attempt to generate a UUID from the provided `EntropySource`.
+    pub fn try_generate<S: EntropySource>(source: &S) -> Result<Self, S::EntropySourceError> {
+        let mut bytes = [0u8; 16];
+
+        source.read_bytes(&mut bytes)?;
+
+        // NOTE: Version 4 in the high nibble of byte 6, variant `10` in the high bits of byte 8.
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+        Ok(Self { bytes })
+    }
+
+    /// Generate a UUID from the provided `EntropySource`.
+    ///
+    /// NOTE: This function will panic if the generation fails. See `try_generate` for a version with error handling.
+    pub fn generate(source: &impl EntropySource) -> Self {
+        Self::try_generate(source).unwrap()
+    }
+
+    /// Get the 16 bytes of the UUID.
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.bytes
+    }
+}
+
+impl fmt::Display for Uuid4 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, byte) in self.bytes.iter().enumerate() {
+            if matches!(index, 4 | 6 | 8 | 10) {
+                f.write_str("-")?;
+            }
+
+            write!(f, "{:02x}", byte)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A ULID: a 48-bit millisecond timestamp followed by 80 random bits.
+///
+/// NOTE: ULIDs generated in the same millisecond are not ordered among themselves, and the timestamp reveals when
+/// the ID was created. `Display` gives the 26 character Crockford base32 form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ulid {
+    value: u128,
+}
+
+impl Ulid {
+    /// Attempt to generate a ULID for the current time from the provided `EntropySource`.
+    #[cfg(feature = "std")]
+    pub fn try_generate<S: EntropySource>(source: &S) -> Result<Self, S::EntropySourceError> {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+
+        Self::try_generate_at(timestamp, source)
+    }
+
+    /// Generate a ULID for the current time from the provided `EntropySource`.
+    ///
+    /// NOTE: This function will panic if the generation fails. See `try_generate` for a version with error handling.
+    #[cfg(feature = "std")]
+    pub fn generate(source: &impl EntropySource) -> Self {
+        Self::try_generate(source).unwrap()
+    }
+
+    /// Attempt to generate a ULID for the given Unix timestamp (in milliseconds, truncated to 48 bits).
+    pub fn try_generate_at<S: EntropySource>(timestamp: u64, source: &S) -> Result<Self, S::EntropySourceError> {
+        let mut bytes = [0u8; 16];
+
+        source.read_bytes(&mut bytes[6..])?;
+
+        bytes[..6].copy_from_slice(&timestamp.to_be_bytes()[2..]);
+
+        Ok(Self {
+            value: u128::from_be_bytes(bytes),
+        })
+    }
+
+    /// Get the Unix timestamp of the ULID, in milliseconds.
+    pub fn timestamp(&self) -> u64 {
+        (self.value >> 80) as u64
+    }
+
+    /// Get the 16 bytes of the ULID.
+    pub fn to_bytes(&self) -> [u8; 16] {
+        self.value.to_be_bytes()
+    }
+}
+
+impl fmt::Display for Ulid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut encoded = [0u8; 26];
+
+        // NOTE: 26 characters hold 130 bits, so the first one only carries the top 3 bits.
+        for (index, character) in encoded.iter_mut().enumerate() {
+            *character = CROCKFORD[(self.value >> (125 - 5 * index)) as usize & 0x1f];
+        }
+
+        // NOTE: Infallible, the alphabet is ASCII.
+        f.write_str(core::str::from_utf8(&encoded).map_err(|_| fmt::Error)?)
+    }
+}
@@ -0,0 +1,269 @@
+use core::error::Error;
+use core::num::NonZeroU32;
+
+use rand::distributions::Distribution;
+use rand_core::{CryptoRng, RngCore};
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{Classify, ErrorKind};
+use crate::lock::Lock;
+use crate::trace;
+use crate::EntropySource;
+
+/// Exposes an `EntropySource` as a `rand_core` RNG (`RngCore` + `CryptoRng`).
+///
+/// NOTE: Errors from the underlying source are reported as `rand_core::Error` with the code `RandAdapter::ERROR_CODE`.
+pub struct RandAdapter<S: EntropySource> {
+    source: S,
+}
+
+impl<S: EntropySource> RandAdapter<S> {
+    /// Error code reported to `rand_core` when the underlying source fails.
+    pub const ERROR_CODE: u32 = rand_core::Error::CUSTOM_START;
+
+    /// Wrap the provided `EntropySource`.
+    pub fn new(source: S) -> Self {
+        Self { source }
+    }
+
+    /// Get a reference to the underlying `EntropySource`.
+    pub fn inner(&self) -> &S {
+        &self.source
+    }
+
+    /// Unwrap the underlying `EntropySource`.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S: EntropySource> RngCore for RandAdapter<S> {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+
+        self.fill_bytes(&mut bytes);
+
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+
+        self.fill_bytes(&mut bytes);
+
+        u64::from_le_bytes(bytes)
+    }
+
+    /// NOTE: This function will panic if the underlying source fails. See `try_fill_bytes` for a version with error handling.
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).unwrap()
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        match self.source.read_bytes(dest) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(NonZeroU32::new(Self::ERROR_CODE).unwrap().into()),
+        }
+    }
+}
+
+/// NOTE: `EntropySource` implementations are assumed to be cryptographically secure.
+impl<S: EntropySource> CryptoRng for RandAdapter<S> {}
+
+#[derive(Debug)]
+pub struct FromRngError {
+    inner: rand_core::Error,
+}
+
+impl FromRngError {
+    /// Get the error code reported by the RNG, if any.
+    pub fn code(&self) -> Option<NonZeroU32> {
+        self.inner.code()
+    }
+
+    /// Get the underlying `rand_core::Error`.
+    pub fn inner(&self) -> &rand_core::Error {
+        &self.inner
+    }
+}
+
+impl core::fmt::Display for FromRngError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.inner)
+    }
+}
+
+impl Error for FromRngError {}
+
+impl Classify for FromRngError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Backend(self.code().map_or(0, |code| code.get() as i32))
+    }
+}
+
+/// Exposes a `rand_core` RNG (`RngCore` + `CryptoRng`) as an `EntropySource`.
+///
+/// NOTE: The RNG is kept behind a lock since `EntropySource::read_bytes` only takes `&self`. Without the `std` feature
+/// this lock is a `RefCell`, so `FromRng` is not `Sync`.
+pub struct FromRng<R: CryptoRng + RngCore> {
+    rng: Lock<R>,
+}
+
+impl<R: CryptoRng + RngCore> FromRng<R> {
+    /// Wrap the provided RNG.
+    pub fn new(rng: R) -> Self {
+        Self {
+            rng: Lock::new(rng),
+        }
+    }
+
+    /// Unwrap the underlying RNG.
+    pub fn into_inner(self) -> R {
+        self.rng.into_inner()
+    }
+}
+
+unsafe impl<R: CryptoRng + RngCore> EntropySource for FromRng<R> {
+    type EntropySourceError = FromRngError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.rng.with(|rng| match rng.try_fill_bytes(buffer) {
+            Ok(_) => Ok(()),
+            Err(inner) => Err(FromRngError { inner }),
+        })
+    }
+}
+
+/// NOTE: `CryptoRng` marks a cryptographically secure generator, so the output is credited as a generator's; whether
+/// the generator was seeded well is up to whoever created it.
+impl<R: CryptoRng + RngCore> EntropyAssessment for FromRng<R> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Other,
+            basis: Basis::Generator,
+        }
+    }
+}
+
+/// Feeds a `rand` distribution from a borrowed source, keeping the first error it reports.
+struct SamplingRng<'a, S: EntropySource> {
+    source: &'a S,
+    error: Option<S::EntropySourceError>,
+    filler: u64,
+}
+
+impl<'a, S: EntropySource> SamplingRng<'a, S> {
+    fn new(source: &'a S) -> Self {
+        Self {
+            source,
+            error: None,
+            filler: 0,
+        }
+    }
+
+    fn finish<T>(self, sample: T) -> Result<T, S::EntropySourceError> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(sample),
+        }
+    }
+}
+
+impl<S: EntropySource> RngCore for SamplingRng<'_, S> {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+
+        self.fill_bytes(&mut bytes);
+
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+
+        self.fill_bytes(&mut bytes);
+
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        if self.error.is_none() {
+            match self.source.read_bytes(dest) {
+                Ok(()) => return,
+                Err(e) => self.error = Some(e),
+            }
+        }
+
+        // NOTE: Once the source has failed the sample is discarded, but rejection loops (e.g. in `Normal`) still need
+        // varying bytes to terminate, so a SplitMix64 sequence stands in for the source.
+        for chunk in dest.chunks_mut(8) {
+            self.filler = self.filler.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+            let mut z = self.filler;
+
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^= z >> 31;
+
+            chunk.copy_from_slice(&z.to_le_bytes()[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+
+        Ok(())
+    }
+}
+
+/// Sampling of `rand` distributions (e.g. `Uniform` over floats, `Bernoulli`, or `rand_distr::Normal`) directly from
+/// an `EntropySource`, for simulations or differential-privacy noise which must not come from a statistical PRNG.
+///
+/// NOTE: Every sample reads from the source in small pieces (typically 8 bytes at a time), so slow sources are best
+/// wrapped in a `buffered::BufferedSource` first. Floating-point noise has its own pitfalls for differential privacy
+/// (e.g. the gaps between representable values), which this does not address.
+pub trait SamplingExt: EntropySource {
+    /// Attempt to draw one sample from `distribution`.
+    fn sample<T, D: Distribution<T>>(&self, distribution: D) -> Result<T, Self::EntropySourceError>
+    where
+        Self: Sized,
+    {
+        let mut rng = SamplingRng::new(self);
+
+        let sample = distribution.sample(&mut rng);
+
+        rng.finish(sample)
+    }
+
+    /// Attempt to fill `samples` with samples drawn from `distribution`.
+    ///
+    /// NOTE: If the source fails, `samples` may be partially overwritten.
+    fn sample_fill<T, D: Distribution<T>>(
+        &self,
+        distribution: D,
+        samples: &mut [T],
+    ) -> Result<(), Self::EntropySourceError>
+    where
+        Self: Sized,
+    {
+        let mut rng = SamplingRng::new(self);
+
+        for sample in samples.iter_mut() {
+            *sample = distribution.sample(&mut rng);
+
+            if rng.error.is_some() {
+                break;
+            }
+        }
+
+        rng.finish(())
+    }
+}
+
+impl<S: EntropySource> SamplingExt for S {}
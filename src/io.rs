@@ -0,0 +1,73 @@
+//! `std::io` adapters, for APIs that consume a reader (e.g. streaming encryptors or file-wiping tools).
+
+use std::io::{self, BufRead, Read};
+
+use crate::buffered::Buffer;
+use crate::EntropySource;
+
+/// Default buffer size for `BufRead`.
+const DEFAULT_CAPACITY: usize = 4096;
+
+/// A `Read` and `BufRead` adapter over an `EntropySource`.
+///
+/// NOTE: The reader never reaches end-of-file, so bound it (e.g. with `Read::take`) before handing it to anything that
+/// reads to the end. Every `read` fills the whole buffer passed in, or fails with the source's error wrapped in an
+/// `io::Error`. Small reads are served from an internal buffer, and served bytes are wiped from it immediately.
+pub struct EntropyReader<S: EntropySource> {
+    source: S,
+    buffer: Buffer,
+    capacity: usize,
+}
+
+impl<S: EntropySource> EntropyReader<S> {
+    /// Wrap a source with a 4 KiB buffer.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            buffer: Buffer::new(),
+            capacity: DEFAULT_CAPACITY,
+        }
+    }
+
+    /// Set the buffer size (minimum 1), discarding any buffered bytes.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.buffer.clear();
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Unwrap the source, wiping any buffered bytes.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S: EntropySource> Read for EntropyReader<S>
+where
+    S::EntropySourceError: Send + Sync + 'static,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.buffer.read(&self.source, buf, self.capacity) {
+            Ok(()) => Ok(buf.len()),
+            Err(e) => Err(io::Error::other(e)),
+        }
+    }
+}
+
+impl<S: EntropySource> BufRead for EntropyReader<S>
+where
+    S::EntropySourceError: Send + Sync + 'static,
+{
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.buffer.fill(&self.source, self.capacity).map_err(io::Error::other)
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.buffer.consume(amount);
+    }
+}
@@ -0,0 +1,43 @@
+//! Minimal JSON formatting for the machine-readable reports.
+
+// NOTE: Not every helper is used by every feature combination.
+#![allow(dead_code)]
+
+use core::fmt::Write;
+use std::string::String;
+
+/// Formats a value as a JSON string.
+pub(crate) struct Json<T: core::fmt::Display>(pub T);
+
+impl<T: core::fmt::Display> core::fmt::Display for Json<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut value = String::new();
+
+        write!(value, "{}", self.0)?;
+
+        f.write_char('"')?;
+
+        for c in value.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+                c => f.write_char(c)?,
+            }
+        }
+
+        f.write_char('"')
+    }
+}
+
+/// Formats a float as a JSON number, or `null` if it is not finite.
+pub(crate) struct Number(pub f64);
+
+impl core::fmt::Display for Number {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.0.is_finite() {
+            true => write!(f, "{}", self.0),
+            false => f.write_str("null"),
+        }
+    }
+}
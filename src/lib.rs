@@ -1,12 +1,252 @@
-use std::error::Error;
+#![no_std]
+
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+use core::error::Error;
+
+mod ext;
+
+#[cfg(any(
+    feature = "buffered",
+    feature = "csprng",
+    feature = "drbg",
+    feature = "pool",
+    feature = "prefetch"
+))]
+mod fork;
+
+pub use ext::{EntropyIter, EntropySourceExt, UniformInt};
+
+#[cfg(feature = "std")]
+mod global;
+
+#[cfg(feature = "std")]
+pub use global::{default_source, set_default_source, DefaultSource, DefaultSourceError};
+
+#[cfg(any(feature = "audit", feature = "export"))]
+mod json;
+
+mod lock;
+
+#[cfg(feature = "serde-unsafe-exposure")]
+mod serialize;
+
+mod trace;
 
 /// A source for random bytes used in cryptographic algorithms.
 ///
-/// NOTE: This trait is `unsafe` because it assumes the source is cryptographically secure.
+/// # Safety
+///
+/// This trait is `unsafe` because it assumes the source is cryptographically secure.
 pub unsafe trait EntropySource {
     type EntropySourceError: Error;
 
     fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError>;
+
+    /// Fill the buffer with random bytes (an alias of `read_bytes`).
+    fn fill(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        self.read_bytes(buffer)
+    }
+
+    /// Attempt to generate a `Vec` of `length` random bytes.
+    ///
+    /// NOTE: See `EntropyVec` for a version that is wiped on drop.
+    #[cfg(feature = "alloc")]
+    fn generate_vec(&self, length: usize) -> Result<alloc::vec::Vec<u8>, Self::EntropySourceError> {
+        let mut bytes = alloc::vec![0u8; length];
+
+        match self.read_bytes(&mut bytes) {
+            Ok(_) => Ok(bytes),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Fill each of the buffers with random bytes, in order.
+    ///
+    /// NOTE: By default `read_bytes` is called once per buffer; sources with a per-call cost (a lock, a syscall) may
+    /// override this to serve all of them at once.
+    #[cfg(feature = "std")]
+    fn read_bytes_vectored(&self, buffers: &mut [std::io::IoSliceMut<'_>]) -> Result<(), Self::EntropySourceError> {
+        for buffer in buffers.iter_mut() {
+            self.read_bytes(buffer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Fill a possibly uninitialized buffer with random bytes, returning it as initialized.
+    ///
+    /// NOTE: By default the buffer is zeroed before calling `read_bytes`; sources able to write to uninitialized
+    /// memory directly may override this.
+    fn fill_uninit<'a>(
+        &self,
+        buffer: &'a mut [core::mem::MaybeUninit<u8>],
+    ) -> Result<&'a mut [u8], Self::EntropySourceError> {
+        buffer.iter_mut().for_each(|byte| {
+            byte.write(0);
+        });
+
+        // NOTE: Sound, since every byte was initialized above, and `MaybeUninit<u8>` has the same layout as `u8`.
+        let bytes = unsafe { &mut *(buffer as *mut [core::mem::MaybeUninit<u8>] as *mut [u8]) };
+
+        self.read_bytes(bytes)?;
+
+        Ok(bytes)
+    }
+}
+
+/// A source that needs exclusive access to produce random bytes, such as a generator used from a single thread.
+///
+/// Implemented natively by the built-in deterministic generators, which then skip the lock `read_bytes` takes. Wrap a
+/// source in `SyncWrapper` to use it as an `EntropySource`.
+///
+/// # Safety
+///
+/// As for `EntropySource`, this trait assumes the source is cryptographically secure.
+pub unsafe trait EntropySourceMut {
+    type EntropySourceError: Error;
+
+    fn read_bytes_mut(&mut self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError>;
+}
+
+unsafe impl<S: EntropySourceMut + ?Sized> EntropySourceMut for &mut S {
+    type EntropySourceError = S::EntropySourceError;
+
+    fn read_bytes_mut(&mut self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        (**self).read_bytes_mut(buffer)
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<S: EntropySourceMut + ?Sized> EntropySourceMut for alloc::boxed::Box<S> {
+    type EntropySourceError = S::EntropySourceError;
+
+    fn read_bytes_mut(&mut self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        (**self).read_bytes_mut(buffer)
+    }
+}
+
+/// Shares an `EntropySourceMut` behind a lock, so it can be used as an `EntropySource`.
+///
+/// NOTE: Without the `std` feature the lock is a `RefCell`, so the wrapper is not `Sync` and panics if read
+/// re-entrantly.
+pub struct SyncWrapper<S: EntropySourceMut> {
+    source: lock::Lock<S>,
+}
+
+impl<S: EntropySourceMut> SyncWrapper<S> {
+    /// Wrap a source.
+    pub const fn new(source: S) -> Self {
+        Self {
+            source: lock::Lock::new(source),
+        }
+    }
+
+    /// Get exclusive access to the wrapped source, without locking.
+    pub fn get_mut(&mut self) -> &mut S {
+        self.source.get_mut()
+    }
+
+    /// Unwrap the source.
+    pub fn into_inner(self) -> S {
+        self.source.into_inner()
+    }
+}
+
+unsafe impl<S: EntropySourceMut> EntropySource for SyncWrapper<S> {
+    type EntropySourceError = S::EntropySourceError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.source.with(|source| source.read_bytes_mut(buffer))
+    }
+}
+
+unsafe impl<S: EntropySourceMut> EntropySourceMut for SyncWrapper<S> {
+    type EntropySourceError = S::EntropySourceError;
+
+    fn read_bytes_mut(&mut self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        self.source.get_mut().read_bytes_mut(buffer)
+    }
+}
+
+impl<S: EntropySourceMut + assess::EntropyAssessment> assess::EntropyAssessment for SyncWrapper<S> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.with(|source| source.min_entropy_per_byte())
+    }
+
+    fn provenance(&self) -> assess::Provenance {
+        self.source.with(|source| source.provenance())
+    }
+}
+
+/// Forward every `EntropySource` method through a reference or smart pointer, keeping the pointee's overrides.
+macro_rules! forward_entropy_source {
+    ($($(#[$meta:meta])* $pointer:ty),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            unsafe impl<S: EntropySource + ?Sized> EntropySource for $pointer {
+                type EntropySourceError = S::EntropySourceError;
+
+                fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+                    (**self).read_bytes(buffer)
+                }
+
+                #[cfg(feature = "std")]
+                fn read_bytes_vectored(
+                    &self,
+                    buffers: &mut [std::io::IoSliceMut<'_>],
+                ) -> Result<(), Self::EntropySourceError> {
+                    (**self).read_bytes_vectored(buffers)
+                }
+
+                fn fill_uninit<'a>(
+                    &self,
+                    buffer: &'a mut [core::mem::MaybeUninit<u8>],
+                ) -> Result<&'a mut [u8], Self::EntropySourceError> {
+                    (**self).fill_uninit(buffer)
+                }
+            }
+        )*
+    };
+}
+
+forward_entropy_source!(
+    &S,
+    &mut S,
+    #[cfg(feature = "alloc")]
+    alloc::boxed::Box<S>,
+    #[cfg(feature = "alloc")]
+    alloc::rc::Rc<S>,
+    #[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+    alloc::sync::Arc<S>,
+);
+
+/// HKDF salt for `Entropy::derive`.
+#[cfg(feature = "hkdf")]
+const DERIVE_SALT: &[u8] = b"librypt-entropy derive v1";
+
+/// `Debug` output for the bytes of an entropy type, e.g. `Entropy<32>([REDACTED])` unless revealed.
+pub(crate) struct DebugBytes<'a> {
+    pub(crate) name: &'static str,
+    pub(crate) bytes: &'a [u8],
+    pub(crate) reveal: bool,
+}
+
+impl core::fmt::Debug for DebugBytes<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}<{}>", self.name, self.bytes.len())?;
+
+        match self.reveal {
+            true => write!(f, "({:?})", self.bytes),
+            false => f.write_str("([REDACTED])"),
+        }
+    }
 }
 
 /// A simple wrapper over a generic byte array sourced from an `EntropySource`.
@@ -17,10 +257,11 @@ pub struct Entropy<const LENGTH: usize> {
 impl<const LENGTH: usize> Entropy<LENGTH> {
     /// Attempt to generate entropy from the provided `EntropySource`.
     pub fn try_generate<S: EntropySource>(source: &S) -> Result<Self, S::EntropySourceError> {
-        let mut bytes = [0u8; LENGTH];
+        // NOTE: Filled in place so no unwiped copy of the bytes is left behind on the stack.
+        let mut entropy = Self { bytes: [0u8; LENGTH] };
 
-        match source.read_bytes(&mut bytes) {
-            Ok(_) => Ok(Self { bytes }),
+        match source.read_bytes(&mut entropy.bytes) {
+            Ok(_) => Ok(entropy),
             Err(e) => Err(e),
         }
     }
@@ -31,41 +272,533 @@ impl<const LENGTH: usize> Entropy<LENGTH> {
     pub fn generate(source: &impl EntropySource) -> Self {
         Self::try_generate(source).unwrap()
     }
+
+    /// Attempt to generate entropy from the process-wide default source (see `set_default_source`).
+    #[cfg(feature = "std")]
+    pub fn try_generate_default() -> Result<Self, erased::ErasedError> {
+        Self::try_generate(&default_source())
+    }
+
+    /// Generate entropy from the process-wide default source (see `set_default_source`).
+    ///
+    /// NOTE: This function will panic if the generation fails. See `try_generate_default` for a version with error
+    /// handling.
+    #[cfg(feature = "std")]
+    pub fn generate_default() -> Self {
+        Self::generate(&default_source())
+    }
+
+    /// Compare against another `Entropy` in constant time.
+    ///
+    /// NOTE: With the `subtle` feature, `subtle::ConstantTimeEq` is also implemented (returning a `Choice`).
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        let mut difference = 0u8;
+
+        for (a, b) in self.bytes.iter().zip(other.bytes.iter()) {
+            difference |= a ^ b;
+        }
+
+        core::hint::black_box(difference) == 0
+    }
+
+    /// Split into two parts of `FIRST` and `SECOND` bytes.
+    ///
+    /// NOTE: Fails to compile unless `FIRST + SECOND == LENGTH`.
+    pub fn split<const FIRST: usize, const SECOND: usize>(self) -> (Entropy<FIRST>, Entropy<SECOND>) {
+        const { assert!(FIRST + SECOND == LENGTH, "split lengths must add up to LENGTH") };
+
+        let mut first = Entropy { bytes: [0u8; FIRST] };
+        let mut second = Entropy { bytes: [0u8; SECOND] };
+
+        first.bytes.copy_from_slice(&self.bytes[..FIRST]);
+        second.bytes.copy_from_slice(&self.bytes[FIRST..]);
+
+        (first, second)
+    }
+
+    /// Append another `Entropy`, giving `OUTPUT` bytes.
+    ///
+    /// NOTE: Fails to compile unless `OUTPUT == LENGTH + OTHER`.
+    pub fn concat<const OTHER: usize, const OUTPUT: usize>(self, other: Entropy<OTHER>) -> Entropy<OUTPUT> {
+        const { assert!(LENGTH + OTHER == OUTPUT, "concat length must be LENGTH + OTHER") };
+
+        let mut output = Entropy { bytes: [0u8; OUTPUT] };
+
+        output.bytes[..LENGTH].copy_from_slice(&self.bytes);
+        output.bytes[LENGTH..].copy_from_slice(&other.bytes);
+
+        output
+    }
+
+    /// Keep only the first `N` bytes.
+    ///
+    /// NOTE: Fails to compile unless `N <= LENGTH`.
+    pub fn truncate<const N: usize>(self) -> Entropy<N> {
+        const { assert!(N <= LENGTH, "cannot truncate to more than LENGTH bytes") };
+
+        let mut output = Entropy { bytes: [0u8; N] };
+
+        output.bytes.copy_from_slice(&self.bytes[..N]);
+
+        output
+    }
+
+    /// Get a `Debug` view which prints the bytes, unlike the redacting `Debug` impl.
+    ///
+    /// NOTE: Only use this where the output cannot end up in logs (e.g. a local test failure).
+    pub fn debug_reveal(&self) -> impl core::fmt::Debug + '_ {
+        DebugBytes {
+            name: "Entropy",
+            bytes: &self.bytes,
+            reveal: true,
+        }
+    }
+}
+
+/// NOTE: The bytes are redacted (`Entropy<32>([REDACTED])`), so logging a value containing entropy leaks nothing. Use
+/// `debug_reveal` to print them.
+impl<const LENGTH: usize> core::fmt::Debug for Entropy<LENGTH> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let bytes = DebugBytes {
+            name: "Entropy",
+            bytes: &self.bytes,
+            reveal: false,
+        };
+
+        core::fmt::Debug::fmt(&bytes, f)
+    }
+}
+
+#[cfg(feature = "hkdf")]
+impl<const LENGTH: usize> Entropy<LENGTH> {
+    /// Derive an `N`-byte subkey labeled by `info` (e.g. `b"enc key"`), using HKDF-SHA256 over these bytes.
+    ///
+    /// NOTE: Fails to compile unless `N <= 8160` (the HKDF-SHA256 output limit). The salt is fixed to this crate's
+    /// domain, keeping subkeys separate from other uses of HKDF over the same bytes.
+    pub fn derive<const N: usize>(&self, info: &[u8]) -> Entropy<N> {
+        const { assert!(N <= 255 * 32, "cannot derive more than 8160 bytes") };
+
+        let mut output = Entropy { bytes: [0u8; N] };
+
+        hkdf::Hkdf::<sha2::Sha256>::new(Some(DERIVE_SALT), &self.bytes)
+            .expand(info, &mut output.bytes)
+            .expect("output length within the HKDF limit");
+
+        output
+    }
+}
+
+impl<const LENGTH: usize> AsRef<[u8]> for Entropy<LENGTH> {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl<const LENGTH: usize> AsMut<[u8]> for Entropy<LENGTH> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes
+    }
+}
+
+/// NOTE: The array passed in is copied, so the caller is responsible for wiping it.
+impl<const LENGTH: usize> From<[u8; LENGTH]> for Entropy<LENGTH> {
+    fn from(bytes: [u8; LENGTH]) -> Self {
+        Self { bytes }
+    }
 }
 
-#[cfg(feature = "os")]
-pub mod os {
-    use std::error::Error;
+/// NOTE: Fails unless the slice is exactly `LENGTH` bytes long.
+impl<const LENGTH: usize> TryFrom<&[u8]> for Entropy<LENGTH> {
+    type Error = core::array::TryFromSliceError;
 
-    use crate::EntropySource;
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        bytes.try_into().map(|bytes| Self { bytes })
+    }
+}
+
+/// NOTE: With the `zeroize` feature, the bytes are copied out and the `Entropy` itself is wiped.
+impl<const LENGTH: usize> From<Entropy<LENGTH>> for [u8; LENGTH] {
+    fn from(entropy: Entropy<LENGTH>) -> Self {
+        entropy.bytes
+    }
+}
+
+/// NOTE: With the `zeroize` feature, the bytes are copied into the iterator and the `Entropy` itself is wiped.
+impl<const LENGTH: usize> IntoIterator for Entropy<LENGTH> {
+    type Item = u8;
+    type IntoIter = core::array::IntoIter<u8, LENGTH>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.bytes.into_iter()
+    }
+}
+
+impl<'a, const LENGTH: usize> IntoIterator for &'a Entropy<LENGTH> {
+    type Item = &'a u8;
+    type IntoIter = core::slice::Iter<'a, u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.bytes.iter()
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl<const LENGTH: usize> subtle::ConstantTimeEq for Entropy<LENGTH> {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        self.bytes.ct_eq(&other.bytes)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const LENGTH: usize> zeroize::Zeroize for Entropy<LENGTH> {
+    fn zeroize(&mut self) {
+        self.bytes.zeroize();
+    }
+}
+
+/// NOTE: Wipes `bytes` when the entropy goes out of scope.
+#[cfg(feature = "zeroize")]
+impl<const LENGTH: usize> Drop for Entropy<LENGTH> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const LENGTH: usize> zeroize::ZeroizeOnDrop for Entropy<LENGTH> {}
+
+#[cfg(feature = "zeroize")]
+impl<const LENGTH: usize> Entropy<LENGTH> {
+    /// Hand the bytes to `f`, wiping them as soon as it returns or panics.
+    ///
+    /// NOTE: Only the bytes held here are wiped; copies `f` makes are its own responsibility.
+    pub fn with_scoped<R>(mut self, f: impl FnOnce(&mut [u8; LENGTH]) -> R) -> R {
+        let mut guard = WipeGuard::new(&mut self.bytes);
+
+        f(&mut guard)
+    }
+}
 
-    #[derive(Debug)]
-    pub enum OsEntropySourceError {
-        /// This is temporary, will be removed in the future.
-        None,
+/// Wipes the borrowed value when dropped, including while unwinding from a panic.
+#[cfg(feature = "zeroize")]
+pub struct WipeGuard<'a, T: zeroize::Zeroize + ?Sized> {
+    value: &'a mut T,
+}
+
+#[cfg(feature = "zeroize")]
+impl<'a, T: zeroize::Zeroize + ?Sized> WipeGuard<'a, T> {
+    /// Guard `value` (e.g. a key buffer) until the end of the current scope.
+    pub fn new(value: &'a mut T) -> Self {
+        Self { value }
     }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T: zeroize::Zeroize + ?Sized> core::ops::Deref for WipeGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
 
-    impl std::fmt::Display for OsEntropySourceError {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            write!(f, "{:?}", self)
+#[cfg(feature = "zeroize")]
+impl<T: zeroize::Zeroize + ?Sized> core::ops::DerefMut for WipeGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T: zeroize::Zeroize + ?Sized> Drop for WipeGuard<'_, T> {
+    fn drop(&mut self) {
+        self.value.zeroize();
+    }
+}
+
+#[cfg(feature = "secrecy")]
+impl<const LENGTH: usize> Entropy<LENGTH> {
+    /// Attempt to generate entropy from the provided `EntropySource` directly into a `SecretBox`.
+    pub fn try_generate_secret<S: EntropySource>(
+        source: &S,
+    ) -> Result<secrecy::SecretBox<[u8; LENGTH]>, S::EntropySourceError> {
+        let mut bytes = alloc::boxed::Box::new([0u8; LENGTH]);
+
+        match source.read_bytes(bytes.as_mut()) {
+            Ok(_) => Ok(secrecy::SecretBox::new(bytes)),
+            Err(e) => Err(e),
         }
     }
 
-    impl Error for OsEntropySourceError {}
+    /// Move the bytes into a `SecretBox`, wiping this copy.
+    pub fn into_secret(self) -> secrecy::SecretBox<[u8; LENGTH]> {
+        let mut bytes = alloc::boxed::Box::new([0u8; LENGTH]);
 
-    /// Entropy from the underlying Operating System.
+        bytes.copy_from_slice(&self.bytes);
+
+        secrecy::SecretBox::new(bytes)
+    }
+}
+
+#[cfg(feature = "secrecy")]
+impl<const LENGTH: usize> secrecy::ExposeSecret<[u8; LENGTH]> for Entropy<LENGTH> {
+    fn expose_secret(&self) -> &[u8; LENGTH] {
+        &self.bytes
+    }
+}
+
+#[cfg(feature = "secrecy")]
+impl<const LENGTH: usize> From<Entropy<LENGTH>> for secrecy::SecretBox<[u8; LENGTH]> {
+    fn from(entropy: Entropy<LENGTH>) -> Self {
+        entropy.into_secret()
+    }
+}
+
+/// A heap-allocated counterpart to `Entropy` for lengths only known at runtime.
+#[cfg(feature = "alloc")]
+pub struct EntropyVec {
+    pub bytes: alloc::vec::Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+impl EntropyVec {
+    /// Attempt to generate `length` bytes of entropy from the provided `EntropySource`.
+    pub fn try_generate<S: EntropySource>(length: usize, source: &S) -> Result<Self, S::EntropySourceError> {
+        // NOTE: Filled in place (and never reallocated) so no unwiped copy of the bytes is left behind.
+        let mut entropy = Self {
+            bytes: alloc::vec![0u8; length],
+        };
+
+        match source.read_bytes(&mut entropy.bytes) {
+            Ok(_) => Ok(entropy),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Generate `length` bytes of entropy from the provided `EntropySource`.
     ///
-    /// NOTE: Implemented using the cross-platform `getrandom` crate.
-    pub struct OsEntropy;
+    /// NOTE: This function will panic if the generation fails. See `try_generate` for a version with error handling.
+    pub fn generate(length: usize, source: &impl EntropySource) -> Self {
+        Self::try_generate(length, source).unwrap()
+    }
 
-    unsafe impl EntropySource for OsEntropy {
-        type EntropySourceError = OsEntropySourceError;
+    /// Get the number of bytes.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
 
-        fn read_bytes(&self, bytes: &mut [u8]) -> Result<(), Self::EntropySourceError> {
-            match getrandom::getrandom(bytes) {
-                Ok(_) => Ok(()),
-                Err(_) => Err(Self::EntropySourceError::None),
-            }
+    /// Whether there are no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Compare against another `EntropyVec` in constant time.
+    ///
+    /// NOTE: Only the contents are compared in constant time; entropy of different lengths is unequal immediately.
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        if self.bytes.len() != other.bytes.len() {
+            return false;
         }
+
+        let mut difference = 0u8;
+
+        for (a, b) in self.bytes.iter().zip(other.bytes.iter()) {
+            difference |= a ^ b;
+        }
+
+        core::hint::black_box(difference) == 0
+    }
+
+    /// Get a `Debug` view which prints the bytes, as with `Entropy::debug_reveal`.
+    pub fn debug_reveal(&self) -> impl core::fmt::Debug + '_ {
+        DebugBytes {
+            name: "EntropyVec",
+            bytes: &self.bytes,
+            reveal: true,
+        }
+    }
+}
+
+/// NOTE: The bytes are redacted, as with `Entropy`.
+#[cfg(feature = "alloc")]
+impl core::fmt::Debug for EntropyVec {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let bytes = DebugBytes {
+            name: "EntropyVec",
+            bytes: &self.bytes,
+            reveal: false,
+        };
+
+        core::fmt::Debug::fmt(&bytes, f)
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "subtle"))]
+impl subtle::ConstantTimeEq for EntropyVec {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        self.bytes.as_slice().ct_eq(other.bytes.as_slice())
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "zeroize"))]
+impl zeroize::Zeroize for EntropyVec {
+    fn zeroize(&mut self) {
+        self.bytes.zeroize();
+    }
+}
+
+/// NOTE: Wipes `bytes` when the entropy goes out of scope.
+#[cfg(all(feature = "alloc", feature = "zeroize"))]
+impl Drop for EntropyVec {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
     }
 }
+
+#[cfg(all(feature = "alloc", feature = "zeroize"))]
+impl zeroize::ZeroizeOnDrop for EntropyVec {}
+
+#[cfg(feature = "secrecy")]
+impl From<EntropyVec> for secrecy::SecretSlice<u8> {
+    fn from(mut entropy: EntropyVec) -> Self {
+        core::mem::take(&mut entropy.bytes).into()
+    }
+}
+
+pub mod assess;
+
+#[cfg(feature = "async")]
+pub mod asynchronous;
+
+#[cfg(feature = "audit")]
+pub mod audit;
+
+#[cfg(feature = "bench")]
+pub mod bench;
+
+#[cfg(feature = "budget")]
+pub mod budget;
+
+#[cfg(feature = "buffered")]
+pub mod buffered;
+
+#[cfg(feature = "capi")]
+pub mod capi;
+
+#[cfg(feature = "combine")]
+pub mod combine;
+
+#[cfg(feature = "concurrent")]
+pub mod concurrent;
+
+#[cfg(feature = "condition")]
+pub mod condition;
+
+#[cfg(feature = "csprng")]
+pub mod csprng;
+
+#[cfg(feature = "drbg")]
+pub mod drbg;
+
+#[cfg(feature = "adc")]
+pub mod embedded;
+
+#[cfg(any(feature = "hex", feature = "base64"))]
+pub mod encoding;
+
+#[cfg(feature = "alloc")]
+pub mod erased;
+
+pub mod error;
+
+#[cfg(feature = "estimate")]
+pub mod estimate;
+
+#[cfg(feature = "export")]
+pub mod export;
+
+#[cfg(feature = "extract")]
+pub mod extract;
+
+#[cfg(all(feature = "feeder", target_os = "linux"))]
+pub mod feeder;
+
+pub mod fips140;
+
+#[cfg(feature = "health")]
+pub mod health;
+
+#[cfg(feature = "ids")]
+pub mod ids;
+
+#[cfg(feature = "rand")]
+pub mod interop;
+
+#[cfg(feature = "io")]
+pub mod io;
+
+#[cfg(feature = "kat")]
+pub mod kat;
+
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
+#[cfg(feature = "nonce")]
+pub mod nonce;
+
+#[cfg(any(feature = "persist", feature = "nv-pool"))]
+pub mod persist;
+
+#[cfg(feature = "policy")]
+pub mod policy;
+
+#[cfg(feature = "pool")]
+pub mod pool;
+
+#[cfg(feature = "prefetch")]
+pub mod prefetch;
+
+#[cfg(feature = "python")]
+pub mod python;
+
+#[cfg(feature = "rate-limit")]
+pub mod rate_limit;
+
+#[cfg(any(feature = "drbg", feature = "csprng"))]
+pub mod reseed;
+
+pub mod retry;
+
+#[cfg(feature = "secure")]
+pub mod secure;
+
+pub mod selftest;
+
+pub mod sources;
+
+#[cfg(feature = "proptest")]
+pub mod strategy;
+
+#[cfg(feature = "async")]
+pub mod stream;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "thread-local")]
+pub mod thread_local;
+
+#[cfg(feature = "timeout")]
+pub mod timeout;
+
+#[cfg(feature = "tokens")]
+pub mod tokens;
+
+#[cfg(feature = "verify")]
+pub mod verify;
+
+#[cfg(feature = "watchdog")]
+pub mod watchdog;
+
+#[cfg(any(feature = "os", feature = "os-native"))]
+pub mod os;
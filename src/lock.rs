@@ -0,0 +1,58 @@
+//! Interior mutability for stateful sources.
+//!
+//! NOTE: Backed by `std::sync::Mutex` with the `std` feature and `core::cell::RefCell` (not `Sync`) without it.
+
+// NOTE: Not every helper is used by every feature combination.
+#![allow(dead_code)]
+
+#[cfg(feature = "std")]
+pub(crate) struct Lock<T>(std::sync::Mutex<T>);
+
+#[cfg(not(feature = "std"))]
+pub(crate) struct Lock<T>(core::cell::RefCell<T>);
+
+#[cfg(feature = "std")]
+impl<T> Lock<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self(std::sync::Mutex::new(value))
+    }
+
+    /// Run `f` with exclusive access to the inner value.
+    ///
+    /// NOTE: A poisoned lock is recovered rather than propagated.
+    pub(crate) fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.0.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Get exclusive access to the inner value without locking.
+    pub(crate) fn get_mut(&mut self) -> &mut T {
+        self.0.get_mut().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn into_inner(self) -> T {
+        self.0.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T> Lock<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self(core::cell::RefCell::new(value))
+    }
+
+    /// Run `f` with exclusive access to the inner value.
+    ///
+    /// NOTE: This function will panic if called re-entrantly.
+    pub(crate) fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.0.borrow_mut())
+    }
+
+    /// Get exclusive access to the inner value without borrow tracking.
+    pub(crate) fn get_mut(&mut self) -> &mut T {
+        self.0.get_mut()
+    }
+
+    pub(crate) fn into_inner(self) -> T {
+        self.0.into_inner()
+    }
+}
@@ -0,0 +1,236 @@
+//! `librypt-entropy`: generate random bytes and diagnose entropy sources from the command line.
+
+use std::io::Write;
+use std::process::ExitCode;
+
+use librypt_entropy::bench::{measure, BenchConfig};
+use librypt_entropy::erased::ErasedError;
+use librypt_entropy::error::Classify;
+use librypt_entropy::estimate::{collect, estimate};
+use librypt_entropy::os::OsEntropy;
+use librypt_entropy::selftest::SelfTest;
+use librypt_entropy::sources::{JitterSource, RdRand};
+use librypt_entropy::{EntropySource, EntropyVec};
+
+const USAGE: &str = "\
+usage: librypt-entropy <command> [options]
+
+commands:
+  gen       write random bytes to stdout
+              --bytes N                 number of bytes (default 32)
+              --format hex|base64|raw   output encoding (default hex)
+  health    run the startup self test and the SP 800-90B estimators on a source
+              --samples N               number of bytes to estimate over (default 1000000)
+  bench     measure throughput and latency of a source
+
+options for every command:
+  --source os|rdrand|jitter             the entropy source (default os)
+";
+
+/// A source both readable and self-testable behind one trait object, whose errors keep their `ErrorKind`.
+trait Diagnosable: SelfTest + Send + Sync {
+    fn read_classified(&self, buffer: &mut [u8]) -> Result<(), ErasedError>;
+}
+
+impl<T> Diagnosable for T
+where
+    T: EntropySource + SelfTest + Send + Sync,
+    T::EntropySourceError: Classify + Send + Sync + 'static,
+{
+    fn read_classified(&self, buffer: &mut [u8]) -> Result<(), ErasedError> {
+        self.read_bytes(buffer).map_err(ErasedError::classified)
+    }
+}
+
+/// Lets the library's generic helpers read from a `Diagnosable`.
+struct Source(Box<dyn Diagnosable>);
+
+unsafe impl EntropySource for Source {
+    type EntropySourceError = ErasedError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        self.0.read_classified(buffer)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Hex,
+    Base64,
+    Raw,
+}
+
+struct Options {
+    source: String,
+    bytes: usize,
+    format: Format,
+    samples: usize,
+}
+
+fn parse(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        source: "os".into(),
+        bytes: 32,
+        format: Format::Hex,
+        samples: 1_000_000,
+    };
+
+    let mut args = args.iter();
+
+    while let Some(flag) = args.next() {
+        let value = match args.next() {
+            Some(value) => value.as_str(),
+            None => return Err(format!("missing value for {}", flag)),
+        };
+
+        let number = || value.parse::<usize>().map_err(|_| format!("invalid number for {}: {}", flag, value));
+
+        match flag.as_str() {
+            "--source" => options.source = value.into(),
+            "--bytes" => options.bytes = number()?,
+            "--samples" => options.samples = number()?,
+            "--format" => {
+                options.format = match value {
+                    "hex" => Format::Hex,
+                    "base64" => Format::Base64,
+                    "raw" => Format::Raw,
+                    _ => return Err(format!("unknown format: {}", value)),
+                }
+            }
+            _ => return Err(format!("unknown option: {}", flag)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn open(name: &str) -> Result<Source, String> {
+    let source: Box<dyn Diagnosable> = match name {
+        "os" => Box::new(OsEntropy),
+        "rdrand" => match RdRand::new() {
+            Ok(source) => Box::new(source),
+            Err(e) => return Err(format!("rdrand unavailable: {:?}", e)),
+        },
+        "jitter" => match JitterSource::new() {
+            Ok(source) => Box::new(source),
+            Err(e) => return Err(format!("jitter unavailable: {:?}", e)),
+        },
+        _ => return Err(format!("unknown source: {}", name)),
+    };
+
+    Ok(Source(source))
+}
+
+fn generate(source: &Source, options: &Options) -> Result<(), String> {
+    let entropy = match EntropyVec::try_generate(options.bytes, source) {
+        Ok(entropy) => entropy,
+        Err(e) => return Err(format!("source failed: {}", e)),
+    };
+
+    let mut stdout = std::io::stdout().lock();
+
+    let result = match options.format {
+        Format::Hex => writeln!(stdout, "{}", entropy.to_hex()),
+        Format::Base64 => writeln!(stdout, "{}", entropy.to_base64()),
+        Format::Raw => stdout.write_all(&entropy.bytes),
+    };
+
+    match result.and_then(|_| stdout.flush()) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(format!("writing output failed: {}", e)),
+    }
+}
+
+fn health(source: &Source, options: &Options) -> Result<(), String> {
+    match source.0.self_test() {
+        Ok(report) => println!(
+            "self test: passed ({} known-answer tests, {} health samples)",
+            report.known_answer_tests, report.health_samples
+        ),
+        Err(e) => return Err(format!("self test failed: {}", e)),
+    }
+
+    let samples = match collect(source, options.samples) {
+        Ok(samples) => samples,
+        Err(e) => return Err(format!("source failed: {}", e)),
+    };
+
+    let estimate = match estimate(&samples, 8) {
+        Ok(estimate) => estimate,
+        Err(e) => return Err(format!("estimate failed: {}", e)),
+    };
+
+    println!("most common value: {:.4} bits/byte", estimate.most_common_value);
+    println!("collision:         {:.4} bits/bit", estimate.collision);
+    println!("markov:            {:.4} bits/bit", estimate.markov);
+    println!("compression:       {:.4} bits/bit", estimate.compression);
+    println!("min-entropy:       {:.4} bits/byte", estimate.min_entropy);
+
+    Ok(())
+}
+
+fn bench(source: &Source) -> Result<(), String> {
+    let report = match measure(source, &BenchConfig::new()) {
+        Ok(report) => report,
+        Err(e) => return Err(format!("source failed: {}", e)),
+    };
+
+    println!("{:>8} {:>10} {:>12} {:>10} {:>10} {:>10}", "block", "calls", "MB/s", "p50", "p99", "max");
+
+    for block in &report.blocks {
+        println!(
+            "{:>8} {:>10} {:>12.2} {:>10?} {:>10?} {:>10?}",
+            block.block_size,
+            block.calls,
+            block.bytes_per_second / 1e6,
+            block.p50,
+            block.p99,
+            block.max
+        );
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) if command != "-h" && command != "--help" => (command.as_str(), rest),
+        _ => {
+            print!("{}", USAGE);
+
+            return ExitCode::SUCCESS;
+        }
+    };
+
+    if !["gen", "health", "bench"].contains(&command) {
+        eprint!("error: unknown command: {}\n\n{}", command, USAGE);
+
+        return ExitCode::from(2);
+    }
+
+    let options = match parse(rest) {
+        Ok(options) => options,
+        Err(e) => {
+            eprint!("error: {}\n\n{}", e, USAGE);
+
+            return ExitCode::from(2);
+        }
+    };
+
+    let result = open(&options.source).and_then(|source| match command {
+        "gen" => generate(&source, &options),
+        "health" => health(&source, &options),
+        _ => bench(&source),
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+
+            ExitCode::FAILURE
+        }
+    }
+}
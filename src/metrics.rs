@@ -0,0 +1,220 @@
+//! Instrumentation of entropy consumption.
+
+use crate::assess::{EntropyAssessment, Provenance};
+use crate::lock::Lock;
+use crate::trace;
+use crate::EntropySource;
+
+/// Receives events from `Instrumented` sources, e.g. to export them as Prometheus counters.
+///
+/// NOTE: Events are delivered synchronously from `read_bytes`, so keep the handlers cheap.
+pub trait MetricsSink {
+    /// A read of `bytes` bytes succeeded.
+    fn on_read(&self, bytes: usize) {
+        let _ = bytes;
+    }
+
+    /// A read failed.
+    fn on_failure(&self) {}
+
+    /// The generator was reseeded.
+    fn on_reseed(&self) {}
+
+    /// A `verify::CrossCheck` of supposedly independent sources ran, producing `findings` findings.
+    fn on_cross_check(&self, findings: usize) {
+        let _ = findings;
+    }
+}
+
+impl<M: MetricsSink + ?Sized> MetricsSink for &M {
+    fn on_read(&self, bytes: usize) {
+        (**self).on_read(bytes)
+    }
+
+    fn on_failure(&self) {
+        (**self).on_failure()
+    }
+
+    fn on_reseed(&self) {
+        (**self).on_reseed()
+    }
+
+    fn on_cross_check(&self, findings: usize) {
+        (**self).on_cross_check(findings)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<M: MetricsSink + ?Sized> MetricsSink for alloc::sync::Arc<M> {
+    fn on_read(&self, bytes: usize) {
+        (**self).on_read(bytes)
+    }
+
+    fn on_failure(&self) {
+        (**self).on_failure()
+    }
+
+    fn on_reseed(&self) {
+        (**self).on_reseed()
+    }
+
+    fn on_cross_check(&self, findings: usize) {
+        (**self).on_cross_check(findings)
+    }
+}
+
+/// A point-in-time copy of the `Counters`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    /// Bytes served by successful reads.
+    pub bytes: u64,
+    /// Reads attempted, successful or not.
+    pub calls: u64,
+    /// Reads that failed.
+    pub failures: u64,
+    /// Reseeds of the generator.
+    pub reseeds: u64,
+    /// Cross-checks run.
+    pub cross_checks: u64,
+    /// Findings of the cross-checks.
+    pub findings: u64,
+}
+
+/// A `MetricsSink` that simply counts events.
+pub struct Counters {
+    snapshot: Lock<MetricsSnapshot>,
+}
+
+impl Counters {
+    /// Start counting from zero.
+    pub const fn new() -> Self {
+        Self {
+            snapshot: Lock::new(MetricsSnapshot {
+                bytes: 0,
+                calls: 0,
+                failures: 0,
+                reseeds: 0,
+                cross_checks: 0,
+                findings: 0,
+            }),
+        }
+    }
+
+    /// Get the current counts.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        self.snapshot.with(|snapshot| *snapshot)
+    }
+
+    /// Reset every count to zero, returning the counts before the reset.
+    pub fn reset(&self) -> MetricsSnapshot {
+        self.snapshot.with(core::mem::take)
+    }
+}
+
+impl Default for Counters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsSink for Counters {
+    fn on_read(&self, bytes: usize) {
+        self.snapshot.with(|snapshot| {
+            snapshot.bytes = snapshot.bytes.saturating_add(bytes as u64);
+            snapshot.calls = snapshot.calls.saturating_add(1);
+        });
+    }
+
+    fn on_failure(&self) {
+        self.snapshot.with(|snapshot| {
+            snapshot.calls = snapshot.calls.saturating_add(1);
+            snapshot.failures = snapshot.failures.saturating_add(1);
+        });
+    }
+
+    fn on_reseed(&self) {
+        self.snapshot.with(|snapshot| snapshot.reseeds = snapshot.reseeds.saturating_add(1));
+    }
+
+    fn on_cross_check(&self, findings: usize) {
+        self.snapshot.with(|snapshot| {
+            snapshot.cross_checks = snapshot.cross_checks.saturating_add(1);
+            snapshot.findings = snapshot.findings.saturating_add(findings as u64);
+        });
+    }
+}
+
+/// Reports every read of the wrapped source to a `MetricsSink`.
+///
+/// NOTE: Reseeds are only seen when they go through this wrapper, e.g. with an `Instrumented` generator inside a
+/// `ReseedingSource`.
+pub struct Instrumented<S: EntropySource, M: MetricsSink = Counters> {
+    source: S,
+    sink: M,
+}
+
+impl<S: EntropySource> Instrumented<S> {
+    /// Count the reads of the provided source.
+    pub const fn new(source: S) -> Self {
+        Self::with_sink(source, Counters::new())
+    }
+}
+
+impl<S: EntropySource, M: MetricsSink> Instrumented<S, M> {
+    /// Report the reads of the provided source to a custom sink.
+    pub const fn with_sink(source: S, sink: M) -> Self {
+        Self { source, sink }
+    }
+
+    /// Get a reference to the sink.
+    pub fn sink(&self) -> &M {
+        &self.sink
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+}
+
+unsafe impl<S: EntropySource, M: MetricsSink> EntropySource for Instrumented<S, M> {
+    type EntropySourceError = S::EntropySourceError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        match self.source.read_bytes(buffer) {
+            Ok(_) => {
+                self.sink.on_read(buffer.len());
+
+                Ok(())
+            }
+            Err(e) => {
+                self.sink.on_failure();
+
+                Err(e)
+            }
+        }
+    }
+}
+
+impl<S: EntropyAssessment, M: MetricsSink> EntropyAssessment for Instrumented<S, M> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.source.provenance()
+    }
+}
+
+#[cfg(any(feature = "drbg", feature = "csprng"))]
+impl<D: crate::reseed::Reseedable, M: MetricsSink> crate::reseed::Reseedable for Instrumented<D, M> {
+    const SEED_LEN: usize = D::SEED_LEN;
+
+    fn reseed_with(&self, entropy: &[u8]) {
+        self.source.reseed_with(entropy);
+
+        self.sink.on_reseed();
+    }
+}
@@ -0,0 +1,153 @@
+//! Nonce and IV generation with misuse resistance.
+//!
+//! NOTE: A generator tracks the nonces issued under one key, so create a new one whenever the key changes and never
+//! share one between keys.
+
+use core::convert::Infallible;
+use core::error::Error;
+
+use crate::lock::Lock;
+use crate::EntropySource;
+
+#[derive(Debug)]
+pub enum NonceError<E: Error = Infallible> {
+    /// The entropy source failed.
+    Source(E),
+    /// The generator has issued as many nonces as it safely can; rekey and start a new one.
+    Exhausted,
+}
+
+impl<E: Error> core::fmt::Display for NonceError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<E: Error> Error for NonceError<E> {}
+
+/// Generates uniformly random `N` byte nonces, refusing to go near the birthday bound.
+///
+/// The limit is `2^(4N - 16)` nonces (capped at `u64::MAX`), which keeps the collision probability below `2^-32`;
+/// for 96-bit nonces that is the `2^32` invocations SP 800-38D allows for random GCM IVs.
+pub struct RandomNonceGen<const N: usize> {
+    issued: Lock<u64>,
+    limit: u64,
+}
+
+impl<const N: usize> RandomNonceGen<N> {
+    /// The default (and maximum) number of nonces issued before `Exhausted` is returned.
+    pub const LIMIT: u64 = match (4 * N).checked_sub(16) {
+        Some(bits) if bits < 64 => 1 << bits,
+        Some(_) => u64::MAX,
+        None => 1,
+    };
+
+    /// Create a new generator for a fresh key.
+    pub const fn new() -> Self {
+        Self {
+            issued: Lock::new(0),
+            limit: Self::LIMIT,
+        }
+    }
+
+    /// Lower the number of nonces issued before `Exhausted` is returned (it can never exceed `LIMIT`).
+    pub fn with_limit(mut self, limit: u64) -> Self {
+        self.limit = limit.min(Self::LIMIT);
+        self
+    }
+
+    /// Attempt to generate the next nonce from the provided `EntropySource`.
+    pub fn next_nonce<S: EntropySource>(&self, source: &S) -> Result<[u8; N], NonceError<S::EntropySourceError>> {
+        self.issued.with(|issued| {
+            if *issued >= self.limit {
+                return Err(NonceError::Exhausted);
+            }
+
+            let mut nonce = [0u8; N];
+
+            if let Err(e) = source.read_bytes(&mut nonce) {
+                return Err(NonceError::Source(e));
+            }
+
+            *issued += 1;
+
+            Ok(nonce)
+        })
+    }
+
+    /// Get the number of nonces issued so far.
+    pub fn issued(&self) -> u64 {
+        self.issued.with(|issued| *issued)
+    }
+
+    /// Get the number of nonces left before `Exhausted` is returned.
+    pub fn remaining(&self) -> u64 {
+        self.limit - self.issued()
+    }
+}
+
+impl<const N: usize> Default for RandomNonceGen<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Generates `N` byte nonces from a random per-session prefix followed by a big-endian counter.
+///
+/// The counter (the last `min(N, 8)` bytes) guarantees every nonce within a session is unique, and the random prefix
+/// (the first `N - 8` bytes, if any) separates sessions under the same key. This is the deterministic construction of
+/// SP 800-38D with a random fixed field.
+///
+/// NOTE: Sessions only stay apart with a long enough prefix; at 96 bits that is 32 bits, so keep the number of
+/// sessions per key well below `2^16`.
+pub struct HybridNonceGen<const N: usize> {
+    prefix: [u8; N],
+    counter: Lock<u64>,
+}
+
+impl<const N: usize> HybridNonceGen<N> {
+    /// The number of nonces issued before `Exhausted` is returned.
+    pub const LIMIT: u64 = match N {
+        0 => 1,
+        n if n >= 8 => u64::MAX,
+        n => 1 << (8 * n),
+    };
+
+    /// Attempt to start a new session, drawing the random prefix from the provided `EntropySource`.
+    pub fn new<S: EntropySource>(source: &S) -> Result<Self, NonceError<S::EntropySourceError>> {
+        let mut prefix = [0u8; N];
+
+        if let Err(e) = source.read_bytes(&mut prefix[..N.saturating_sub(8)]) {
+            return Err(NonceError::Source(e));
+        }
+
+        Ok(Self {
+            prefix,
+            counter: Lock::new(0),
+        })
+    }
+
+    /// Attempt to generate the next nonce.
+    pub fn next_nonce(&self) -> Result<[u8; N], NonceError> {
+        self.counter.with(|counter| {
+            if *counter >= Self::LIMIT {
+                return Err(NonceError::Exhausted);
+            }
+
+            let width = N.min(8);
+
+            let mut nonce = self.prefix;
+
+            nonce[N - width..].copy_from_slice(&counter.to_be_bytes()[8 - width..]);
+
+            *counter += 1;
+
+            Ok(nonce)
+        })
+    }
+
+    /// Get the number of nonces issued so far.
+    pub fn issued(&self) -> u64 {
+        self.counter.with(|counter| *counter)
+    }
+}
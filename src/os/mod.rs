@@ -0,0 +1,430 @@
+use core::error::Error;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{Classify, ErrorKind};
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+#[cfg(feature = "os-native")]
+mod native;
+
+#[cfg(all(
+    feature = "os-native",
+    not(feature = "os"),
+    not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_vendor = "apple",
+        target_os = "openbsd",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "dragonfly"
+    ))
+))]
+compile_error!("`os-native` has no backend for this target, enable the `os` feature to use `getrandom` instead");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsEntropySourceError {
+    /// The `getrandom` crate failed, with its error code preserved.
+    #[cfg(feature = "os")]
+    Getrandom(getrandom::Error),
+    /// A native call (the `os-native` backend, or `getrandom(2)` with `OsOptions`) failed with the given `errno`.
+    Native(i32),
+    /// A strict source refused to read, since `getrandom` would have used a known-insecure fallback.
+    InsecureBackend,
+}
+
+impl OsEntropySourceError {
+    /// Get the underlying OS error code, if any.
+    pub fn raw_os_error(&self) -> Option<i32> {
+        match *self {
+            #[cfg(feature = "os")]
+            Self::Getrandom(e) => e.raw_os_error(),
+            Self::Native(errno) => Some(errno),
+            Self::InsecureBackend => None,
+        }
+    }
+
+    /// Get the `getrandom` error, if `getrandom` failed.
+    #[cfg(feature = "os")]
+    pub fn getrandom_error(&self) -> Option<getrandom::Error> {
+        match *self {
+            Self::Getrandom(e) => Some(e),
+            Self::Native(_) => None,
+            Self::InsecureBackend => None,
+        }
+    }
+
+    /// Whether the platform (or its configuration, e.g. a seccomp filter) does not support the entropy source.
+    ///
+    /// NOTE: Callers should fall back to another source rather than retry.
+    pub fn is_unsupported(&self) -> bool {
+        if *self == Self::InsecureBackend {
+            return true;
+        }
+
+        #[cfg(feature = "os")]
+        if let Some(e) = self.getrandom_error() {
+            if matches!(e, getrandom::Error::UNSUPPORTED | getrandom::Error::NO_RDRAND | getrandom::Error::NODE_ES_MODULE) {
+                return true;
+            }
+        }
+
+        self.raw_os_error().map(classify) == Some(ErrorClass::Unsupported)
+    }
+
+    /// Whether the failure is transient (e.g. interrupted, would block, or out of memory) and worth retrying.
+    pub fn is_transient(&self) -> bool {
+        #[cfg(feature = "os")]
+        if self.getrandom_error() == Some(getrandom::Error::FAILED_RDRAND) {
+            return true;
+        }
+
+        self.raw_os_error().map(classify) == Some(ErrorClass::Transient)
+    }
+}
+
+impl core::fmt::Display for OsEntropySourceError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for OsEntropySourceError {}
+
+impl Classify for OsEntropySourceError {
+    fn kind(&self) -> ErrorKind {
+        match (self.is_unsupported(), self.is_transient()) {
+            (true, _) => ErrorKind::Unsupported,
+            (_, true) => ErrorKind::Transient,
+            _ => ErrorKind::Backend(self.raw_os_error().unwrap_or(0)),
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum ErrorClass {
+    Unsupported,
+    Transient,
+    Other,
+}
+
+/// Classify an OS error code (by `std::io::ErrorKind` with `std`, otherwise by `errno` on Unix).
+fn classify(code: i32) -> ErrorClass {
+    #[cfg(feature = "std")]
+    return match std::io::Error::from_raw_os_error(code).kind() {
+        std::io::ErrorKind::Unsupported => ErrorClass::Unsupported,
+        std::io::ErrorKind::Interrupted | std::io::ErrorKind::WouldBlock | std::io::ErrorKind::OutOfMemory => {
+            ErrorClass::Transient
+        }
+        _ => ErrorClass::Other,
+    };
+
+    #[cfg(all(not(feature = "std"), unix))]
+    return match code {
+        libc::ENOSYS | libc::EOPNOTSUPP => ErrorClass::Unsupported,
+        libc::EINTR | libc::EAGAIN | libc::ENOMEM => ErrorClass::Transient,
+        _ => ErrorClass::Other,
+    };
+
+    #[cfg(not(any(feature = "std", unix)))]
+    {
+        let _ = code;
+
+        ErrorClass::Other
+    }
+}
+
+/// Entropy from the underlying Operating System.
+///
+/// NOTE: Implemented using the cross-platform `getrandom` crate, or with `os-native`, by calling the platform directly
+/// (`getrandom(2)` on Linux/Android, `getentropy(2)` on Apple platforms/OpenBSD, `arc4random_buf(3)` on
+/// FreeBSD/NetBSD/DragonFly). With both features, `getrandom` is only used on targets without a native backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OsEntropy;
+
+/// How reads from a `ConfiguredOsEntropy` behave.
+///
+/// NOTE: `nonblock` and `allow_insecure_early_boot` choose how reads behave while the OS pool is not yet seeded
+/// (early boot, e.g. in an init system or initramfs). Only Linux/Android have these flags
+/// (`GRND_NONBLOCK`/`GRND_INSECURE`); elsewhere they are ignored, as the OS interfaces used there block until seeded
+/// (or are always seeded).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OsOptions {
+    /// Fail immediately (with an `is_transient` error, `EAGAIN`) instead of blocking until seeded.
+    pub nonblock: bool,
+    /// Serve bytes before the pool is seeded instead of blocking, which are *not* suitable for key material.
+    ///
+    /// NOTE: Kernels before 5.6 lack `GRND_INSECURE`; the read is then made with `GRND_NONBLOCK` instead.
+    pub allow_insecure_early_boot: bool,
+    /// Refuse to read (with `InsecureBackend`) whenever `getrandom` would use a known-insecure fallback.
+    ///
+    /// NOTE: Rejected are the `/dev/urandom` fallback on Linux/Android (a kernel before 3.17, or `getrandom(2)`
+    /// blocked by seccomp), and targets with no OS source at all, where `getrandom` can only be served by a `custom`
+    /// or `rdrand` build, or by the JS shim (`wasm32-unknown-unknown`, whose shim cannot be told apart from a custom
+    /// one; use `WebCrypto` there instead). Native backends are never rejected.
+    pub strict: bool,
+}
+
+impl OsEntropy {
+    /// Configure how reads behave.
+    ///
+    /// NOTE: On Linux/Android, `nonblock` or `allow_insecure_early_boot` always call `getrandom(2)` directly, even
+    /// with only `os`.
+    pub const fn with_options(options: OsOptions) -> ConfiguredOsEntropy {
+        ConfiguredOsEntropy { options }
+    }
+
+    /// Refuse to read whenever `getrandom` would use a known-insecure fallback (see `OsOptions::strict`).
+    pub const fn strict() -> ConfiguredOsEntropy {
+        Self::with_options(OsOptions {
+            nonblock: false,
+            allow_insecure_early_boot: false,
+            strict: true,
+        })
+    }
+}
+
+/// Entropy from the underlying Operating System, as from `OsEntropy` but with `OsOptions`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ConfiguredOsEntropy {
+    options: OsOptions,
+}
+
+impl ConfiguredOsEntropy {
+    /// Whether the source is strict.
+    pub fn is_strict(&self) -> bool {
+        self.options.strict
+    }
+
+    /// Get the options.
+    pub fn options(&self) -> OsOptions {
+        self.options
+    }
+}
+
+fn fill(bytes: &mut [u8], options: OsOptions) -> Result<(), OsEntropySourceError> {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    if options.nonblock || options.allow_insecure_early_boot {
+        let nonblock = match options.nonblock {
+            true => libc::GRND_NONBLOCK,
+            false => 0,
+        };
+
+        let result = match options.allow_insecure_early_boot {
+            true => match getrandom_with_flags(bytes, nonblock | libc::GRND_INSECURE) {
+                Err(libc::EINVAL) => getrandom_with_flags(bytes, libc::GRND_NONBLOCK),
+                result => result,
+            },
+            false => getrandom_with_flags(bytes, nonblock),
+        };
+
+        return result.map_err(OsEntropySourceError::Native);
+    }
+
+    #[cfg(feature = "os-native")]
+    if let Some(result) = native::fill(bytes) {
+        return match result {
+            Ok(_) => Ok(()),
+            Err(errno) => Err(OsEntropySourceError::Native(errno)),
+        };
+    }
+
+    #[cfg(feature = "os")]
+    if options.strict && insecure_fallback() {
+        trace::event!(error, "getrandom would use an insecure fallback");
+
+        return Err(OsEntropySourceError::InsecureBackend);
+    }
+
+    #[cfg(feature = "os")]
+    return match getrandom::getrandom(bytes) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(OsEntropySourceError::Getrandom(e)),
+    };
+
+    #[cfg(not(feature = "os"))]
+    {
+        let _ = options;
+
+        unreachable!()
+    }
+}
+
+unsafe impl EntropySource for OsEntropy {
+    type EntropySourceError = OsEntropySourceError;
+
+    fn read_bytes(&self, bytes: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(bytes.len());
+
+        fill(bytes, OsOptions::default())
+    }
+}
+
+unsafe impl EntropySource for ConfiguredOsEntropy {
+    type EntropySourceError = OsEntropySourceError;
+
+    fn read_bytes(&self, bytes: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(bytes.len());
+
+        fill(bytes, self.options)
+    }
+}
+
+impl EntropyAssessment for OsEntropy {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Os,
+            basis: Basis::Generator,
+        }
+    }
+}
+
+/// NOTE: Credited as `OsEntropy`, even with `allow_insecure_early_boot`, whose early bytes are not.
+impl EntropyAssessment for ConfiguredOsEntropy {
+    fn min_entropy_per_byte(&self) -> f64 {
+        OsEntropy.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        OsEntropy.provenance()
+    }
+}
+
+impl SelfTest for OsEntropy {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
+
+impl SelfTest for ConfiguredOsEntropy {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn errno() -> i32 {
+    // SAFETY: `__errno_location` always returns a valid thread-local pointer.
+    unsafe { *libc::__errno_location() }
+}
+
+/// Fill `bytes` by calling `getrandom(2)` with the given flags, returning the `errno` on failure.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn getrandom_with_flags(bytes: &mut [u8], flags: libc::c_uint) -> Result<(), i32> {
+    let mut filled = 0;
+
+    while filled < bytes.len() {
+        let remaining = &mut bytes[filled..];
+
+        // SAFETY: The pointer and length describe the unfilled (valid, writable) tail of `bytes`.
+        let result = unsafe { libc::getrandom(remaining.as_mut_ptr().cast(), remaining.len(), flags) };
+
+        if result < 0 {
+            match errno() {
+                libc::EINTR => continue,
+                errno => return Err(errno),
+            }
+        }
+
+        filled += result as usize;
+    }
+
+    Ok(())
+}
+
+/// Whether `getrandom` would fall back to `/dev/urandom`, since `getrandom(2)` is missing or blocked.
+///
+/// NOTE: Mirrors the check made by `getrandom` itself. Only a positive result is cached, as `getrandom` does.
+#[cfg(all(feature = "os", any(target_os = "linux", target_os = "android")))]
+fn insecure_fallback() -> bool {
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    static AVAILABLE: AtomicBool = AtomicBool::new(false);
+
+    if AVAILABLE.load(Ordering::Relaxed) {
+        return false;
+    }
+
+    // SAFETY: A zero-length request writes nothing; `GRND_NONBLOCK` keeps it from waiting for the pool.
+    let result = unsafe { libc::syscall(libc::SYS_getrandom, core::ptr::null_mut::<u8>(), 0usize, libc::GRND_NONBLOCK) };
+
+    let available = result >= 0 || !matches!(errno(), libc::ENOSYS | libc::EPERM);
+
+    AVAILABLE.store(available, Ordering::Relaxed);
+
+    !available
+}
+
+/// Whether `getrandom` would use a known-insecure fallback; never on targets where it calls an OS source.
+#[cfg(all(
+    feature = "os",
+    any(
+        windows,
+        target_vendor = "apple",
+        target_os = "openbsd",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "dragonfly",
+        target_os = "illumos",
+        target_os = "solaris",
+        target_os = "hurd",
+        target_os = "haiku",
+        target_os = "redox",
+        target_os = "nto",
+        target_os = "aix",
+        target_os = "vita",
+        target_os = "emscripten",
+        target_os = "fuchsia",
+        target_os = "hermit",
+        target_os = "vxworks",
+        target_os = "solid_asp3",
+        target_os = "espidf",
+        target_os = "horizon",
+        target_os = "cygwin",
+        target_os = "wasi"
+    )
+))]
+fn insecure_fallback() -> bool {
+    false
+}
+
+/// Whether `getrandom` would use a known-insecure fallback; always on targets without an OS source.
+#[cfg(all(
+    feature = "os",
+    not(any(
+        target_os = "linux",
+        target_os = "android",
+        windows,
+        target_vendor = "apple",
+        target_os = "openbsd",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "dragonfly",
+        target_os = "illumos",
+        target_os = "solaris",
+        target_os = "hurd",
+        target_os = "haiku",
+        target_os = "redox",
+        target_os = "nto",
+        target_os = "aix",
+        target_os = "vita",
+        target_os = "emscripten",
+        target_os = "fuchsia",
+        target_os = "hermit",
+        target_os = "vxworks",
+        target_os = "solid_asp3",
+        target_os = "espidf",
+        target_os = "horizon",
+        target_os = "cygwin",
+        target_os = "wasi"
+    ))
+))]
+fn insecure_fallback() -> bool {
+    true
+}
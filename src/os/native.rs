@@ -0,0 +1,59 @@
+//! Native OS backends used by the `os-native` feature.
+//!
+//! NOTE: Each `fill` returns `None` when the target has no native backend, and otherwise the `errno` on failure.
+
+/// Maximum length of a single `getentropy(2)` call.
+#[cfg(any(target_vendor = "apple", target_os = "openbsd"))]
+const GETENTROPY_MAX: usize = 256;
+
+#[cfg(any(target_vendor = "apple", target_os = "openbsd"))]
+fn errno() -> i32 {
+    // SAFETY: `__error`/`__errno` always return a valid thread-local pointer.
+    #[cfg(target_vendor = "apple")]
+    unsafe {
+        *libc::__error()
+    }
+
+    #[cfg(target_os = "openbsd")]
+    unsafe {
+        *libc::__errno()
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(crate) fn fill(bytes: &mut [u8]) -> Option<Result<(), i32>> {
+    Some(super::getrandom_with_flags(bytes, 0))
+}
+
+#[cfg(any(target_vendor = "apple", target_os = "openbsd"))]
+pub(crate) fn fill(bytes: &mut [u8]) -> Option<Result<(), i32>> {
+    for chunk in bytes.chunks_mut(GETENTROPY_MAX) {
+        // SAFETY: The pointer and length describe `chunk`, which is at most `GETENTROPY_MAX` bytes.
+        if unsafe { libc::getentropy(chunk.as_mut_ptr().cast(), chunk.len()) } != 0 {
+            return Some(Err(errno()));
+        }
+    }
+
+    Some(Ok(()))
+}
+
+#[cfg(any(target_os = "freebsd", target_os = "netbsd", target_os = "dragonfly"))]
+pub(crate) fn fill(bytes: &mut [u8]) -> Option<Result<(), i32>> {
+    // SAFETY: The pointer and length describe `bytes`. `arc4random_buf` cannot fail.
+    unsafe { libc::arc4random_buf(bytes.as_mut_ptr().cast(), bytes.len()) };
+
+    Some(Ok(()))
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_vendor = "apple",
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+)))]
+pub(crate) fn fill(_bytes: &mut [u8]) -> Option<Result<(), i32>> {
+    None
+}
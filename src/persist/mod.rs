@@ -0,0 +1,11 @@
+//! Persisting seeds and pool state across restarts.
+
+#[cfg(feature = "nv-pool")]
+mod nv;
+#[cfg(feature = "persist")]
+mod seed_file;
+
+#[cfg(feature = "nv-pool")]
+pub use nv::{NvPool, NvPoolError, NvStorage, RECORD_LEN};
+#[cfg(feature = "persist")]
+pub use seed_file::{SeedFile, SeedFileError, SEED_LEN};
@@ -0,0 +1,357 @@
+use core::error::Error;
+use core::fmt::Debug;
+
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{Classify, ErrorKind};
+use crate::lock::Lock;
+use crate::trace;
+use crate::EntropySource;
+
+/// Size of a stored checkpoint record; every storage slot must hold at least this many bytes.
+pub const RECORD_LEN: usize = 4 + 8 + 32 + 8;
+
+/// Marks a slot as holding a checkpoint record (erased flash reads as all ones).
+const MAGIC: [u8; 4] = *b"LNV1";
+
+/// Default number of output bytes after which a scheduled checkpoint is due.
+const DEFAULT_CHECKPOINT_INTERVAL: u64 = 1 << 16;
+
+/// Domain separation prefix for the key restored from a record.
+const RESTORE_DOMAIN: &[u8] = b"librypt-entropy nv pool restore";
+
+/// Domain separation prefix for the key written to a record.
+const STORE_DOMAIN: &[u8] = b"librypt-entropy nv pool store";
+
+/// Domain separation prefix for the key kept in memory after a checkpoint or read.
+const RATCHET_DOMAIN: &[u8] = b"librypt-entropy nv pool ratchet";
+
+/// Domain separation prefix for output blocks.
+const OUTPUT_DOMAIN: &[u8] = b"librypt-entropy nv pool output";
+
+/// Domain separation prefix for mixed-in data.
+const MIX_DOMAIN: &[u8] = b"librypt-entropy nv pool mix";
+
+#[derive(Debug)]
+pub enum NvPoolError<E: Debug> {
+    /// Erasing, writing or reading the storage failed.
+    Storage(E),
+    /// The storage has no slots.
+    NoSlots,
+    /// No valid checkpoint was found and the pool has not been reseeded since.
+    NotSeeded,
+}
+
+impl<E: Debug> core::fmt::Display for NvPoolError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<E: Debug> Error for NvPoolError<E> {}
+
+impl<E: Debug> Classify for NvPoolError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Storage(_) => ErrorKind::Backend(0),
+            Self::NoSlots => ErrorKind::Unsupported,
+            Self::NotSeeded => ErrorKind::Exhausted,
+        }
+    }
+}
+
+/// Non-volatile storage divided into independently erasable slots (e.g. flash pages or EEPROM rows).
+pub trait NvStorage {
+    type Error: Debug;
+
+    /// Get the number of slots (at least 2 for power-fail safety).
+    fn slots(&self) -> usize;
+
+    /// Erase a slot.
+    fn erase(&mut self, slot: usize) -> Result<(), Self::Error>;
+
+    /// Write `data` (`RECORD_LEN` bytes) to the start of an erased slot.
+    fn write(&mut self, slot: usize, data: &[u8]) -> Result<(), Self::Error>;
+
+    /// Read `data.len()` (`RECORD_LEN`) bytes from the start of a slot.
+    fn read(&mut self, slot: usize, data: &mut [u8]) -> Result<(), Self::Error>;
+}
+
+fn hash(domain: &[u8], inputs: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+
+    hasher.update(domain);
+
+    for input in inputs {
+        hasher.update((input.len() as u64).to_le_bytes());
+        hasher.update(input);
+    }
+
+    hasher.finalize().into()
+}
+
+fn check(record: &[u8]) -> [u8; 8] {
+    let digest = Sha256::digest(&record[..RECORD_LEN - 8]);
+
+    let mut check = [0u8; 8];
+
+    check.copy_from_slice(&digest[..8]);
+
+    check
+}
+
+struct NvState<T: NvStorage> {
+    storage: T,
+    key: [u8; 32],
+    seeded: bool,
+    sequence: u64,
+    next_slot: usize,
+    since_checkpoint: u64,
+    changed: bool,
+}
+
+impl<T: NvStorage> NvState<T> {
+    /// Write a checkpoint to the next slot in turn, then move the in-memory key past it.
+    fn checkpoint(&mut self) -> Result<(), NvPoolError<T::Error>> {
+        let mut record = [0u8; RECORD_LEN];
+        let sequence = self.sequence.wrapping_add(1);
+
+        record[..4].copy_from_slice(&MAGIC);
+        record[4..12].copy_from_slice(&sequence.to_le_bytes());
+        record[12..44].copy_from_slice(&hash(STORE_DOMAIN, &[&self.key]));
+
+        let check = check(&record);
+
+        record[44..].copy_from_slice(&check);
+
+        let result = match self.storage.erase(self.next_slot) {
+            Ok(()) => self.storage.write(self.next_slot, &record),
+            Err(e) => Err(e),
+        };
+
+        record.zeroize();
+
+        if let Err(e) = result {
+            return Err(NvPoolError::Storage(e));
+        }
+
+        let mut key = hash(RATCHET_DOMAIN, &[&self.key]);
+
+        self.key.copy_from_slice(&key);
+
+        key.zeroize();
+
+        self.sequence = sequence;
+        self.next_slot = (self.next_slot + 1) % self.storage.slots();
+        self.since_checkpoint = 0;
+        self.changed = false;
+
+        Ok(())
+    }
+
+    fn mix(&mut self, data: &[u8]) {
+        let mut key = hash(MIX_DOMAIN, &[&self.key, data]);
+
+        self.key.copy_from_slice(&key);
+
+        key.zeroize();
+
+        self.changed = true;
+    }
+}
+
+impl<T: NvStorage> Drop for NvState<T> {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+/// An entropy pool whose state is checkpointed to non-volatile storage, so microcontrollers without a TRNG (or with a
+/// slow one) carry entropy across resets and power cycles.
+///
+/// Checkpoints rotate over the storage slots, so each slot is erased once per `slots()` checkpoints, and scheduled
+/// checkpoints are skipped until enough output has been generated or new entropy has been mixed in. Output is
+/// generated with SHA-256 from an in-memory key that is ratcheted forward after every read.
+///
+/// NOTE: `restore` consumes the stored state: it writes a successor checkpoint before the pool produces any output, so
+/// a reset without a later checkpoint never repeats output. Restoring an old copy of the storage (a physical rollback)
+/// does repeat output, so mix in something fresh after every restore (`reseed` from any available noise source, or at
+/// least `mix` a unique value such as an RTC reading).
+pub struct NvPool<T: NvStorage> {
+    state: Lock<NvState<T>>,
+    checkpoint_interval: u64,
+}
+
+impl<T: NvStorage> NvPool<T> {
+    /// Attempt to restore the pool from the newest valid checkpoint in `storage` and immediately write its successor.
+    ///
+    /// NOTE: Without a valid checkpoint (e.g. on first boot) the pool starts unseeded and reads fail until `reseed`.
+    pub fn restore(mut storage: T) -> Result<Self, NvPoolError<T::Error>> {
+        let slots = storage.slots();
+
+        if slots == 0 {
+            return Err(NvPoolError::NoSlots);
+        }
+
+        let mut newest: Option<(u64, usize, [u8; 32])> = None;
+        let mut record = [0u8; RECORD_LEN];
+
+        for slot in 0..slots {
+            if let Err(e) = storage.read(slot, &mut record) {
+                record.zeroize();
+
+                return Err(NvPoolError::Storage(e));
+            }
+
+            if record[..4] != MAGIC || record[44..] != check(&record) {
+                continue;
+            }
+
+            let sequence = u64::from_le_bytes(record[4..12].try_into().unwrap());
+
+            if newest.map_or(true, |(newest, _, _)| sequence > newest) {
+                let mut stored = [0u8; 32];
+
+                stored.copy_from_slice(&record[12..44]);
+
+                newest = Some((sequence, slot, stored));
+            }
+        }
+
+        record.zeroize();
+
+        let mut state = NvState {
+            storage,
+            key: [0u8; 32],
+            seeded: false,
+            sequence: 0,
+            next_slot: 0,
+            since_checkpoint: 0,
+            changed: false,
+        };
+
+        if let Some((sequence, slot, mut stored)) = newest {
+            state.key = hash(RESTORE_DOMAIN, &[&stored, &sequence.to_le_bytes()]);
+            state.seeded = true;
+            state.sequence = sequence;
+            state.next_slot = (slot + 1) % slots;
+
+            stored.zeroize();
+
+            state.checkpoint()?;
+        }
+
+        Ok(Self {
+            state: Lock::new(state),
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+        })
+    }
+
+    /// Set the number of output bytes after which `checkpoint` writes again even if nothing was mixed in.
+    pub fn with_checkpoint_interval(mut self, bytes: u64) -> Self {
+        self.checkpoint_interval = bytes;
+        self
+    }
+
+    /// Attempt to seed the pool with 32 bytes from the provided `EntropySource`.
+    pub fn reseed<S: EntropySource>(&self, source: &S) -> Result<(), S::EntropySourceError> {
+        let mut entropy = [0u8; 32];
+
+        let result = source.read_bytes(&mut entropy);
+
+        if result.is_ok() {
+            self.state.with(|state| {
+                state.mix(&entropy);
+                state.seeded = true;
+            });
+
+            trace::event!(debug, "nv pool reseeded");
+        }
+
+        entropy.zeroize();
+
+        result
+    }
+
+    /// Mix data into the pool without counting it as seeding (e.g. a device serial number or an RTC reading).
+    pub fn mix(&self, data: &[u8]) {
+        self.state.with(|state| state.mix(data));
+    }
+
+    /// Get whether a scheduled checkpoint is due: the pool changed, or the checkpoint interval has been generated.
+    pub fn checkpoint_due(&self) -> bool {
+        self.state
+            .with(|state| state.seeded && (state.changed || state.since_checkpoint >= self.checkpoint_interval))
+    }
+
+    /// Attempt to write a checkpoint if one is due, returning whether one was written.
+    pub fn checkpoint(&self) -> Result<bool, NvPoolError<T::Error>> {
+        if !self.checkpoint_due() {
+            return Ok(false);
+        }
+
+        self.checkpoint_now().map(|_| true)
+    }
+
+    /// Attempt to write a checkpoint regardless of the schedule (e.g. before a planned power-down).
+    pub fn checkpoint_now(&self) -> Result<(), NvPoolError<T::Error>> {
+        self.state.with(|state| match state.seeded {
+            true => state.checkpoint(),
+            false => Err(NvPoolError::NotSeeded),
+        })
+    }
+
+    /// Get the sequence number of the last checkpoint written.
+    pub fn sequence(&self) -> u64 {
+        self.state.with(|state| state.sequence)
+    }
+}
+
+unsafe impl<T: NvStorage> EntropySource for NvPool<T> {
+    type EntropySourceError = NvPoolError<T::Error>;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.state.with(|state| {
+            if !state.seeded {
+                return Err(NvPoolError::NotSeeded);
+            }
+
+            for (counter, chunk) in buffer.chunks_mut(32).enumerate() {
+                let mut block = hash(OUTPUT_DOMAIN, &[&state.key, &(counter as u64).to_le_bytes()]);
+
+                chunk.copy_from_slice(&block[..chunk.len()]);
+
+                block.zeroize();
+            }
+
+            let mut key = hash(RATCHET_DOMAIN, &[&state.key]);
+
+            state.key.copy_from_slice(&key);
+
+            key.zeroize();
+
+            state.since_checkpoint = state.since_checkpoint.saturating_add(buffer.len() as u64);
+
+            Ok(())
+        })
+    }
+}
+
+/// NOTE: Reads fail until the pool is seeded, after which it is credited as a generator. That credit assumes the
+/// storage was never rolled back (see `NvPool`).
+impl<T: NvStorage> EntropyAssessment for NvPool<T> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Other,
+            basis: Basis::Generator,
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use core::convert::Infallible;
+use std::error::Error;
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+use crate::{Entropy, EntropySource};
+
+/// Size of a stored seed.
+pub const SEED_LEN: usize = 32;
+
+/// Domain separation prefix for the seed handed out by `load`.
+const OUTPUT_DOMAIN: &[u8] = b"librypt-entropy seed file output";
+
+/// Domain separation prefix for the seed written back by `load`.
+const NEXT_DOMAIN: &[u8] = b"librypt-entropy seed file next";
+
+/// Domain separation prefix for the seed written by `save`.
+const SAVE_DOMAIN: &[u8] = b"librypt-entropy seed file save";
+
+#[derive(Debug)]
+pub enum SeedFileError<E: Error = Infallible> {
+    /// Reading or writing the seed file failed.
+    Io(std::io::Error),
+    /// The entropy source failed while saving.
+    Source(E),
+    /// The seed file is readable or writable by other users, so it may have been observed or tampered with.
+    InsecurePermissions,
+    /// The seed file does not contain a seed.
+    InvalidSeed,
+}
+
+impl<E: Error> std::fmt::Display for SeedFileError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<E: Error> Error for SeedFileError<E> {}
+
+impl<E: Error> From<std::io::Error> for SeedFileError<E> {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// A seed file in the style of OpenSSL's `~/.rnd`: saved at shutdown, loaded (and mixed in) at startup.
+///
+/// NOTE: Files are written atomically (temporary file, `fsync`, rename) and are only accessible by their owner. A
+/// stored seed is never handed out twice: `load` replaces it with a one-way successor before returning anything.
+pub struct SeedFile {
+    path: PathBuf,
+}
+
+impl SeedFile {
+    /// Use the seed file at the given path.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Get the path of the seed file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Attempt to save a fresh seed conditioned from the provided `EntropySource` (e.g. a seeded DRBG at shutdown).
+    pub fn save<S: EntropySource>(&self, source: &S) -> Result<(), SeedFileError<S::EntropySourceError>> {
+        let mut entropy = [0u8; SEED_LEN];
+
+        if let Err(e) = source.read_bytes(&mut entropy) {
+            return Err(SeedFileError::Source(e));
+        }
+
+        let mut seed = condition(SAVE_DOMAIN, &entropy);
+
+        entropy.zeroize();
+
+        let result = self.write(&seed);
+
+        seed.zeroize();
+
+        Ok(result?)
+    }
+
+    /// Attempt to load the stored seed, to be mixed into a generator (e.g. with `Reseedable::reseed_with` or as a
+    /// Fortuna event).
+    ///
+    /// NOTE: The stored seed is replaced before this returns, and the returned seed is derived separately from its
+    /// replacement, so a crash before the next `save` never leads to the same seed being loaded twice.
+    pub fn load(&self) -> Result<Entropy<SEED_LEN>, SeedFileError> {
+        let mut seed = self.read()?;
+        let mut next = condition(NEXT_DOMAIN, &seed);
+
+        let result = self.write(&next);
+
+        next.zeroize();
+
+        let output = Entropy {
+            bytes: condition(OUTPUT_DOMAIN, &seed),
+        };
+
+        seed.zeroize();
+
+        result?;
+
+        Ok(output)
+    }
+
+    fn read(&self) -> Result<[u8; SEED_LEN], SeedFileError> {
+        let mut file = File::open(&self.path)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            if file.metadata()?.permissions().mode() & 0o077 != 0 {
+                return Err(SeedFileError::InsecurePermissions);
+            }
+        }
+
+        let mut seed = [0u8; SEED_LEN];
+        let mut extra = [0u8; 1];
+
+        let result = match file.read_exact(&mut seed) {
+            Ok(_) => match file.read(&mut extra) {
+                Ok(0) => Ok(seed),
+                Ok(_) => Err(SeedFileError::InvalidSeed),
+                Err(e) => Err(SeedFileError::Io(e)),
+            },
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => Err(SeedFileError::InvalidSeed),
+            Err(e) => Err(SeedFileError::Io(e)),
+        };
+
+        seed.zeroize();
+
+        result
+    }
+
+    /// Atomically replace the seed file.
+    fn write(&self, seed: &[u8; SEED_LEN]) -> std::io::Result<()> {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+
+        name.push(".tmp");
+
+        let temporary = self.path.with_file_name(name);
+
+        let result = (|| {
+            let mut options = OpenOptions::new();
+
+            options.write(true).create(true).truncate(true);
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+
+                options.mode(0o600);
+            }
+
+            let mut file = options.open(&temporary)?;
+
+            // NOTE: `mode` only applies to newly created files, so a stale temporary file is tightened explicitly.
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+
+                file.set_permissions(fs::Permissions::from_mode(0o600))?;
+            }
+
+            file.write_all(seed)?;
+            file.sync_all()?;
+
+            fs::rename(&temporary, &self.path)?;
+
+            #[cfg(unix)]
+            if let Some(parent) = self.path.parent() {
+                let parent = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+
+                File::open(parent)?.sync_all()?;
+            }
+
+            Ok(())
+        })();
+
+        if result.is_err() {
+            let _ = fs::remove_file(&temporary);
+        }
+
+        result
+    }
+}
+
+fn condition(domain: &[u8], input: &[u8]) -> [u8; SEED_LEN] {
+    let mut hasher = Sha256::new();
+
+    hasher.update(domain);
+    hasher.update(input);
+
+    hasher.finalize().into()
+}
@@ -0,0 +1,303 @@
+//! Policy-driven combining of multiple sources: quorums per class of source, and entropy credited per source.
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::error::Error;
+
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+pub use crate::assess::SourceClass;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance};
+use crate::erased::ErasedEntropySource;
+use crate::error::{Classify, ErrorKind};
+use crate::trace;
+use crate::EntropySource;
+
+/// Domain separation prefix for the conditioning hash.
+const POLICY_DOMAIN: &[u8] = b"librypt-entropy policy v1";
+
+/// Entropy credited to the input of each 32-byte output block, in bits.
+///
+/// NOTE: The 64 bits beyond the output length are the margin SP 800-90B requires for full-entropy output from a
+/// vetted conditioning function.
+const BLOCK_INPUT_BITS: f64 = 256.0 + 64.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyError {
+    /// The mandatory source at the given index (in order of addition) failed, with the kind of its error.
+    MandatoryFailed(usize, ErrorKind),
+    /// Fewer sources of the class than required succeeded.
+    QuorumNotMet(SourceClass),
+    /// The sources which succeeded are not credited with enough entropy (e.g. only zero-weight sources are left).
+    InsufficientEntropy,
+}
+
+impl core::fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for PolicyError {}
+
+impl Classify for PolicyError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::MandatoryFailed(_, kind) => *kind,
+            Self::QuorumNotMet(_) | Self::InsufficientEntropy => ErrorKind::Exhausted,
+        }
+    }
+}
+
+struct Member {
+    source: Box<dyn ErasedEntropySource + Send + Sync>,
+    class: SourceClass,
+    entropy_per_byte: f64,
+    mandatory: bool,
+}
+
+/// Conditioned output from a set of sources, produced only while a policy over them is satisfied.
+///
+/// Every source is credited with an entropy rate (e.g. 8 bits per byte for the OS, 0.3 for raw jitter), and enough
+/// input is read for each 32-byte block of output to be credited with 320 bits before hashing it through SHA-256.
+/// Quorums (e.g. at least one `Hardware` source) and mandatory sources must also hold for every block.
+///
+/// NOTE: A failed optional source is left out of the current read, and the others are read from again to make up
+/// its credit. Wrap raw noise sources in `Monitored`, so that failing health tests exclude them (or fail the read if
+/// they are mandatory).
+pub struct PolicySource {
+    members: Vec<Member>,
+    quorums: Vec<(SourceClass, usize)>,
+}
+
+impl PolicySource {
+    /// Create a policy with no sources and no rules.
+    pub fn new() -> Self {
+        Self {
+            members: Vec::new(),
+            quorums: Vec::new(),
+        }
+    }
+
+    /// Add a source of the given class, credited with the given min-entropy per output byte (in bits, `0.0..=8.0`).
+    pub fn with_source<S>(self, source: S, class: SourceClass, entropy_per_byte: f64) -> Self
+    where
+        S: EntropySource + Send + Sync + 'static,
+        S::EntropySourceError: Send + Sync + 'static,
+    {
+        self.with_member(Box::new(source), class, entropy_per_byte, false)
+    }
+
+    /// Add a source whose failure fails the whole read (fail closed), as with `with_source`.
+    ///
+    /// NOTE: Pass `source.erase_classified()` for `MandatoryFailed` to carry the kind of its error.
+    pub fn with_mandatory_source<S>(self, source: S, class: SourceClass, entropy_per_byte: f64) -> Self
+    where
+        S: EntropySource + Send + Sync + 'static,
+        S::EntropySourceError: Send + Sync + 'static,
+    {
+        self.with_member(Box::new(source), class, entropy_per_byte, true)
+    }
+
+    /// Add a source as with `with_source`, taking its class and credit from its assessment.
+    pub fn with_assessed_source<S>(self, source: S) -> Self
+    where
+        S: EntropyAssessment + Send + Sync + 'static,
+        S::EntropySourceError: Send + Sync + 'static,
+    {
+        let (class, entropy_per_byte) = (source.provenance().class, source.min_entropy_per_byte());
+
+        self.with_member(Box::new(source), class, entropy_per_byte, false)
+    }
+
+    /// Add a mandatory source as with `with_mandatory_source`, taking its class and credit from its assessment.
+    pub fn with_assessed_mandatory_source<S>(self, source: S) -> Self
+    where
+        S: EntropyAssessment + Send + Sync + 'static,
+        S::EntropySourceError: Send + Sync + 'static,
+    {
+        let (class, entropy_per_byte) = (source.provenance().class, source.min_entropy_per_byte());
+
+        self.with_member(Box::new(source), class, entropy_per_byte, true)
+    }
+
+    fn with_member(
+        mut self,
+        source: Box<dyn ErasedEntropySource + Send + Sync>,
+        class: SourceClass,
+        entropy_per_byte: f64,
+        mandatory: bool,
+    ) -> Self {
+        // NOTE: `NaN` is credited with nothing.
+        let entropy_per_byte = match entropy_per_byte.is_nan() {
+            true => 0.0,
+            false => entropy_per_byte.clamp(0.0, 8.0),
+        };
+
+        self.members.push(Member {
+            source,
+            class,
+            entropy_per_byte,
+            mandatory,
+        });
+        self
+    }
+
+    /// Require at least `count` sources of the given class to succeed for every read.
+    ///
+    /// NOTE: Requiring the same class again replaces the earlier count.
+    pub fn require(mut self, class: SourceClass, count: usize) -> Self {
+        self.quorums.retain(|(other, _)| *other != class);
+        self.quorums.push((class, count));
+        self
+    }
+
+    /// Get the number of sources.
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Whether the policy has no sources.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Get the number of bytes read from every source per round, so that one round from all of them is credited with
+    /// enough entropy for one block.
+    fn round_length(&self) -> Result<usize, PolicyError> {
+        let total: f64 = self.members.iter().map(|member| member.entropy_per_byte).sum();
+
+        if total <= 0.0 {
+            return Err(PolicyError::InsufficientEntropy);
+        }
+
+        // NOTE: `f64::ceil` needs `std`, so the quotient is rounded up by hand.
+        let quotient = BLOCK_INPUT_BITS / total;
+        let length = quotient as usize;
+
+        match (length as f64) < quotient {
+            true => Ok(length + 1),
+            false => Ok(length),
+        }
+    }
+
+    /// Fill one (at most 32-byte) block, reading rounds from the sources until their credit is reached.
+    fn block(&self, block: &mut [u8], counter: u64, input: &mut [u8], failed: &mut [bool]) -> Result<(), PolicyError> {
+        let mut hasher = Sha256::new();
+
+        hasher.update(POLICY_DOMAIN);
+        hasher.update(counter.to_be_bytes());
+
+        failed.fill(false);
+
+        let mut credit = 0.0;
+
+        while credit < BLOCK_INPUT_BITS {
+            let mut round = 0.0;
+
+            for (index, member) in self.members.iter().enumerate() {
+                if failed[index] {
+                    continue;
+                }
+
+                if let Err(e) = member.source.read_bytes_erased(input) {
+                    trace::event!(warn, index, error = ?e, "policy source failed");
+
+                    match member.mandatory {
+                        true => return Err(PolicyError::MandatoryFailed(index, e.kind())),
+                        false => failed[index] = true,
+                    }
+
+                    continue;
+                }
+
+                hasher.update((index as u32).to_be_bytes());
+                hasher.update(&*input);
+
+                round += member.entropy_per_byte * input.len() as f64;
+            }
+
+            if round == 0.0 {
+                return Err(PolicyError::InsufficientEntropy);
+            }
+
+            credit += round;
+        }
+
+        for &(class, count) in &self.quorums {
+            let healthy = self
+                .members
+                .iter()
+                .zip(failed.iter())
+                .filter(|(member, &failed)| member.class == class && !failed)
+                .count();
+
+            if healthy < count {
+                trace::event!(warn, class = ?class, healthy, required = count, "policy quorum not met");
+
+                return Err(PolicyError::QuorumNotMet(class));
+            }
+        }
+
+        let mut digest: [u8; 32] = hasher.finalize().into();
+
+        block.copy_from_slice(&digest[..block.len()]);
+
+        digest.zeroize();
+
+        Ok(())
+    }
+}
+
+impl Default for PolicySource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl EntropySource for PolicySource {
+    type EntropySourceError = PolicyError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        let mut input = vec![0u8; self.round_length()?];
+        let mut failed = vec![false; self.members.len()];
+
+        let mut result = Ok(());
+
+        for (counter, block) in buffer.chunks_mut(32).enumerate() {
+            result = self.block(block, counter as u64, &mut input, &mut failed);
+
+            if result.is_err() {
+                break;
+            }
+        }
+
+        input.zeroize();
+
+        if result.is_err() {
+            buffer.fill(0);
+        }
+
+        result
+    }
+}
+
+/// NOTE: Every block is conditioned from input credited with 64 bits more than its size, so the output is credited
+/// with full entropy (as far as the credits given to the sources hold).
+impl EntropyAssessment for PolicySource {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Other,
+            basis: Basis::Derived,
+        }
+    }
+}
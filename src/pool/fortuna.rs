@@ -0,0 +1,309 @@
+use core::error::Error;
+
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::Aes256;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{Classify, ErrorKind};
+use crate::fork;
+use crate::lock::Lock;
+use crate::selftest::{known_answer, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+/// Number of entropy pools.
+const POOLS: usize = 32;
+
+/// Bytes of events pool 0 must accumulate before a reseed.
+const MIN_POOL_SIZE: u64 = 64;
+
+/// Maximum size of a single event.
+const MAX_EVENT_SIZE: usize = 32;
+
+/// Maximum number of bytes generated before the generator is rekeyed.
+const MAX_REQUEST: usize = 1 << 20;
+
+/// Minimum time between reseeds.
+#[cfg(feature = "std")]
+const RESEED_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Expected first 32 output bytes after adding `0x00..0x20` from sources 0 and 1.
+const KAT_OUTPUT: [u8; 32] = [
+    0x64, 0x00, 0xad, 0xa2, 0x52, 0x92, 0x30, 0xa6, 0x59, 0x36, 0x3e, 0x6e, 0x0e, 0x3e, 0xa6, 0x3f,
+    0x9c, 0x77, 0x3a, 0x01, 0x62, 0xc1, 0x3f, 0x2a, 0x06, 0x96, 0x4d, 0x8b, 0x6d, 0xd2, 0x1f, 0x64,
+];
+
+#[derive(Debug)]
+pub enum FortunaError {
+    /// The generator has not been seeded yet (not enough events have been added).
+    NotSeeded,
+    /// Events must contain between 1 and 32 bytes.
+    InvalidEvent,
+}
+
+impl core::fmt::Display for FortunaError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for FortunaError {}
+
+impl Classify for FortunaError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::NotSeeded => ErrorKind::Exhausted,
+            Self::InvalidEvent => ErrorKind::Unsupported,
+        }
+    }
+}
+
+/// The Fortuna generator: AES-256 in counter mode, rekeyed after every request.
+struct Generator {
+    key: [u8; 32],
+    counter: u128,
+}
+
+impl Generator {
+    fn reseed(&mut self, seed: &[u8; 32]) {
+        let mut hasher = Sha256::new();
+
+        hasher.update(self.key);
+        hasher.update(seed);
+
+        self.key = Sha256::digest(hasher.finalize()).into();
+        self.counter = self.counter.wrapping_add(1);
+    }
+
+    fn generate_blocks(&mut self, cipher: &Aes256, output: &mut [u8]) {
+        for chunk in output.chunks_mut(16) {
+            let mut block = self.counter.to_le_bytes();
+
+            cipher.encrypt_block((&mut block).into());
+
+            chunk.copy_from_slice(&block[..chunk.len()]);
+
+            block.zeroize();
+
+            self.counter = self.counter.wrapping_add(1);
+        }
+    }
+
+    /// NOTE: `output` must not exceed `MAX_REQUEST` bytes.
+    fn generate(&mut self, output: &mut [u8]) {
+        let cipher = Aes256::new(&self.key.into());
+
+        self.generate_blocks(&cipher, output);
+
+        let mut key = [0u8; 32];
+
+        self.generate_blocks(&cipher, &mut key);
+
+        self.key = key;
+
+        key.zeroize();
+    }
+}
+
+struct FortunaState {
+    generator: Generator,
+    pools: [Sha256; POOLS],
+    pool_zero_size: u64,
+    next_pool: [u8; 256],
+    reseed_count: u32,
+    #[cfg(feature = "std")]
+    last_reseed: Option<std::time::Instant>,
+    fork_epoch: u64,
+}
+
+impl FortunaState {
+    fn new() -> Self {
+        Self {
+            generator: Generator { key: [0u8; 32], counter: 0 },
+            pools: Default::default(),
+            pool_zero_size: 0,
+            next_pool: [0u8; 256],
+            reseed_count: 0,
+            #[cfg(feature = "std")]
+            last_reseed: None,
+            fork_epoch: fork::epoch(),
+        }
+    }
+
+    /// Start over unseeded in a child process after `fork`.
+    ///
+    /// NOTE: The generator key and pools are copies of the parent's, so only events added after the fork are safe.
+    fn check_fork(&mut self) {
+        if self.fork_epoch != fork::epoch() {
+            trace::event!(info, generator = "Fortuna", "state discarded after fork");
+
+            *self = Self::new();
+        }
+    }
+
+    fn should_reseed(&self) -> bool {
+        if self.pool_zero_size < MIN_POOL_SIZE {
+            return false;
+        }
+
+        #[cfg(feature = "std")]
+        if let Some(last_reseed) = self.last_reseed {
+            if last_reseed.elapsed() < RESEED_INTERVAL {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Reseed from every pool `i` where `2^i` divides the reseed count.
+    fn reseed(&mut self) {
+        self.reseed_count = self.reseed_count.wrapping_add(1);
+
+        trace::event!(debug, generator = "Fortuna", reseed_count = self.reseed_count, "reseeded");
+
+        let mut hasher = Sha256::new();
+
+        for (i, pool) in self.pools.iter_mut().enumerate() {
+            if i > 0 && self.reseed_count % (1 << i) != 0 {
+                break;
+            }
+
+            hasher.update(core::mem::take(pool).finalize());
+        }
+
+        let mut seed: [u8; 32] = hasher.finalize().into();
+
+        self.generator.reseed(&seed);
+        self.pool_zero_size = 0;
+
+        #[cfg(feature = "std")]
+        {
+            self.last_reseed = Some(std::time::Instant::now());
+        }
+
+        seed.zeroize();
+    }
+}
+
+impl Drop for FortunaState {
+    fn drop(&mut self) {
+        self.generator.key.zeroize();
+    }
+}
+
+/// A Fortuna entropy accumulator (Ferguson & Schneier) with 32 pools.
+///
+/// NOTE: Reads fail with `FortunaError::NotSeeded` until enough events have been added for the first reseed, and
+/// again in a child process after `fork` until the child has added its own events.
+pub struct Fortuna {
+    state: Lock<FortunaState>,
+}
+
+impl Fortuna {
+    /// Create an empty (unseeded) accumulator.
+    pub fn new() -> Self {
+        Self {
+            state: Lock::new(FortunaState::new()),
+        }
+    }
+
+    /// Attempt to add an event (1 to 32 bytes) from the noise source identified by `source_id`.
+    ///
+    /// NOTE: Each source distributes its events over the pools in turn.
+    pub fn add_event(&self, source_id: u8, data: &[u8]) -> Result<(), FortunaError> {
+        if data.is_empty() || data.len() > MAX_EVENT_SIZE {
+            return Err(FortunaError::InvalidEvent);
+        }
+
+        self.state.with(|state| {
+            state.check_fork();
+
+            let pool = state.next_pool[source_id as usize] as usize;
+
+            state.pools[pool].update([source_id, data.len() as u8]);
+            state.pools[pool].update(data);
+
+            if pool == 0 {
+                state.pool_zero_size += 2 + data.len() as u64;
+            }
+
+            state.next_pool[source_id as usize] = ((pool + 1) % POOLS) as u8;
+        });
+
+        Ok(())
+    }
+
+    /// Get the number of times the generator has been reseeded from the pools.
+    pub fn reseed_count(&self) -> u32 {
+        self.state.with(|state| state.reseed_count)
+    }
+}
+
+impl Default for Fortuna {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl EntropySource for Fortuna {
+    type EntropySourceError = FortunaError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.state.with(|state| {
+            state.check_fork();
+
+            if state.should_reseed() {
+                state.reseed();
+            }
+
+            if state.reseed_count == 0 {
+                return Err(FortunaError::NotSeeded);
+            }
+
+            for chunk in buffer.chunks_mut(MAX_REQUEST) {
+                state.generator.generate(chunk);
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// NOTE: Credited with full entropy once seeded, as for any generator; the credit is only sound if the events added
+/// carry real entropy, which the accumulator cannot check.
+impl EntropyAssessment for Fortuna {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Other,
+            basis: Basis::Generator,
+        }
+    }
+}
+
+impl SelfTest for Fortuna {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        let error = SelfTestError::KnownAnswer("Fortuna");
+        let fortuna = Fortuna::new();
+        let mut event = [0u8; MAX_EVENT_SIZE];
+        let mut output = [0u8; 32];
+
+        for (i, byte) in event.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+
+        fortuna.add_event(0, &event).map_err(|_| error)?;
+        fortuna.add_event(1, &event).map_err(|_| error)?;
+        fortuna.read_bytes(&mut output).map_err(|_| error)?;
+
+        known_answer("Fortuna", &output, &KAT_OUTPUT)
+    }
+}
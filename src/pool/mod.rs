@@ -0,0 +1,5 @@
+//! Entropy accumulators fed by events from multiple noise sources.
+
+mod fortuna;
+
+pub use fortuna::{Fortuna, FortunaError};
@@ -0,0 +1,180 @@
+//! Fixed-size entropy blocks generated ahead of time on a background thread, for latency-sensitive callers.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::fork;
+use crate::trace;
+use crate::{Entropy, EntropySource};
+
+/// Default number of blocks kept queued.
+const DEFAULT_CAPACITY: usize = 16;
+
+/// Time the worker waits before reading from the source again after it failed.
+const RETRY_DELAY: Duration = Duration::from_millis(100);
+
+struct Queue<const LENGTH: usize> {
+    blocks: VecDeque<Entropy<LENGTH>>,
+    fork_epoch: u64,
+    stop: bool,
+}
+
+struct Shared<S: EntropySource, const LENGTH: usize> {
+    source: S,
+    capacity: usize,
+    queue: Mutex<Queue<LENGTH>>,
+    space: Condvar,
+}
+
+impl<S: EntropySource, const LENGTH: usize> Shared<S, LENGTH> {
+    fn lock(&self) -> MutexGuard<'_, Queue<LENGTH>> {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Keep the queue topped up, sleeping while it is full, until stopped.
+    fn run(&self) {
+        let mut queue = self.lock();
+
+        loop {
+            while !queue.stop && queue.blocks.len() >= self.capacity {
+                queue = self.space.wait(queue).unwrap_or_else(|e| e.into_inner());
+            }
+
+            if queue.stop {
+                return;
+            }
+
+            drop(queue);
+
+            // NOTE: The source is read without holding the lock, so taking a block never waits on it.
+            let block = Entropy::try_generate(&self.source);
+
+            queue = self.lock();
+
+            match block {
+                Ok(block) => queue.blocks.push_back(block),
+                Err(_e) => {
+                    trace::event!(warn, error = ?_e, "prefetch failed");
+
+                    if !queue.stop {
+                        queue = match self.space.wait_timeout(queue, RETRY_DELAY) {
+                            Ok((queue, _)) => queue,
+                            Err(e) => e.into_inner().0,
+                        };
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Entropy blocks of `LENGTH` bytes generated ahead of time by a background thread, so that taking one is a cheap pop
+/// instead of a read from a slow source (e.g. a TPM).
+///
+/// The queue is bounded: the worker sleeps while it holds `capacity` blocks, so the source is never read faster than
+/// blocks are taken. When the queue runs dry, `take` generates a block inline (and sees any error from the source),
+/// while `try_take` returns nothing without waiting.
+///
+/// NOTE: Queued blocks are wiped when dropped. In a child process after `fork`, they are discarded and, since the
+/// worker does not survive the fork, every block is generated inline.
+pub struct Prefetcher<S: EntropySource + Send + Sync + 'static, const LENGTH: usize> {
+    shared: Arc<Shared<S, LENGTH>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl<S: EntropySource + Send + Sync + 'static, const LENGTH: usize> Prefetcher<S, LENGTH> {
+    /// Start prefetching from `source`, keeping up to 16 blocks queued.
+    pub fn new(source: S) -> Self {
+        Self::with_capacity(source, DEFAULT_CAPACITY)
+    }
+
+    /// Start prefetching from `source`, keeping up to `capacity` blocks queued (minimum 1).
+    pub fn with_capacity(source: S, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+
+        let shared = Arc::new(Shared {
+            source,
+            capacity,
+            queue: Mutex::new(Queue {
+                blocks: VecDeque::with_capacity(capacity),
+                fork_epoch: fork::epoch(),
+                stop: false,
+            }),
+            space: Condvar::new(),
+        });
+
+        let prefetching = shared.clone();
+        let worker = std::thread::spawn(move || prefetching.run());
+
+        Self {
+            shared,
+            worker: Some(worker),
+        }
+    }
+
+    /// Take a queued block, if there is one.
+    pub fn try_take(&self) -> Option<Entropy<LENGTH>> {
+        let mut queue = self.shared.lock();
+
+        let fork_epoch = fork::epoch();
+
+        if queue.fork_epoch != fork_epoch {
+            queue.blocks.clear();
+            queue.fork_epoch = fork_epoch;
+        }
+
+        let block = queue.blocks.pop_front();
+
+        if block.is_some() {
+            self.shared.space.notify_one();
+        }
+
+        block
+    }
+
+    /// Take a queued block, or generate one inline if the queue is empty.
+    pub fn take(&self) -> Result<Entropy<LENGTH>, S::EntropySourceError> {
+        match self.try_take() {
+            Some(block) => Ok(block),
+            None => {
+                trace::event!(debug, "prefetch queue empty, generating inline");
+
+                Entropy::try_generate(&self.shared.source)
+            }
+        }
+    }
+
+    /// Get the number of blocks currently queued.
+    pub fn len(&self) -> usize {
+        self.shared.lock().blocks.len()
+    }
+
+    /// Whether no blocks are currently queued.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the most blocks kept queued.
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.shared.source
+    }
+}
+
+impl<S: EntropySource + Send + Sync + 'static, const LENGTH: usize> Drop for Prefetcher<S, LENGTH> {
+    fn drop(&mut self) {
+        // NOTE: Setting `stop` under the lock ensures the worker is either waiting or has yet to check it.
+        self.shared.lock().stop = true;
+        self.shared.space.notify_one();
+
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
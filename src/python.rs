@@ -0,0 +1,321 @@
+//! Python bindings over the OS source, the DRBGs, health testing and token generation.
+//!
+//! Build the extension module with `cargo rustc --release --lib --features python,pyo3/extension-module --crate-type
+//! cdylib`, and install `liblibrypt_entropy.so` as `librypt_entropy.so` (or `librypt_entropy.pyd` on Windows):
+//!
+//! ```text
+//! >>> import librypt_entropy as le
+//! >>> drbg = le.HmacDrbg(le.Monitored(hardware_rng, min_entropy=6))
+//! >>> le.TokenGenerator("url_safe", min_entropy=128).generate(drbg)
+//! ```
+//!
+//! Any Python object with a `read(n) -> bytes` method can be used as a source, including every class here.
+//!
+//! NOTE: Python `bytes` and `str` objects are immutable, so generated secrets cannot be wiped once returned.
+
+use std::boxed::Box;
+use std::collections::BTreeSet;
+use std::format;
+use std::string::String;
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::drbg::{CtrDrbg, Drbg, DrbgError, HashDrbg, HmacDrbg, Sha256, Sha512};
+use crate::health::{Monitored, MonitoredError};
+use crate::lock::Lock;
+use crate::os::{OsEntropy, OsEntropySourceError};
+use crate::tokens::{is_valid_charset, Charset, TokenError, TokenGenerator};
+use crate::EntropySource;
+
+create_exception!(librypt_entropy, EntropyError, PyException, "An entropy source failed.");
+create_exception!(librypt_entropy, HealthTestError, EntropyError, "A continuous health test failed.");
+
+#[derive(Debug)]
+enum SourceError {
+    /// The OS source failed.
+    Os(OsEntropySourceError),
+    /// The Python source raised, or returned the wrong number of bytes.
+    Python(PyErr),
+}
+
+impl core::fmt::Display for SourceError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl core::error::Error for SourceError {}
+
+impl From<SourceError> for PyErr {
+    fn from(error: SourceError) -> Self {
+        match error {
+            SourceError::Os(e) => EntropyError::new_err(format!("OS entropy source failed: {:?}", e)),
+            SourceError::Python(e) => e,
+        }
+    }
+}
+
+impl From<MonitoredError<SourceError>> for PyErr {
+    fn from(error: MonitoredError<SourceError>) -> Self {
+        match error {
+            MonitoredError::Source(e) => e.into(),
+            MonitoredError::Health(e) => HealthTestError::new_err(format!("{:?}", e)),
+        }
+    }
+}
+
+/// A source passed in from Python: the OS directly, or anything with a `read(n) -> bytes` method.
+enum Source {
+    Os,
+    Object(PyObject),
+}
+
+impl Source {
+    fn extract(source: Option<&Bound<'_, PyAny>>) -> Self {
+        match source {
+            None => Self::Os,
+            Some(source) if source.is_instance_of::<PyOsEntropy>() => Self::Os,
+            Some(source) => Self::Object(source.clone().unbind()),
+        }
+    }
+}
+
+unsafe impl EntropySource for Source {
+    type EntropySourceError = SourceError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let object = match self {
+            Self::Os => return OsEntropy.read_bytes(buffer).map_err(SourceError::Os),
+            Self::Object(object) => object,
+        };
+
+        Python::with_gil(|py| {
+            let bytes = object.call_method1(py, "read", (buffer.len(),))?;
+            let bytes = bytes.downcast_bound::<PyBytes>(py)?.as_bytes();
+
+            if bytes.len() != buffer.len() {
+                return Err(PyValueError::new_err(format!(
+                    "source returned {} bytes instead of {}",
+                    bytes.len(),
+                    buffer.len()
+                )));
+            }
+
+            buffer.copy_from_slice(bytes);
+
+            Ok(())
+        })
+        .map_err(SourceError::Python)
+    }
+}
+
+/// Read `length` bytes from `source` into a new `bytes` object, releasing the GIL while reading.
+fn read<S>(py: Python<'_>, source: &S, length: usize) -> PyResult<Py<PyBytes>>
+where
+    S: EntropySource + Sync,
+    S::EntropySourceError: Send,
+    PyErr: From<S::EntropySourceError>,
+{
+    let mut bytes = zeroize::Zeroizing::new(std::vec![0u8; length]);
+
+    py.allow_threads(|| source.read_bytes(&mut bytes))?;
+
+    Ok(PyBytes::new(py, &bytes).unbind())
+}
+
+/// Entropy from the operating system.
+#[pyclass(name = "OsEntropy", module = "librypt_entropy", frozen)]
+struct PyOsEntropy;
+
+#[pymethods]
+impl PyOsEntropy {
+    #[new]
+    fn new() -> Self {
+        Self
+    }
+
+    /// Read `n` bytes.
+    fn read(&self, py: Python<'_>, n: usize) -> PyResult<Py<PyBytes>> {
+        read(py, &Source::Os, n)
+    }
+}
+
+/// SP 800-90B continuous health tests over a raw noise source claiming `min_entropy` bits per byte.
+#[pyclass(name = "Monitored", module = "librypt_entropy", frozen)]
+struct PyMonitored {
+    source: Monitored<Source>,
+}
+
+#[pymethods]
+impl PyMonitored {
+    #[new]
+    #[pyo3(signature = (source, min_entropy))]
+    fn new(source: &Bound<'_, PyAny>, min_entropy: u8) -> Self {
+        Self {
+            source: Monitored::new(Source::extract(Some(source)), min_entropy),
+        }
+    }
+
+    /// Read `n` bytes, raising `HealthTestError` if a test fails now or failed earlier.
+    fn read(&self, py: Python<'_>, n: usize) -> PyResult<Py<PyBytes>> {
+        read(py, &self.source, n)
+    }
+
+    /// The latched health test failure, if any.
+    #[getter]
+    fn failure(&self) -> Option<String> {
+        self.source.failure().map(|e| format!("{:?}", e))
+    }
+}
+
+impl From<DrbgError<SourceError>> for PyErr {
+    fn from(error: DrbgError<SourceError>) -> Self {
+        match error {
+            DrbgError::Source(e) => e.into(),
+        }
+    }
+}
+
+macro_rules! py_drbg {
+    ($name:ident, $python:literal, $drbg:ty, $doc:literal) => {
+        #[doc = $doc]
+        #[pyclass(name = $python, module = "librypt_entropy", frozen)]
+        struct $name {
+            drbg: $drbg,
+        }
+
+        #[pymethods]
+        impl $name {
+            #[new]
+            #[pyo3(signature = (source = None, reseed_interval = None))]
+            fn new(py: Python<'_>, source: Option<&Bound<'_, PyAny>>, reseed_interval: Option<u64>) -> PyResult<Self> {
+                let source = Source::extract(source);
+                let drbg = py.allow_threads(|| <$drbg>::instantiate(source))?;
+
+                Ok(Self {
+                    drbg: match reseed_interval {
+                        Some(reseed_interval) => drbg.with_reseed_interval(reseed_interval),
+                        None => drbg,
+                    },
+                })
+            }
+
+            /// Generate `n` bytes.
+            fn generate(&self, py: Python<'_>, n: usize) -> PyResult<Py<PyBytes>> {
+                read(py, &self.drbg, n)
+            }
+
+            /// Generate `n` bytes (an alias of `generate`, so generators can be used as sources).
+            fn read(&self, py: Python<'_>, n: usize) -> PyResult<Py<PyBytes>> {
+                read(py, &self.drbg, n)
+            }
+
+            /// Reseed from the source immediately.
+            fn reseed(&self, py: Python<'_>) -> PyResult<()> {
+                Ok(py.allow_threads(|| self.drbg.reseed())?)
+            }
+        }
+    };
+}
+
+py_drbg!(PyHmacDrbg, "HmacDrbg", HmacDrbg<Source>, "HMAC_DRBG (SHA-256), seeded from `source` or the OS.");
+py_drbg!(PyCtrDrbg, "CtrDrbg", CtrDrbg<Source>, "CTR_DRBG (AES-256), seeded from `source` or the OS.");
+py_drbg!(PyHashDrbg, "HashDrbg", HashDrbg<Source, Sha256>, "Hash_DRBG (SHA-256), seeded from `source` or the OS.");
+py_drbg!(
+    PyHashDrbgSha512,
+    "HashDrbgSha512",
+    HashDrbg<Source, Sha512>,
+    "Hash_DRBG (SHA-512), seeded from `source` or the OS."
+);
+
+/// Custom charsets, each kept once for the rest of the process and shared by every generator using it.
+static CUSTOM_CHARSETS: Lock<BTreeSet<&'static str>> = Lock::new(BTreeSet::new());
+
+/// Get the kept copy of a custom charset, keeping `characters` if no generator has used them yet.
+fn intern(characters: &str) -> &'static str {
+    CUSTOM_CHARSETS.with(|charsets| match charsets.get(characters) {
+        Some(interned) => *interned,
+        None => {
+            let interned: &'static str = Box::leak(characters.into());
+
+            charsets.insert(interned);
+
+            interned
+        }
+    })
+}
+
+/// Tokens over `charset` (`"alphanumeric"`, `"url_safe"`, `"hex"`, or a string of distinct ASCII characters), of
+/// `length` characters or the shortest length carrying `min_entropy` bits (32 characters by default).
+///
+/// NOTE: Invalid charsets raise `ValueError`. Every distinct custom charset is kept (once) for the rest of the process.
+#[pyclass(name = "TokenGenerator", module = "librypt_entropy", frozen)]
+struct PyTokenGenerator {
+    generator: TokenGenerator,
+}
+
+#[pymethods]
+impl PyTokenGenerator {
+    #[new]
+    #[pyo3(signature = (charset = "alphanumeric", length = None, min_entropy = None))]
+    fn new(charset: &str, length: Option<usize>, min_entropy: Option<u32>) -> PyResult<Self> {
+        let charset = match charset {
+            "alphanumeric" => Charset::Alphanumeric,
+            "url_safe" => Charset::UrlSafe,
+            "hex" => Charset::Hex,
+            custom if is_valid_charset(custom) => Charset::Custom(intern(custom)),
+            _ => return Err(PyValueError::new_err("invalid charset")),
+        };
+
+        let generator = match (length, min_entropy) {
+            (Some(_), Some(_)) => return Err(PyValueError::new_err("pass either length or min_entropy, not both")),
+            (Some(length), None) => TokenGenerator::new(charset).with_length(length),
+            (None, Some(bits)) => TokenGenerator::new(charset).with_min_entropy(bits),
+            (None, None) => TokenGenerator::new(charset),
+        };
+
+        Ok(Self { generator })
+    }
+
+    /// Generate a token from `source` (the OS by default).
+    #[pyo3(signature = (source = None))]
+    fn generate(&self, py: Python<'_>, source: Option<&Bound<'_, PyAny>>) -> PyResult<String> {
+        let source = Source::extract(source);
+
+        match py.allow_threads(|| self.generator.generate(&source)) {
+            Ok(token) => Ok(token),
+            Err(TokenError::Source(e)) => Err(e.into()),
+            Err(TokenError::InvalidCharset) => Err(PyValueError::new_err("invalid charset")),
+        }
+    }
+
+    /// The token length, in characters.
+    #[getter]
+    fn length(&self) -> usize {
+        self.generator.length()
+    }
+
+    /// The entropy of one token, in bits.
+    #[getter]
+    fn entropy_bits(&self) -> f64 {
+        self.generator.entropy_bits()
+    }
+}
+
+#[pymodule]
+fn librypt_entropy(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("EntropyError", m.py().get_type::<EntropyError>())?;
+    m.add("HealthTestError", m.py().get_type::<HealthTestError>())?;
+    m.add_class::<PyOsEntropy>()?;
+    m.add_class::<PyMonitored>()?;
+    m.add_class::<PyHmacDrbg>()?;
+    m.add_class::<PyCtrDrbg>()?;
+    m.add_class::<PyHashDrbg>()?;
+    m.add_class::<PyHashDrbgSha512>()?;
+    m.add_class::<PyTokenGenerator>()?;
+
+    Ok(())
+}
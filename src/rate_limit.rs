@@ -0,0 +1,195 @@
+//! Rate limiting for shared or slow sources.
+
+use core::error::Error;
+use std::time::{Duration, Instant};
+
+use crate::assess::{EntropyAssessment, Provenance};
+use crate::error::{Classify, ErrorKind};
+use crate::lock::Lock;
+use crate::trace;
+use crate::EntropySource;
+
+#[derive(Debug)]
+pub enum RateLimitedError<E: Error> {
+    /// The wrapped source failed.
+    Source(E),
+    /// The read exceeded the configured rate (only with `OnLimit::Fail`).
+    RateLimited,
+}
+
+impl<E: Error> core::fmt::Display for RateLimitedError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<E: Error> Error for RateLimitedError<E> {}
+
+impl<E: Error + Classify> Classify for RateLimitedError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Source(e) => e.kind(),
+            Self::RateLimited => ErrorKind::Transient,
+        }
+    }
+}
+
+/// What to do with a read that exceeds the rate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OnLimit {
+    /// Sleep until the read is allowed.
+    #[default]
+    Block,
+    /// Fail with `RateLimitedError::RateLimited`.
+    Fail,
+}
+
+/// A token bucket holding up to one second worth of its rate.
+struct Bucket {
+    rate: f64,
+    tokens: f64,
+}
+
+impl Bucket {
+    fn new(rate: Option<u64>) -> Option<Self> {
+        rate.map(|rate| Self {
+            rate: rate.max(1) as f64,
+            tokens: rate.max(1) as f64,
+        })
+    }
+
+    fn refill(&mut self, elapsed: Duration) {
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.rate);
+    }
+
+    /// Get how long until `amount` tokens may be taken.
+    ///
+    /// NOTE: Requests larger than the bucket only wait for a full bucket, then leave it in debt.
+    fn wait(&self, amount: f64) -> Duration {
+        let missing = amount.min(self.rate) - self.tokens;
+
+        match missing > 0.0 {
+            true => Duration::from_secs_f64(missing / self.rate),
+            false => Duration::ZERO,
+        }
+    }
+}
+
+struct LimiterState {
+    bytes: Option<Bucket>,
+    calls: Option<Bucket>,
+    last: Instant,
+}
+
+/// Limits the bytes and calls per second served by the wrapped source, using token buckets.
+///
+/// Each bucket holds one second worth of its rate, so short bursts up to the rate are served immediately.
+pub struct RateLimited<S: EntropySource> {
+    source: S,
+    on_limit: OnLimit,
+    state: Lock<LimiterState>,
+}
+
+impl<S: EntropySource> RateLimited<S> {
+    /// Wrap a source, without any limits yet.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            on_limit: OnLimit::Block,
+            state: Lock::new(LimiterState {
+                bytes: None,
+                calls: None,
+                last: Instant::now(),
+            }),
+        }
+    }
+
+    /// Limit the bytes served per second.
+    pub fn with_bytes_per_second(self, rate: u64) -> Self {
+        self.state.with(|state| state.bytes = Bucket::new(Some(rate)));
+        self
+    }
+
+    /// Limit the calls served per second.
+    pub fn with_calls_per_second(self, rate: u64) -> Self {
+        self.state.with(|state| state.calls = Bucket::new(Some(rate)));
+        self
+    }
+
+    /// Set what to do with reads that exceed the rate.
+    pub fn with_on_limit(mut self, on_limit: OnLimit) -> Self {
+        self.on_limit = on_limit;
+        self
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Take the tokens for a read, or get how long until they are available.
+    fn acquire(&self, length: usize) -> Result<(), Duration> {
+        self.state.with(|state| {
+            let now = Instant::now();
+            let elapsed = now.duration_since(state.last);
+
+            state.last = now;
+
+            for bucket in [&mut state.bytes, &mut state.calls].into_iter().flatten() {
+                bucket.refill(elapsed);
+            }
+
+            let wait = [(&state.bytes, length as f64), (&state.calls, 1.0)]
+                .into_iter()
+                .filter_map(|(bucket, amount)| bucket.as_ref().map(|bucket| bucket.wait(amount)))
+                .max()
+                .unwrap_or(Duration::ZERO);
+
+            if !wait.is_zero() {
+                return Err(wait);
+            }
+
+            if let Some(bucket) = &mut state.bytes {
+                bucket.tokens -= length as f64;
+            }
+
+            if let Some(bucket) = &mut state.calls {
+                bucket.tokens -= 1.0;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySource for RateLimited<S> {
+    type EntropySourceError = RateLimitedError<S::EntropySourceError>;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        while let Err(wait) = self.acquire(buffer.len()) {
+            trace::event!(debug, ?wait, "rate limit reached");
+
+            match self.on_limit {
+                OnLimit::Block => std::thread::sleep(wait),
+                OnLimit::Fail => return Err(RateLimitedError::RateLimited),
+            }
+        }
+
+        match self.source.read_bytes(buffer) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(RateLimitedError::Source(e)),
+        }
+    }
+}
+
+impl<S: EntropyAssessment> EntropyAssessment for RateLimited<S> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.source.provenance()
+    }
+}
@@ -0,0 +1,210 @@
+//! Forcing deterministic generators to reseed from a live source.
+
+use core::error::Error;
+
+use zeroize::Zeroize;
+
+use crate::assess::{self, Basis, EntropyAssessment, Provenance};
+use crate::error::{Classify, ErrorKind};
+use crate::lock::Lock;
+use crate::trace;
+use crate::EntropySource;
+
+/// Largest `Reseedable::SEED_LEN` of the built-in generators.
+const MAX_SEED_LEN: usize = 64;
+
+/// Default number of output bytes between reseeds.
+const DEFAULT_BYTE_INTERVAL: u64 = 1 << 20;
+
+/// A deterministic generator which can be reseeded with externally supplied entropy.
+pub trait Reseedable: EntropySource {
+    /// Number of entropy bytes consumed by `reseed_with` (at most 64).
+    const SEED_LEN: usize;
+
+    /// Reseed the generator with `SEED_LEN` bytes of fresh entropy.
+    fn reseed_with(&self, entropy: &[u8]);
+}
+
+#[derive(Debug)]
+pub enum ReseedingError<G: Error, S: Error> {
+    /// The generator failed.
+    Generator(G),
+    /// The live source failed while reseeding.
+    Source(S),
+}
+
+impl<G: Error, S: Error> core::fmt::Display for ReseedingError<G, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<G: Error, S: Error> Error for ReseedingError<G, S> {}
+
+impl<G: Error + Classify, S: Error + Classify> Classify for ReseedingError<G, S> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Generator(e) => e.kind(),
+            Self::Source(e) => e.kind(),
+        }
+    }
+}
+
+struct ReseedState {
+    served: u64,
+    #[cfg(feature = "std")]
+    last_reseed: std::time::Instant,
+}
+
+/// Reseeds a deterministic generator from a live source after a number of output bytes or (with `std`) an amount of
+/// time, whichever comes first.
+///
+/// NOTE: This bounds the output exposed by any single seed on top of the generator's own reseed schedule.
+pub struct ReseedingSource<D: Reseedable, S: EntropySource> {
+    generator: D,
+    source: S,
+    state: Lock<ReseedState>,
+    byte_interval: u64,
+    #[cfg(feature = "std")]
+    time_interval: Option<std::time::Duration>,
+}
+
+impl<D: Reseedable, S: EntropySource> ReseedingSource<D, S> {
+    /// Wrap a generator, reseeding it from `source` every 1 MiB of output.
+    pub fn new(generator: D, source: S) -> Self {
+        Self {
+            generator,
+            source,
+            state: Lock::new(ReseedState {
+                served: 0,
+                #[cfg(feature = "std")]
+                last_reseed: std::time::Instant::now(),
+            }),
+            byte_interval: DEFAULT_BYTE_INTERVAL,
+            #[cfg(feature = "std")]
+            time_interval: None,
+        }
+    }
+
+    /// Set the number of output bytes between reseeds (minimum 1).
+    pub fn with_byte_interval(mut self, byte_interval: u64) -> Self {
+        self.byte_interval = byte_interval.max(1);
+        self
+    }
+
+    /// Set the maximum time between reseeds (none by default).
+    ///
+    /// NOTE: The time is only checked on reads, so an idle generator reseeds on its next read.
+    #[cfg(feature = "std")]
+    pub fn with_time_interval(mut self, time_interval: std::time::Duration) -> Self {
+        self.time_interval = Some(time_interval);
+        self
+    }
+
+    /// Attempt to reseed the generator from the live source now.
+    pub fn reseed(&self) -> Result<(), S::EntropySourceError> {
+        self.state.with(|state| self.reseed_state(state))
+    }
+
+    /// Get a reference to the generator.
+    pub fn generator(&self) -> &D {
+        &self.generator
+    }
+
+    /// Get a reference to the live source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    fn reseed_state(&self, state: &mut ReseedState) -> Result<(), S::EntropySourceError> {
+        let mut seed = [0u8; MAX_SEED_LEN];
+        let seed = &mut seed[..D::SEED_LEN.min(MAX_SEED_LEN)];
+
+        let result = self.source.read_bytes(seed);
+
+        if result.is_ok() {
+            trace::event!(debug, generator = core::any::type_name::<D>(), "reseeding from live source");
+
+            self.generator.reseed_with(seed);
+
+            state.served = 0;
+
+            #[cfg(feature = "std")]
+            {
+                state.last_reseed = std::time::Instant::now();
+            }
+        }
+
+        seed.zeroize();
+
+        result
+    }
+
+    fn should_reseed(&self, state: &ReseedState) -> bool {
+        if state.served >= self.byte_interval {
+            return true;
+        }
+
+        #[cfg(feature = "std")]
+        if let Some(time_interval) = self.time_interval {
+            return state.last_reseed.elapsed() >= time_interval;
+        }
+
+        false
+    }
+}
+
+unsafe impl<D: Reseedable, S: EntropySource> EntropySource for ReseedingSource<D, S> {
+    type EntropySourceError = ReseedingError<D::EntropySourceError, S::EntropySourceError>;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.state.with(|state| {
+            let mut filled = 0;
+
+            while filled < buffer.len() {
+                if self.should_reseed(state) {
+                    if let Err(e) = self.reseed_state(state) {
+                        return Err(ReseedingError::Source(e));
+                    }
+                }
+
+                let remaining = (self.byte_interval - state.served).min((buffer.len() - filled) as u64) as usize;
+
+                if let Err(e) = self.generator.read_bytes(&mut buffer[filled..filled + remaining]) {
+                    return Err(ReseedingError::Generator(e));
+                }
+
+                state.served += remaining as u64;
+                filled += remaining;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// NOTE: Credited with the better of the generator's own rate and the rate its reseeds from the live source would
+/// earn on their own (full entropy if every `SEED_LEN`-byte seed carries 256 bits).
+impl<D: Reseedable + EntropyAssessment, S: EntropyAssessment> EntropyAssessment for ReseedingSource<D, S> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        let seeded = assess::seeded(self.source.min_entropy_per_byte(), D::SEED_LEN.min(MAX_SEED_LEN));
+
+        seeded.max(self.generator.min_entropy_per_byte())
+    }
+
+    fn provenance(&self) -> Provenance {
+        let seeded = assess::seeded(self.source.min_entropy_per_byte(), D::SEED_LEN.min(MAX_SEED_LEN));
+
+        let provenance = match seeded >= self.generator.min_entropy_per_byte() {
+            true => self.source.provenance(),
+            false => self.generator.provenance(),
+        };
+
+        Provenance {
+            basis: Basis::Derived,
+            ..provenance
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! Retrying transient source failures.
+
+use core::time::Duration;
+
+use crate::assess::{EntropyAssessment, Provenance};
+use crate::trace;
+use crate::EntropySource;
+
+/// Default maximum number of attempts per read.
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// How long to wait between attempts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backoff {
+    /// Retry immediately.
+    None,
+    /// Wait the same duration before every retry.
+    Fixed(Duration),
+    /// Wait `initial`, doubling before every further retry up to `max`.
+    Exponential { initial: Duration, max: Duration },
+}
+
+impl Backoff {
+    /// Get the delay before the given retry (1 for the first retry).
+    pub fn delay(&self, retry: u32) -> Duration {
+        match *self {
+            Backoff::None => Duration::ZERO,
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential { initial, max } => {
+                let factor = 1u32.checked_shl(retry.saturating_sub(1)).unwrap_or(u32::MAX);
+
+                initial.saturating_mul(factor).min(max)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn default_sleep(duration: Duration) {
+    std::thread::sleep(duration);
+}
+
+#[cfg(not(feature = "std"))]
+fn default_sleep(_: Duration) {}
+
+/// Retries failed reads from the wrapped source, returning the last error once every attempt has failed.
+///
+/// NOTE: Without the `std` feature the default sleep is a no-op; use `with_sleep` to provide a platform delay.
+pub struct RetrySource<S: EntropySource> {
+    source: S,
+    max_attempts: u32,
+    backoff: Backoff,
+    retryable: fn(&S::EntropySourceError) -> bool,
+    sleep: fn(Duration),
+}
+
+impl<S: EntropySource> RetrySource<S> {
+    /// Wrap a source, retrying every error up to 3 attempts without backoff.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            backoff: Backoff::None,
+            retryable: |_| true,
+            sleep: default_sleep,
+        }
+    }
+
+    /// Set the maximum number of attempts per read, including the first (minimum 1).
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the delay between attempts.
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Only retry errors for which `retryable` returns true; other errors are returned immediately.
+    pub fn with_retryable(mut self, retryable: fn(&S::EntropySourceError) -> bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+
+    /// Set the function used to wait between attempts.
+    pub fn with_sleep(mut self, sleep: fn(Duration)) -> Self {
+        self.sleep = sleep;
+        self
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySource for RetrySource<S> {
+    type EntropySourceError = S::EntropySourceError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        let mut attempt = 1;
+
+        loop {
+            let error = match self.source.read_bytes(buffer) {
+                Ok(_) => return Ok(()),
+                Err(e) => e,
+            };
+
+            if attempt >= self.max_attempts || !(self.retryable)(&error) {
+                return Err(error);
+            }
+
+            let delay = self.backoff.delay(attempt);
+
+            trace::event!(debug, attempt, ?delay, error = ?error, "read failed, retrying");
+
+            if !delay.is_zero() {
+                (self.sleep)(delay);
+            }
+
+            attempt += 1;
+        }
+    }
+}
+
+impl<S: EntropyAssessment> EntropyAssessment for RetrySource<S> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.source.provenance()
+    }
+}
@@ -0,0 +1,216 @@
+//! Entropy kept in memory locked into RAM and excluded from core dumps where the platform allows it.
+
+use alloc::alloc::{alloc_zeroed, dealloc, handle_alloc_error, Layout};
+use core::ptr::NonNull;
+
+use zeroize::Zeroize;
+
+use crate::trace;
+use crate::{DebugBytes, EntropySource};
+
+/// How the storage of a `SecureEntropy` is protected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Protection {
+    /// The pages are locked into RAM (`mlock`/`VirtualLock`), so they are never written to swap.
+    pub locked: bool,
+    /// The pages are left out of core dumps (`MADV_DONTDUMP`).
+    pub excluded_from_dumps: bool,
+}
+
+/// An `Entropy` counterpart whose bytes live on pages of their own, locked into RAM and excluded from core dumps.
+///
+/// NOTE: Protection is best-effort: when locking fails (e.g. `RLIMIT_MEMLOCK` is exhausted) or the platform has no
+/// support, the bytes are still generated and wiped on drop, and `protection` reports what was not applied.
+pub struct SecureEntropy<const LENGTH: usize> {
+    bytes: NonNull<[u8; LENGTH]>,
+    layout: Layout,
+    protection: Protection,
+}
+
+// SAFETY: The storage is owned exclusively, as with a `Box`.
+unsafe impl<const LENGTH: usize> Send for SecureEntropy<LENGTH> {}
+unsafe impl<const LENGTH: usize> Sync for SecureEntropy<LENGTH> {}
+
+impl<const LENGTH: usize> SecureEntropy<LENGTH> {
+    /// Attempt to generate entropy from the provided `EntropySource` directly into protected storage.
+    pub fn try_generate<S: EntropySource>(source: &S) -> Result<Self, S::EntropySourceError> {
+        let mut entropy = Self::allocate();
+
+        // NOTE: The pages are protected before anything is written to them.
+        match source.read_bytes(entropy.bytes_mut()) {
+            Ok(_) => Ok(entropy),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Generate entropy from the provided `EntropySource` directly into protected storage.
+    ///
+    /// NOTE: This function will panic if the generation fails. See `try_generate` for a version with error handling.
+    pub fn generate(source: &impl EntropySource) -> Self {
+        Self::try_generate(source).unwrap()
+    }
+
+    /// Get the bytes.
+    pub fn bytes(&self) -> &[u8; LENGTH] {
+        // SAFETY: `bytes` points to an initialized, owned allocation of at least `LENGTH` bytes.
+        unsafe { self.bytes.as_ref() }
+    }
+
+    /// Get the bytes mutably.
+    pub fn bytes_mut(&mut self) -> &mut [u8; LENGTH] {
+        // SAFETY: As in `bytes`, and `&mut self` guarantees exclusive access.
+        unsafe { self.bytes.as_mut() }
+    }
+
+    /// Get a `Debug` view which prints the bytes, as with `Entropy::debug_reveal`.
+    pub fn debug_reveal(&self) -> impl core::fmt::Debug + '_ {
+        DebugBytes {
+            name: "SecureEntropy",
+            bytes: self.bytes(),
+            reveal: true,
+        }
+    }
+
+    /// Get the protection applied to the storage.
+    pub fn protection(&self) -> Protection {
+        self.protection
+    }
+
+    /// Allocate zeroed, page-aligned storage rounded up to whole pages (so no other data shares them), and protect it.
+    fn allocate() -> Self {
+        let page = page_size();
+        let size = LENGTH.max(1).div_ceil(page) * page;
+
+        let layout = match Layout::from_size_align(size, page) {
+            Ok(layout) => layout,
+            Err(_) => handle_alloc_error(Layout::new::<[u8; LENGTH]>()),
+        };
+
+        // SAFETY: `layout` has a non-zero size.
+        let bytes: NonNull<[u8; LENGTH]> = match NonNull::new(unsafe { alloc_zeroed(layout) }) {
+            Some(bytes) => bytes.cast(),
+            None => handle_alloc_error(layout),
+        };
+
+        // SAFETY: `bytes` is a live allocation described by `layout`.
+        let protection = unsafe { protect(bytes.as_ptr().cast(), layout.size()) };
+
+        if !(protection.locked && protection.excluded_from_dumps) {
+            trace::event!(
+                warn,
+                locked = protection.locked,
+                excluded_from_dumps = protection.excluded_from_dumps,
+                "secure entropy storage only partially protected"
+            );
+        }
+
+        Self {
+            bytes,
+            layout,
+            protection,
+        }
+    }
+}
+
+impl<const LENGTH: usize> AsRef<[u8]> for SecureEntropy<LENGTH> {
+    fn as_ref(&self) -> &[u8] {
+        self.bytes()
+    }
+}
+
+impl<const LENGTH: usize> AsMut<[u8]> for SecureEntropy<LENGTH> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        self.bytes_mut()
+    }
+}
+
+/// NOTE: The bytes are redacted, as with `Entropy`.
+impl<const LENGTH: usize> core::fmt::Debug for SecureEntropy<LENGTH> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let bytes = DebugBytes {
+            name: "SecureEntropy",
+            bytes: self.bytes(),
+            reveal: false,
+        };
+
+        core::fmt::Debug::fmt(&bytes, f)
+    }
+}
+
+/// NOTE: Wipes the bytes, then unlocks and frees the storage.
+impl<const LENGTH: usize> Drop for SecureEntropy<LENGTH> {
+    fn drop(&mut self) {
+        self.bytes_mut().zeroize();
+
+        // SAFETY: `bytes` was allocated with `layout` and protected as recorded in `protection`.
+        unsafe {
+            unprotect(self.bytes.as_ptr().cast(), self.layout.size(), self.protection);
+
+            dealloc(self.bytes.as_ptr().cast(), self.layout);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn page_size() -> usize {
+    match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+        size if size > 0 && (size as usize).is_power_of_two() => size as usize,
+        _ => 4096,
+    }
+}
+
+#[cfg(not(unix))]
+fn page_size() -> usize {
+    4096
+}
+
+#[cfg(unix)]
+unsafe fn protect(pointer: *mut u8, size: usize) -> Protection {
+    Protection {
+        locked: libc::mlock(pointer.cast(), size) == 0,
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        excluded_from_dumps: libc::madvise(pointer.cast(), size, libc::MADV_DONTDUMP) == 0,
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        excluded_from_dumps: false,
+    }
+}
+
+#[cfg(unix)]
+unsafe fn unprotect(pointer: *mut u8, size: usize, protection: Protection) {
+    // NOTE: The pages go back to the allocator, so they are made dumpable again for whatever reuses them.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    if protection.excluded_from_dumps {
+        libc::madvise(pointer.cast(), size, libc::MADV_DODUMP);
+    }
+
+    if protection.locked {
+        libc::munlock(pointer.cast(), size);
+    }
+}
+
+#[cfg(windows)]
+unsafe fn protect(pointer: *mut u8, size: usize) -> Protection {
+    use windows_sys::Win32::System::Memory::VirtualLock;
+
+    Protection {
+        locked: VirtualLock(pointer.cast(), size) != 0,
+        excluded_from_dumps: false,
+    }
+}
+
+#[cfg(windows)]
+unsafe fn unprotect(pointer: *mut u8, size: usize, protection: Protection) {
+    use windows_sys::Win32::System::Memory::VirtualUnlock;
+
+    if protection.locked {
+        VirtualUnlock(pointer.cast(), size);
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+unsafe fn protect(_pointer: *mut u8, _size: usize) -> Protection {
+    Protection::default()
+}
+
+#[cfg(not(any(unix, windows)))]
+unsafe fn unprotect(_pointer: *mut u8, _size: usize, _protection: Protection) {}
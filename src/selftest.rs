@@ -0,0 +1,135 @@
+//! Power-on self tests for sources and generators.
+
+// NOTE: The shared helpers are unused when no source or generator is enabled.
+#![allow(dead_code)]
+
+use core::convert::Infallible;
+use core::error::Error;
+
+#[cfg(feature = "health")]
+use crate::health::HealthConfig;
+use crate::EntropySource;
+
+/// Number of bytes sampled by the startup health tests.
+const STARTUP_SAMPLES: usize = 1024;
+
+/// Repetition Count Test cutoff for full-entropy bytes (`alpha = 2^-40`).
+const RCT_CUTOFF: usize = 6;
+
+/// Adaptive Proportion Test cutoff for full-entropy bytes (`alpha = 2^-40`, `W = 1024`).
+const APT_CUTOFF: usize = 26;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfTestError {
+    /// The named known-answer test produced the wrong output.
+    KnownAnswer(&'static str),
+    /// The source failed while being sampled.
+    Source,
+    /// The startup Repetition Count Test failed.
+    RepetitionCount,
+    /// The startup Adaptive Proportion Test failed.
+    AdaptiveProportion,
+}
+
+impl core::fmt::Display for SelfTestError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for SelfTestError {}
+
+/// The outcome of a successful self test, for logging.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SelfTestReport {
+    /// Number of known-answer tests passed.
+    pub known_answer_tests: usize,
+    /// Number of output bytes which passed the startup health tests.
+    pub health_samples: usize,
+}
+
+/// A power-on self test, to be run (and logged) before first use.
+///
+/// NOTE: Deterministic components (DRBGs, CSPRNGs, pools) run known-answer tests on a fresh instance; their state is
+/// untouched. Noise sources sample their own output through startup health tests, which consumes entropy.
+pub trait SelfTest {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError>;
+}
+
+/// A fixed source for known-answer tests: every read fills the buffer with `0x00, 0x01, 0x02, ...`.
+pub(crate) struct KatSource;
+
+unsafe impl EntropySource for KatSource {
+    type EntropySourceError = Infallible;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        for (i, byte) in buffer.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compare the output of a known-answer test.
+pub(crate) fn known_answer(name: &'static str, output: &[u8], expected: &[u8]) -> Result<SelfTestReport, SelfTestError> {
+    match output == expected {
+        true => Ok(SelfTestReport {
+            known_answer_tests: 1,
+            health_samples: 0,
+        }),
+        false => Err(SelfTestError::KnownAnswer(name)),
+    }
+}
+
+/// Sample a noise source and run the Repetition Count and Adaptive Proportion tests on its output bytes.
+///
+/// NOTE: The cutoffs assume (nearly) full-entropy output; raw noise with under a few bits of entropy per byte may fail.
+pub(crate) fn startup_test<S: EntropySource>(source: &S) -> Result<SelfTestReport, SelfTestError> {
+    startup_test_with(source, RCT_CUTOFF, APT_CUTOFF)
+}
+
+/// Sample a noise source as in `startup_test`, with the cutoffs for its claimed min-entropy per byte.
+///
+/// NOTE: The whole sample forms a single Adaptive Proportion Test window of 1024 bytes.
+#[cfg(feature = "health")]
+pub(crate) fn startup_test_with_config<S: EntropySource>(
+    source: &S,
+    config: HealthConfig,
+) -> Result<SelfTestReport, SelfTestError> {
+    startup_test_with(source, config.rct_cutoff(), config.apt_cutoff())
+}
+
+fn startup_test_with<S: EntropySource>(
+    source: &S,
+    rct_cutoff: usize,
+    apt_cutoff: usize,
+) -> Result<SelfTestReport, SelfTestError> {
+    let mut samples = [0u8; STARTUP_SAMPLES];
+
+    if source.read_bytes(&mut samples).is_err() {
+        return Err(SelfTestError::Source);
+    }
+
+    let mut rct_count = 1;
+
+    for pair in samples.windows(2) {
+        match pair[0] == pair[1] {
+            true => rct_count += 1,
+            false => rct_count = 1,
+        }
+
+        if rct_count >= rct_cutoff {
+            return Err(SelfTestError::RepetitionCount);
+        }
+    }
+
+    if samples.iter().filter(|&&byte| byte == samples[0]).count() >= apt_cutoff {
+        return Err(SelfTestError::AdaptiveProportion);
+    }
+
+    Ok(SelfTestReport {
+        known_answer_tests: 0,
+        health_samples: STARTUP_SAMPLES,
+    })
+}
@@ -0,0 +1,101 @@
+//! `serde` support for `Entropy`.
+//!
+//! NOTE: Serializing entropy copies secret bytes out of this crate's control; only serialize into an encrypted
+//! envelope (or similar). Human-readable formats get a lowercase hex string, binary formats raw bytes.
+
+use core::fmt;
+
+use serde::de::{Deserialize, Deserializer, Error, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::Entropy;
+
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+impl<const LENGTH: usize> Serialize for Entropy<LENGTH> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(&Hex(&self.bytes))
+        } else {
+            serializer.serialize_bytes(&self.bytes)
+        }
+    }
+}
+
+struct EntropyVisitor<const LENGTH: usize>;
+
+fn nibble<E: Error>(digit: u8) -> Result<u8, E> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(E::custom("invalid hex digit")),
+    }
+}
+
+impl<'de, const LENGTH: usize> Visitor<'de> for EntropyVisitor<LENGTH> {
+    type Value = Entropy<LENGTH>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bytes or {} hex digits", LENGTH, LENGTH * 2)
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+        if value.len() != LENGTH * 2 {
+            return Err(E::invalid_length(value.len(), &self));
+        }
+
+        let mut entropy = Entropy { bytes: [0u8; LENGTH] };
+
+        for (byte, pair) in entropy.bytes.iter_mut().zip(value.as_bytes().chunks_exact(2)) {
+            *byte = (nibble(pair[0])? << 4) | nibble(pair[1])?;
+        }
+
+        Ok(entropy)
+    }
+
+    fn visit_bytes<E: Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+        if value.len() != LENGTH {
+            return Err(E::invalid_length(value.len(), &self));
+        }
+
+        let mut entropy = Entropy { bytes: [0u8; LENGTH] };
+
+        entropy.bytes.copy_from_slice(value);
+
+        Ok(entropy)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut entropy = Entropy { bytes: [0u8; LENGTH] };
+
+        for (index, byte) in entropy.bytes.iter_mut().enumerate() {
+            match seq.next_element()? {
+                Some(value) => *byte = value,
+                None => return Err(A::Error::invalid_length(index, &self)),
+            }
+        }
+
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(A::Error::invalid_length(LENGTH + 1, &self));
+        }
+
+        Ok(entropy)
+    }
+}
+
+impl<'de, const LENGTH: usize> Deserialize<'de> for Entropy<LENGTH> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(EntropyVisitor)
+        } else {
+            deserializer.deserialize_bytes(EntropyVisitor)
+        }
+    }
+}
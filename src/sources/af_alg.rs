@@ -0,0 +1,200 @@
+use core::ptr;
+use std::error::Error;
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::string::String;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{self, io_kind, Classify};
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+/// Default algorithm name, which the kernel resolves to its highest-priority RNG (normally a DRBG).
+pub const DEFAULT_AF_ALG_RNG: &str = "stdrng";
+
+#[derive(Debug)]
+pub enum AfAlgError {
+    /// The algorithm name does not fit in `sockaddr_alg` (at most 63 bytes) or contains a NUL byte.
+    InvalidName,
+    /// Setting up the socket failed (e.g. `ENOENT` from `bind` if no RNG of that name is registered).
+    Io(std::io::Error),
+    /// The socket reported end-of-file before filling the buffer.
+    UnexpectedEof,
+}
+
+impl std::fmt::Display for AfAlgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for AfAlgError {}
+
+impl Classify for AfAlgError {
+    fn kind(&self) -> error::ErrorKind {
+        match self {
+            Self::InvalidName => error::ErrorKind::Unsupported,
+            // NOTE: Kernels built without `CONFIG_CRYPTO_USER_API_RNG` reject the socket family itself.
+            Self::Io(e) if e.raw_os_error() == Some(libc::EAFNOSUPPORT) => error::ErrorKind::Unsupported,
+            Self::Io(e) => io_kind(e),
+            Self::UnexpectedEof => error::ErrorKind::Exhausted,
+        }
+    }
+}
+
+/// Entropy from an RNG registered with the Linux kernel crypto API, read through an `AF_ALG` socket of type `rng`.
+///
+/// The algorithm is selected by name: a generic name (e.g. `stdrng` or `jitterentropy_rng`) picks the
+/// highest-priority implementation, while a driver name (e.g. `drbg_pr_hmac_sha256`, or a hardware driver's name from
+/// `/proc/crypto`) picks that implementation exactly. This reaches kernel drivers which are not exposed as
+/// `/dev/hwrng`.
+///
+/// NOTE: The kernel returns at most 128 bytes per read, so larger requests take several reads; short reads are
+/// continued and `EINTR` is retried until the buffer is full.
+pub struct AfAlgRng {
+    socket: File,
+    algorithm: String,
+}
+
+impl AfAlgRng {
+    /// Attempt to open the kernel's default RNG (`stdrng`).
+    pub fn open() -> Result<Self, AfAlgError> {
+        Self::open_algorithm(DEFAULT_AF_ALG_RNG)
+    }
+
+    /// Attempt to open the RNG registered under the algorithm or driver name `name`.
+    ///
+    /// The RNG is reset with an empty seed, which a DRBG takes as the signal to instantiate itself from the kernel's
+    /// own entropy sources.
+    pub fn open_algorithm(name: &str) -> Result<Self, AfAlgError> {
+        Self::open_seeded(name, &[])
+    }
+
+    /// Attempt to open the RNG registered under the algorithm or driver name `name`, resetting it with `seed`.
+    ///
+    /// NOTE: A DRBG uses the seed only as a personalization string, but some hardware drivers require seed material of
+    /// a specific length (its `seedsize` in `/proc/crypto`).
+    pub fn open_seeded(name: &str, seed: &[u8]) -> Result<Self, AfAlgError> {
+        // SAFETY: `sockaddr_alg` is plain data, for which all zeroes is a valid (unnamed) address.
+        let mut address: libc::sockaddr_alg = unsafe { core::mem::zeroed() };
+
+        // NOTE: Both names must stay NUL-terminated, so the last byte of each field is never written.
+        if name.len() >= address.salg_name.len() || name.as_bytes().contains(&0) {
+            return Err(AfAlgError::InvalidName);
+        }
+
+        address.salg_family = libc::AF_ALG as libc::sa_family_t;
+        address.salg_type[..3].copy_from_slice(b"rng");
+        address.salg_name[..name.len()].copy_from_slice(name.as_bytes());
+
+        // SAFETY: Creating a socket has no memory-safety preconditions.
+        let parent = match unsafe { libc::socket(libc::AF_ALG, libc::SOCK_SEQPACKET | libc::SOCK_CLOEXEC, 0) } {
+            -1 => return Err(AfAlgError::Io(std::io::Error::last_os_error())),
+            // SAFETY: The descriptor was just created and is owned by nothing else.
+            fd => unsafe { OwnedFd::from_raw_fd(fd) },
+        };
+
+        let parent_fd = parent.as_raw_fd();
+
+        // SAFETY: `address` is a valid `sockaddr_alg` of the length passed.
+        let result = unsafe {
+            libc::bind(
+                parent_fd,
+                (&address as *const libc::sockaddr_alg).cast(),
+                size_of::<libc::sockaddr_alg>() as libc::socklen_t,
+            )
+        };
+
+        if result == -1 {
+            return Err(AfAlgError::Io(std::io::Error::last_os_error()));
+        }
+
+        // SAFETY: `seed` is valid for reads of the length passed.
+        let result = unsafe {
+            libc::setsockopt(
+                parent_fd,
+                libc::SOL_ALG,
+                libc::ALG_SET_KEY,
+                seed.as_ptr().cast(),
+                seed.len() as libc::socklen_t,
+            )
+        };
+
+        if result == -1 {
+            return Err(AfAlgError::Io(std::io::Error::last_os_error()));
+        }
+
+        // NOTE: The operation socket keeps the parent's RNG alive, so the parent can be closed once it is accepted.
+        let socket = loop {
+            // SAFETY: The peer address is not requested, so no output buffers are passed.
+            let fd = unsafe { libc::accept4(parent_fd, ptr::null_mut(), ptr::null_mut(), libc::SOCK_CLOEXEC) };
+
+            match fd {
+                -1 => {
+                    let e = std::io::Error::last_os_error();
+
+                    if e.kind() != ErrorKind::Interrupted {
+                        return Err(AfAlgError::Io(e));
+                    }
+                }
+                // SAFETY: The descriptor was just created and is owned by nothing else.
+                fd => break File::from(unsafe { OwnedFd::from_raw_fd(fd) }),
+            }
+        };
+
+        trace::event!(debug, algorithm = name, "af_alg rng opened");
+
+        Ok(Self {
+            socket,
+            algorithm: String::from(name),
+        })
+    }
+
+    /// Get the algorithm or driver name the RNG was opened with.
+    pub fn algorithm(&self) -> &str {
+        &self.algorithm
+    }
+}
+
+unsafe impl EntropySource for AfAlgRng {
+    type EntropySourceError = AfAlgError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        let mut filled = 0;
+
+        while filled < buffer.len() {
+            match (&self.socket).read(&mut buffer[filled..]) {
+                Ok(0) => return Err(AfAlgError::UnexpectedEof),
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(AfAlgError::Io(e)),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// NOTE: Credited as the kernel's generator, whichever implementation the name selected.
+impl EntropyAssessment for AfAlgRng {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Os,
+            basis: Basis::Generator,
+        }
+    }
+}
+
+impl SelfTest for AfAlgRng {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
@@ -0,0 +1,173 @@
+use core::error::Error;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{Classify, ErrorKind};
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+/// Number of attempts per word before giving up.
+#[cfg(target_arch = "aarch64")]
+const RETRIES: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArmRngError {
+    /// The CPU (or target architecture) does not implement FEAT_RNG.
+    Unsupported,
+    /// The register kept reporting failure within the retry limit.
+    Exhausted,
+}
+
+impl core::fmt::Display for ArmRngError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for ArmRngError {}
+
+impl Classify for ArmRngError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Unsupported => ErrorKind::Unsupported,
+            Self::Exhausted => ErrorKind::Exhausted,
+        }
+    }
+}
+
+#[cfg(all(target_arch = "aarch64", feature = "std"))]
+fn has_rndr() -> bool {
+    std::arch::is_aarch64_feature_detected!("rand")
+}
+
+/// NOTE: Without `std`, support is read from `ID_AA64ISAR0_EL1`, which requires EL1 or kernel emulation (as on Linux).
+#[cfg(all(target_arch = "aarch64", not(feature = "std")))]
+fn has_rndr() -> bool {
+    let isar0: u64;
+
+    // SAFETY: Reading the ID register has no side effects.
+    unsafe { core::arch::asm!("mrs {}, ID_AA64ISAR0_EL1", out(reg) isar0, options(nomem, nostack)) };
+
+    (isar0 >> 60) & 0xf >= 1
+}
+
+/// Read `RNDR`, returning `None` if the hardware reported a failure.
+#[cfg(target_arch = "aarch64")]
+fn rndr() -> Option<u64> {
+    let value: u64;
+    let ok: u64;
+
+    // SAFETY: Only called once FEAT_RNG has been detected. `s3_3_c2_c4_0` is the encoding of `RNDR`, which sets the Z
+    // flag on failure.
+    unsafe {
+        core::arch::asm!(
+            "mrs {value}, s3_3_c2_c4_0",
+            "cset {ok}, ne",
+            value = out(reg) value,
+            ok = out(reg) ok,
+            options(nomem, nostack)
+        )
+    };
+
+    (ok == 1).then_some(value)
+}
+
+/// Read `RNDRRS` (reseeding the hardware DRBG first), returning `None` if the hardware reported a failure.
+#[cfg(target_arch = "aarch64")]
+fn rndrrs() -> Option<u64> {
+    let value: u64;
+    let ok: u64;
+
+    // SAFETY: As for `rndr`; `s3_3_c2_c4_1` is the encoding of `RNDRRS`.
+    unsafe {
+        core::arch::asm!(
+            "mrs {value}, s3_3_c2_c4_1",
+            "cset {ok}, ne",
+            value = out(reg) value,
+            ok = out(reg) ok,
+            options(nomem, nostack)
+        )
+    };
+
+    (ok == 1).then_some(value)
+}
+
+/// Entropy from the ARMv8.5 FEAT_RNG `RNDR` (or `RNDRRS`) register.
+///
+/// NOTE: Support is detected at runtime; on other architectures `new` always fails.
+pub struct ArmRndr {
+    #[cfg_attr(not(target_arch = "aarch64"), allow(dead_code))]
+    reseed: bool,
+}
+
+impl ArmRndr {
+    /// Attempt to create a source reading `RNDR`, failing if the CPU does not implement FEAT_RNG.
+    pub fn new() -> Result<Self, ArmRngError> {
+        Self::with_reseed(false)
+    }
+
+    /// Attempt to create a source reading `RNDRRS`, which reseeds the hardware DRBG before every read.
+    pub fn new_reseeded() -> Result<Self, ArmRngError> {
+        Self::with_reseed(true)
+    }
+
+    fn with_reseed(reseed: bool) -> Result<Self, ArmRngError> {
+        #[cfg(target_arch = "aarch64")]
+        if has_rndr() {
+            return Ok(Self { reseed });
+        }
+
+        let _ = reseed;
+
+        Err(ArmRngError::Unsupported)
+    }
+}
+
+unsafe impl EntropySource for ArmRndr {
+    type EntropySourceError = ArmRngError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        #[cfg(target_arch = "aarch64")]
+        {
+            let read = match self.reseed {
+                true => rndrrs,
+                false => rndr,
+            };
+
+            for chunk in buffer.chunks_mut(8) {
+                let word = (0..RETRIES).find_map(|_| read()).ok_or(ArmRngError::Exhausted)?;
+
+                chunk.copy_from_slice(&word.to_ne_bytes()[..chunk.len()]);
+            }
+
+            Ok(())
+        }
+
+        #[cfg(not(target_arch = "aarch64"))]
+        {
+            let _ = buffer;
+            Err(ArmRngError::Unsupported)
+        }
+    }
+}
+
+impl EntropyAssessment for ArmRndr {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Hardware,
+            basis: Basis::Generator,
+        }
+    }
+}
+
+impl SelfTest for ArmRndr {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
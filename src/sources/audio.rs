@@ -0,0 +1,337 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SampleFormat, SizedSample};
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::condition::Conditioned;
+use crate::error::{Classify, ErrorKind};
+use crate::health::{HealthError, Monitored, MonitoredError};
+use crate::trace;
+use crate::EntropySource;
+
+/// Default min-entropy claimed per raw byte (8 packed sample LSBs), in bits.
+const DEFAULT_MIN_ENTROPY: u8 = 1;
+
+/// Default time to wait for enough samples before a read fails.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Maximum number of raw bytes kept while nobody is reading.
+const MAX_BUFFERED: usize = 1 << 16;
+
+#[derive(Debug)]
+pub enum AudioNoiseError {
+    /// There is no default input device.
+    NoDevice,
+    /// The input device has no usable default configuration.
+    Config(cpal::DefaultStreamConfigError),
+    /// The input device only offers a sample format without usable low bits.
+    UnsupportedFormat(SampleFormat),
+    /// The input stream could not be built.
+    Build(cpal::BuildStreamError),
+    /// The input stream could not be started.
+    Play(cpal::PlayStreamError),
+    /// The input stream failed while running.
+    Stream(cpal::StreamError),
+    /// The input stream failed during an earlier read.
+    Stopped,
+    /// Not enough samples arrived in time.
+    Timeout,
+    /// A continuous health test failed on the raw samples.
+    Health(HealthError),
+}
+
+impl std::fmt::Display for AudioNoiseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for AudioNoiseError {}
+
+impl Classify for AudioNoiseError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::NoDevice | Self::Config(_) | Self::UnsupportedFormat(_) => ErrorKind::Unsupported,
+            Self::Build(_) | Self::Play(_) | Self::Stream(_) | Self::Stopped => ErrorKind::Backend(0),
+            Self::Timeout => ErrorKind::Timeout,
+            Self::Health(e) => e.kind(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Capture {
+    bytes: VecDeque<u8>,
+    pending: u8,
+    bits: u32,
+    error: Option<cpal::StreamError>,
+    stopped: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+    capture: Mutex<Capture>,
+    ready: Condvar,
+}
+
+impl Shared {
+    /// Pack the least significant bit (at 16-bit resolution) of every sample into raw bytes.
+    fn push<T: Sample>(&self, samples: &[T])
+    where
+        i16: FromSample<T>,
+    {
+        let mut capture = self.capture.lock().unwrap_or_else(|e| e.into_inner());
+
+        for sample in samples {
+            let bit = sample.to_sample::<i16>() as u8 & 1;
+
+            capture.pending = (capture.pending << 1) | bit;
+            capture.bits += 1;
+
+            if capture.bits == 8 {
+                if capture.bytes.len() < MAX_BUFFERED {
+                    let byte = capture.pending;
+
+                    capture.bytes.push_back(byte);
+                }
+
+                capture.pending = 0;
+                capture.bits = 0;
+            }
+        }
+
+        self.ready.notify_all();
+    }
+
+    fn fail(&self, error: cpal::StreamError) {
+        let mut capture = self.capture.lock().unwrap_or_else(|e| e.into_inner());
+
+        capture.error = Some(error);
+        capture.stopped = true;
+
+        self.ready.notify_all();
+    }
+}
+
+/// The raw, unconditioned sample LSBs.
+struct AudioSamples {
+    shared: Arc<Shared>,
+    timeout: Duration,
+    min_entropy: u8,
+}
+
+unsafe impl EntropySource for AudioSamples {
+    type EntropySourceError = AudioNoiseError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let deadline = Instant::now() + self.timeout;
+
+        let mut capture = self.shared.capture.lock().unwrap_or_else(|e| e.into_inner());
+        let mut filled = 0;
+
+        while filled < buffer.len() {
+            if let Some(e) = capture.error.take() {
+                return Err(AudioNoiseError::Stream(e));
+            }
+
+            if capture.stopped {
+                return Err(AudioNoiseError::Stopped);
+            }
+
+            let available = capture.bytes.len().min(buffer.len() - filled);
+
+            for (byte, raw) in buffer[filled..filled + available].iter_mut().zip(capture.bytes.drain(..available)) {
+                *byte = raw;
+            }
+
+            filled += available;
+
+            if filled == buffer.len() {
+                break;
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            if remaining.is_zero() {
+                buffer.fill(0);
+
+                return Err(AudioNoiseError::Timeout);
+            }
+
+            capture = match self.shared.ready.wait_timeout(capture, remaining) {
+                Ok((capture, _)) => capture,
+                Err(e) => e.into_inner().0,
+            };
+        }
+
+        Ok(())
+    }
+}
+
+/// NOTE: Credited with the min-entropy claimed with `with_min_entropy`, as for the health tests.
+impl EntropyAssessment for AudioSamples {
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.min_entropy as f64
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Noise,
+            basis: Basis::Claimed,
+        }
+    }
+}
+
+/// Entropy from the noise in the least significant bits of an audio input (microphone or line-in), via `cpal`.
+///
+/// The raw bits are health tested against the claimed min-entropy, then conditioned with SHA-256 using
+/// `16 / min_entropy` raw bytes per output byte, so the output carries full entropy if the claim holds.
+///
+/// NOTE: The default claim of 1 bit per 8 samples is deliberately conservative, and yields only a few hundred bytes
+/// per second at 48 kHz. A muted or digitally silent input fails the health tests rather than producing output.
+/// Use this as a second, physically independent source (e.g. with `Combiner`), not as the only one.
+pub struct AudioNoise {
+    source: Conditioned<Monitored<AudioSamples>>,
+    min_entropy: u8,
+    _stream: cpal::Stream,
+}
+
+impl AudioNoise {
+    /// Attempt to start sampling the default input device of the default host.
+    pub fn open_default() -> Result<Self, AudioNoiseError> {
+        match cpal::default_host().default_input_device() {
+            Some(device) => Self::open(&device),
+            None => Err(AudioNoiseError::NoDevice),
+        }
+    }
+
+    /// Attempt to start sampling the given input device, with its default configuration.
+    pub fn open(device: &cpal::Device) -> Result<Self, AudioNoiseError> {
+        let config = match device.default_input_config() {
+            Ok(config) => config,
+            Err(e) => return Err(AudioNoiseError::Config(e)),
+        };
+
+        let shared = Arc::new(Shared::default());
+
+        let stream = match config.sample_format() {
+            SampleFormat::I16 => build::<i16>(device, &config.config(), &shared),
+            SampleFormat::U16 => build::<u16>(device, &config.config(), &shared),
+            SampleFormat::I32 => build::<i32>(device, &config.config(), &shared),
+            SampleFormat::U32 => build::<u32>(device, &config.config(), &shared),
+            SampleFormat::F32 => build::<f32>(device, &config.config(), &shared),
+            SampleFormat::F64 => build::<f64>(device, &config.config(), &shared),
+            format => return Err(AudioNoiseError::UnsupportedFormat(format)),
+        }?;
+
+        if let Err(e) = stream.play() {
+            return Err(AudioNoiseError::Play(e));
+        }
+
+        let samples = AudioSamples {
+            shared,
+            timeout: DEFAULT_TIMEOUT,
+            min_entropy: DEFAULT_MIN_ENTROPY,
+        };
+
+        Ok(Self {
+            source: Self::pipeline(samples, DEFAULT_MIN_ENTROPY),
+            min_entropy: DEFAULT_MIN_ENTROPY,
+            _stream: stream,
+        })
+    }
+
+    /// Set the min-entropy claimed per raw byte of 8 sample LSBs (in bits, clamped to 1..=8), e.g. from an
+    /// `estimate` of the device.
+    pub fn with_min_entropy(mut self, min_entropy: u8) -> Self {
+        self.min_entropy = min_entropy.clamp(1, 8);
+        self.source = Self::pipeline(self.samples(self.timeout()), self.min_entropy);
+        self
+    }
+
+    /// Set how long a read waits for enough samples before failing.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.source = Self::pipeline(self.samples(timeout), self.min_entropy);
+        self
+    }
+
+    /// Get the min-entropy claimed per raw byte, in bits.
+    pub fn min_entropy(&self) -> u8 {
+        self.min_entropy
+    }
+
+    /// Get how long a read waits for enough samples before failing.
+    pub fn timeout(&self) -> Duration {
+        self.source.source().source().timeout
+    }
+
+    /// Get the health test failure latched on the raw samples, if any.
+    pub fn failure(&self) -> Option<HealthError> {
+        self.source.source().failure()
+    }
+
+    fn samples(&self, timeout: Duration) -> AudioSamples {
+        AudioSamples {
+            shared: self.source.source().source().shared.clone(),
+            timeout,
+            min_entropy: self.min_entropy,
+        }
+    }
+
+    fn pipeline(samples: AudioSamples, min_entropy: u8) -> Conditioned<Monitored<AudioSamples>> {
+        Conditioned::new(Monitored::new(samples, min_entropy)).with_ratio(16usize.div_ceil(min_entropy as usize))
+    }
+}
+
+unsafe impl EntropySource for AudioNoise {
+    type EntropySourceError = AudioNoiseError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        match self.source.read_bytes(buffer) {
+            Ok(()) => Ok(()),
+            Err(MonitoredError::Source(e)) => Err(e),
+            Err(MonitoredError::Health(e)) => Err(AudioNoiseError::Health(e)),
+        }
+    }
+}
+
+impl EntropyAssessment for AudioNoise {
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.source.provenance()
+    }
+}
+
+fn build<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    shared: &Arc<Shared>,
+) -> Result<cpal::Stream, AudioNoiseError>
+where
+    T: SizedSample,
+    i16: FromSample<T>,
+{
+    let data = shared.clone();
+    let error = shared.clone();
+
+    let stream = device.build_input_stream(
+        config,
+        move |samples: &[T], _: &cpal::InputCallbackInfo| data.push(samples),
+        move |e| error.fail(e),
+        None,
+    );
+
+    match stream {
+        Ok(stream) => Ok(stream),
+        Err(e) => Err(AudioNoiseError::Build(e)),
+    }
+}
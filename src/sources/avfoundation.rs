@@ -0,0 +1,322 @@
+use core::ffi::{c_char, c_void, CStr};
+use std::boxed::Box;
+use std::sync::{Condvar, Mutex, OnceLock, PoisonError};
+use std::time::Duration;
+use std::vec::Vec;
+
+use super::camera::{CameraNoiseError, FrameSource};
+
+/// Time to wait for the session to deliver a frame before the capture fails.
+const FRAME_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `kCVPixelFormatType_420YpCbCr8BiPlanarVideoRange` (`'420v'`): 8-bit luma in plane 0, the cameras' native format.
+const PIXEL_FORMAT_420V: u32 = u32::from_be_bytes(*b"420v");
+
+/// `kCVPixelBufferLock_ReadOnly`.
+const LOCK_READ_ONLY: u64 = 1;
+
+/// Name of the delegate class registered with the Objective-C runtime.
+const DELEGATE_CLASS: &CStr = c"LibryptEntropyFrameDelegate";
+
+/// Name of the delegate's instance variable pointing at its `FrameSlot`.
+const SLOT_IVAR: &CStr = c"slot";
+
+type Id = *mut c_void;
+type Sel = *const c_void;
+type Class = *mut c_void;
+type Ivar = *mut c_void;
+
+// NOTE: `BOOL` is `signed char` on x86-64 and `bool` on ARM64; both are returned as a single byte.
+type Bool = i8;
+
+#[link(name = "objc")]
+extern "C" {
+    fn objc_getClass(name: *const c_char) -> Class;
+    fn objc_allocateClassPair(superclass: Class, name: *const c_char, extra_bytes: usize) -> Class;
+    fn objc_registerClassPair(class: Class);
+    fn class_addIvar(class: Class, name: *const c_char, size: usize, alignment: u8, types: *const c_char) -> Bool;
+    fn class_addMethod(class: Class, name: Sel, imp: *const c_void, types: *const c_char) -> Bool;
+    fn class_getInstanceVariable(class: Class, name: *const c_char) -> Ivar;
+    fn ivar_getOffset(ivar: Ivar) -> isize;
+    fn sel_registerName(name: *const c_char) -> Sel;
+    fn objc_msgSend();
+    fn objc_autoreleasePoolPush() -> *mut c_void;
+    fn objc_autoreleasePoolPop(pool: *mut c_void);
+}
+
+#[link(name = "Foundation", kind = "framework")]
+extern "C" {}
+
+#[link(name = "AVFoundation", kind = "framework")]
+extern "C" {
+    static AVMediaTypeVideo: Id;
+}
+
+#[link(name = "CoreMedia", kind = "framework")]
+extern "C" {
+    fn CMSampleBufferGetImageBuffer(sample: *mut c_void) -> *mut c_void;
+}
+
+#[link(name = "CoreVideo", kind = "framework")]
+extern "C" {
+    static kCVPixelBufferPixelFormatTypeKey: Id;
+
+    fn CVPixelBufferLockBaseAddress(buffer: *mut c_void, flags: u64) -> i32;
+    fn CVPixelBufferUnlockBaseAddress(buffer: *mut c_void, flags: u64) -> i32;
+    fn CVPixelBufferGetBaseAddressOfPlane(buffer: *mut c_void, plane: usize) -> *const u8;
+    fn CVPixelBufferGetWidthOfPlane(buffer: *mut c_void, plane: usize) -> usize;
+    fn CVPixelBufferGetHeightOfPlane(buffer: *mut c_void, plane: usize) -> usize;
+    fn CVPixelBufferGetBytesPerRowOfPlane(buffer: *mut c_void, plane: usize) -> usize;
+}
+
+extern "C" {
+    fn dispatch_queue_create(label: *const c_char, attributes: *mut c_void) -> *mut c_void;
+    fn dispatch_sync_f(queue: *mut c_void, context: *mut c_void, work: extern "C" fn(*mut c_void));
+    fn dispatch_release(object: *mut c_void);
+}
+
+/// Send `$selector` to `$receiver`, calling `objc_msgSend` with the argument and return types given.
+macro_rules! send {
+    ($receiver:expr, $selector:expr $(, $arg:expr => $ty:ty)* ; $ret:ty) => {{
+        let imp: unsafe extern "C" fn(Id, Sel $(, $ty)*) -> $ret =
+            core::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+
+        imp($receiver, sel_registerName($selector.as_ptr()) $(, $arg)*)
+    }};
+}
+
+/// The latest frame delivered by the session, handed from its dispatch queue to `capture`.
+struct FrameSlot {
+    frame: Mutex<Option<Vec<u8>>>,
+    ready: Condvar,
+}
+
+/// `captureOutput:didOutputSampleBuffer:fromConnection:`, run by the session on its dispatch queue.
+extern "C" fn did_output(this: Id, _cmd: Sel, _output: Id, sample: *mut c_void, _connection: Id) {
+    // SAFETY: `this` is an instance of the delegate class, whose slot was set before the session started and outlives
+    // it; the pixel buffer is only read between locking and unlocking it.
+    unsafe {
+        let slot = &**slot_of(this);
+        let image = CMSampleBufferGetImageBuffer(sample);
+
+        if image.is_null() || CVPixelBufferLockBaseAddress(image, LOCK_READ_ONLY) != 0 {
+            return;
+        }
+
+        let base = CVPixelBufferGetBaseAddressOfPlane(image, 0);
+        let width = CVPixelBufferGetWidthOfPlane(image, 0);
+        let height = CVPixelBufferGetHeightOfPlane(image, 0);
+        let stride = CVPixelBufferGetBytesPerRowOfPlane(image, 0);
+
+        // NOTE: A panic must not unwind into the session's queue, so a poisoned slot drops the frame instead.
+        if let (false, Ok(mut frame)) = (base.is_null(), slot.frame.lock()) {
+            let luma = frame.get_or_insert_with(Vec::new);
+
+            luma.fill(0);
+            luma.clear();
+
+            for row in 0..height {
+                luma.extend_from_slice(core::slice::from_raw_parts(base.add(row * stride), width));
+            }
+
+            slot.ready.notify_one();
+        }
+
+        CVPixelBufferUnlockBaseAddress(image, LOCK_READ_ONLY);
+    }
+}
+
+/// Get the slot pointer stored in a delegate instance.
+unsafe fn slot_of(delegate: Id) -> *mut *const FrameSlot {
+    let ivar = class_getInstanceVariable(delegate_class() as Class, SLOT_IVAR.as_ptr());
+
+    delegate.cast::<u8>().offset(ivar_getOffset(ivar)).cast()
+}
+
+/// Register the sample buffer delegate class on first use.
+fn delegate_class() -> usize {
+    static CLASS: OnceLock<usize> = OnceLock::new();
+
+    // SAFETY: The class is created and registered once, with an ivar and method matching the declared types.
+    *CLASS.get_or_init(|| unsafe {
+        let class = objc_allocateClassPair(objc_getClass(c"NSObject".as_ptr()), DELEGATE_CLASS.as_ptr(), 0);
+
+        class_addIvar(
+            class,
+            SLOT_IVAR.as_ptr(),
+            size_of::<*const FrameSlot>(),
+            align_of::<*const FrameSlot>().trailing_zeros() as u8,
+            c"^v".as_ptr(),
+        );
+
+        class_addMethod(
+            class,
+            sel_registerName(c"captureOutput:didOutputSampleBuffer:fromConnection:".as_ptr()),
+            did_output as *const c_void,
+            c"v@:@^v@".as_ptr(),
+        );
+
+        objc_registerClassPair(class);
+
+        class as usize
+    })
+}
+
+extern "C" fn drained(_context: *mut c_void) {}
+
+fn poisoned<T>(_: PoisonError<T>) -> std::io::Error {
+    std::io::Error::other("the frame slot is poisoned")
+}
+
+fn unavailable(message: &'static str) -> CameraNoiseError {
+    CameraNoiseError::Io(std::io::Error::new(std::io::ErrorKind::NotFound, message))
+}
+
+/// The default AVFoundation video capture device, streaming `420v` frames through an `AVCaptureSession`.
+///
+/// NOTE: macOS asks the user to allow camera access the first time a process opens a capture device, and opening
+/// fails until access is granted. Frames arrive on a private dispatch queue; `capture` waits up to 5 seconds for one,
+/// and only ever returns the latest.
+pub struct AvFoundationCamera {
+    session: Id,
+    output: Id,
+    delegate: Id,
+    queue: *mut c_void,
+    slot: Box<FrameSlot>,
+}
+
+// SAFETY: `AVCaptureSession` may be started and stopped from any thread, and the frames are handed over through the
+// slot's mutex.
+unsafe impl Send for AvFoundationCamera {}
+
+impl AvFoundationCamera {
+    /// Attempt to open the default video capture device and start streaming at its default resolution.
+    pub fn open() -> Result<Self, CameraNoiseError> {
+        // SAFETY: Objects are only messaged with selectors they implement, with the argument types they declare, and
+        // every object kept is owned (`alloc`/`init`) or retained by the session.
+        unsafe {
+            let pool = objc_autoreleasePoolPush();
+            let result = Self::start();
+
+            objc_autoreleasePoolPop(pool);
+
+            result
+        }
+    }
+
+    unsafe fn start() -> Result<Self, CameraNoiseError> {
+        let device: Id = send!(objc_getClass(c"AVCaptureDevice".as_ptr()), c"defaultDeviceWithMediaType:",
+            AVMediaTypeVideo => Id; Id);
+
+        if device.is_null() {
+            return Err(unavailable("no video capture device"));
+        }
+
+        let mut error: Id = core::ptr::null_mut();
+        let input: Id = send!(objc_getClass(c"AVCaptureDeviceInput".as_ptr()), c"deviceInputWithDevice:error:",
+            device => Id, &mut error => *mut Id; Id);
+
+        if input.is_null() {
+            return Err(CameraNoiseError::Io(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                "the capture device could not be opened (is camera access allowed?)",
+            )));
+        }
+
+        let session: Id = send!(send!(objc_getClass(c"AVCaptureSession".as_ptr()), c"alloc"; Id), c"init"; Id);
+        let output: Id = send!(send!(objc_getClass(c"AVCaptureVideoDataOutput".as_ptr()), c"alloc"; Id), c"init"; Id);
+
+        let format: Id = send!(objc_getClass(c"NSNumber".as_ptr()), c"numberWithUnsignedInt:",
+            PIXEL_FORMAT_420V => u32; Id);
+        let settings: Id = send!(objc_getClass(c"NSDictionary".as_ptr()), c"dictionaryWithObject:forKey:",
+            format => Id, kCVPixelBufferPixelFormatTypeKey => Id; Id);
+
+        let _: () = send!(output, c"setVideoSettings:", settings => Id; ());
+
+        let slot = Box::new(FrameSlot {
+            frame: Mutex::new(None),
+            ready: Condvar::new(),
+        });
+
+        let delegate: Id = send!(send!(delegate_class() as Class, c"alloc"; Id), c"init"; Id);
+
+        *slot_of(delegate) = &*slot;
+
+        let queue = dispatch_queue_create(c"librypt-entropy camera".as_ptr(), core::ptr::null_mut());
+
+        let _: () = send!(output, c"setSampleBufferDelegate:queue:", delegate => Id, queue => *mut c_void; ());
+
+        // NOTE: From here on, dropping the camera tears down everything created above.
+        let camera = Self {
+            session,
+            output,
+            delegate,
+            queue,
+            slot,
+        };
+
+        let added_input: Bool = send!(session, c"canAddInput:", input => Id; Bool);
+        let added_output: Bool = send!(session, c"canAddOutput:", output => Id; Bool);
+
+        if added_input == 0 || added_output == 0 {
+            return Err(CameraNoiseError::NotCapture);
+        }
+
+        let _: () = send!(session, c"addInput:", input => Id; ());
+        let _: () = send!(session, c"addOutput:", output => Id; ());
+        let _: () = send!(session, c"startRunning"; ());
+
+        Ok(camera)
+    }
+}
+
+impl FrameSource for AvFoundationCamera {
+    fn capture(&mut self, luma: &mut Vec<u8>) -> std::io::Result<()> {
+        let mut frame = self.slot.frame.lock().map_err(poisoned)?;
+
+        loop {
+            if let Some(latest) = frame.as_mut() {
+                luma.clear();
+                luma.extend_from_slice(latest);
+
+                latest.fill(0);
+                *frame = None;
+
+                return Ok(());
+            }
+
+            let (next, timeout) = self.slot.ready.wait_timeout(frame, FRAME_TIMEOUT).map_err(poisoned)?;
+
+            if timeout.timed_out() && next.is_none() {
+                return Err(std::io::ErrorKind::TimedOut.into());
+            }
+
+            frame = next;
+        }
+    }
+}
+
+impl Drop for AvFoundationCamera {
+    fn drop(&mut self) {
+        // SAFETY: The session is stopped and its queue drained before the delegate and slot it points at are released.
+        unsafe {
+            let _: () = send!(self.session, c"stopRunning"; ());
+            let _: () = send!(self.output, c"setSampleBufferDelegate:queue:",
+                core::ptr::null_mut() => Id, core::ptr::null_mut() => *mut c_void; ());
+
+            dispatch_sync_f(self.queue, core::ptr::null_mut(), drained);
+
+            let _: () = send!(self.session, c"release"; ());
+            let _: () = send!(self.output, c"release"; ());
+            let _: () = send!(self.delegate, c"release"; ());
+
+            dispatch_release(self.queue);
+        }
+
+        if let Ok(frame) = self.slot.frame.get_mut() {
+            if let Some(frame) = frame.as_mut() {
+                frame.fill(0);
+            }
+        }
+    }
+}
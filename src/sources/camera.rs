@@ -0,0 +1,239 @@
+use std::error::Error;
+use std::vec::Vec;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::condition::Conditioned;
+use crate::error::{io_kind, Classify, ErrorKind};
+use crate::extract::{ExtractError, VonNeumann};
+use crate::health::{HealthError, Monitored, MonitoredError};
+use crate::lock::Lock;
+use crate::trace;
+use crate::EntropySource;
+
+/// Default mean luma above which a frame is not considered dark.
+const DEFAULT_MAX_BRIGHTNESS: u8 = 32;
+
+/// Min-entropy claimed per raw byte (8 pixel LSBs) for the health tests, in bits.
+const MIN_ENTROPY: u8 = 1;
+
+#[derive(Debug)]
+pub enum CameraNoiseError {
+    /// The device could not be opened, configured or read.
+    Io(std::io::Error),
+    /// The device is not a streaming video capture device.
+    NotCapture,
+    /// The device offers neither `YUYV` nor `GREY` frames (contains the offered fourcc).
+    UnsupportedFormat(u32),
+    /// A frame was too bright to be a dark frame (contains its mean luma).
+    NotDark(u8),
+    /// The extractor produced too few bits (e.g. a sensor with denoising that zeroes the low bits).
+    Stalled,
+    /// A continuous health test failed on the raw pixel bits.
+    Health(HealthError),
+}
+
+impl std::fmt::Display for CameraNoiseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for CameraNoiseError {}
+
+impl Classify for CameraNoiseError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Io(e) => io_kind(e),
+            Self::NotCapture | Self::UnsupportedFormat(_) => ErrorKind::Unsupported,
+            Self::NotDark(_) | Self::Stalled => ErrorKind::HealthCheckFailed,
+            Self::Health(e) => e.kind(),
+        }
+    }
+}
+
+/// A source of 8-bit luma frames (e.g. V4L2 or AVFoundation).
+pub trait FrameSource {
+    /// Capture one frame and replace the contents of `luma` with its luma samples.
+    fn capture(&mut self, luma: &mut Vec<u8>) -> std::io::Result<()>;
+}
+
+struct Capture<F: FrameSource> {
+    frames: F,
+    luma: Vec<u8>,
+    bits: Vec<u8>,
+    position: usize,
+    max_brightness: u8,
+}
+
+impl<F: FrameSource> Capture<F> {
+    /// Capture a dark frame and pack the least significant bit of every luma sample into raw bytes.
+    fn refill(&mut self) -> Result<(), CameraNoiseError> {
+        if let Err(e) = self.frames.capture(&mut self.luma) {
+            return Err(CameraNoiseError::Io(e));
+        }
+
+        if self.luma.len() < 8 {
+            return Err(CameraNoiseError::Stalled);
+        }
+
+        let sum = self.luma.iter().map(|&y| y as u64).sum::<u64>();
+        let mean = (sum / self.luma.len() as u64) as u8;
+
+        if mean > self.max_brightness {
+            return Err(CameraNoiseError::NotDark(mean));
+        }
+
+        self.bits.clear();
+        self.bits.extend(
+            self.luma
+                .chunks_exact(8)
+                .map(|pixels| pixels.iter().fold(0u8, |byte, &y| (byte << 1) | (y & 1))),
+        );
+
+        self.luma.fill(0);
+        self.position = 0;
+
+        Ok(())
+    }
+}
+
+/// The raw, unconditioned pixel LSBs.
+struct RawFrames<F: FrameSource> {
+    capture: Lock<Capture<F>>,
+}
+
+unsafe impl<F: FrameSource> EntropySource for RawFrames<F> {
+    type EntropySourceError = CameraNoiseError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        self.capture.with(|capture| {
+            let mut filled = 0;
+
+            while filled < buffer.len() {
+                if capture.position == capture.bits.len() {
+                    capture.refill()?;
+
+                    continue;
+                }
+
+                let n = (capture.bits.len() - capture.position).min(buffer.len() - filled);
+
+                buffer[filled..filled + n].copy_from_slice(&capture.bits[capture.position..capture.position + n]);
+                capture.bits[capture.position..capture.position + n].fill(0);
+
+                capture.position += n;
+                filled += n;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl<F: FrameSource> EntropyAssessment for RawFrames<F> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        MIN_ENTROPY as f64
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Noise,
+            basis: Basis::Claimed,
+        }
+    }
+}
+
+/// Entropy from the shot and read noise of a camera sensor, sampled from dark frames (lens covered, or in a dark
+/// enclosure).
+///
+/// The least significant bit of every luma sample is health tested, debiased with a von Neumann extractor and then
+/// conditioned with SHA-256. Frames brighter than a threshold are rejected, since scene content is not noise.
+///
+/// NOTE: Many webcams denoise or compress in hardware, which can flatten the low bits; the health tests and the
+/// extractor then fail rather than produce output. V4L2 (`V4l2Camera`) and AVFoundation (`AvFoundationCamera`) are
+/// built in; other platforms can implement `FrameSource` themselves.
+pub struct CameraNoise<F: FrameSource> {
+    source: Conditioned<VonNeumann<Monitored<RawFrames<F>>>>,
+}
+
+#[cfg(target_os = "linux")]
+impl CameraNoise<super::V4l2Camera> {
+    /// Attempt to open `/dev/video0` and sample its dark frames.
+    pub fn open() -> Result<Self, CameraNoiseError> {
+        Ok(Self::new(super::V4l2Camera::open()?))
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl CameraNoise<super::AvFoundationCamera> {
+    /// Attempt to open the default AVFoundation video capture device and sample its dark frames.
+    pub fn open() -> Result<Self, CameraNoiseError> {
+        Ok(Self::new(super::AvFoundationCamera::open()?))
+    }
+}
+
+impl<F: FrameSource> CameraNoise<F> {
+    /// Sample the dark frames of a frame source.
+    pub fn new(frames: F) -> Self {
+        let raw = RawFrames {
+            capture: Lock::new(Capture {
+                frames,
+                luma: Vec::new(),
+                bits: Vec::new(),
+                position: 0,
+                max_brightness: DEFAULT_MAX_BRIGHTNESS,
+            }),
+        };
+
+        Self {
+            source: Conditioned::new(VonNeumann::new(Monitored::new(raw, MIN_ENTROPY))),
+        }
+    }
+
+    /// Set the mean luma (0 to 255) above which a frame is rejected as not dark.
+    pub fn with_max_brightness(self, max_brightness: u8) -> Self {
+        self.raw().capture.with(|capture| capture.max_brightness = max_brightness);
+        self
+    }
+
+    /// Get the mean luma above which a frame is rejected as not dark.
+    pub fn max_brightness(&self) -> u8 {
+        self.raw().capture.with(|capture| capture.max_brightness)
+    }
+
+    /// Get the health test failure latched on the raw pixel bits, if any.
+    pub fn failure(&self) -> Option<HealthError> {
+        self.source.source().source().failure()
+    }
+
+    fn raw(&self) -> &RawFrames<F> {
+        self.source.source().source().source()
+    }
+}
+
+unsafe impl<F: FrameSource> EntropySource for CameraNoise<F> {
+    type EntropySourceError = CameraNoiseError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        match self.source.read_bytes(buffer) {
+            Ok(()) => Ok(()),
+            Err(ExtractError::Source(MonitoredError::Source(e))) => Err(e),
+            Err(ExtractError::Source(MonitoredError::Health(e))) => Err(CameraNoiseError::Health(e)),
+            // NOTE: `InvalidSeed` is only returned by `Toeplitz`.
+            Err(ExtractError::Stalled | ExtractError::InvalidSeed) => Err(CameraNoiseError::Stalled),
+        }
+    }
+}
+
+/// NOTE: Derived from the pipeline, starting from the 1 bit per raw byte also claimed for the health tests.
+impl<F: FrameSource> EntropyAssessment for CameraNoise<F> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.source.provenance()
+    }
+}
@@ -0,0 +1,442 @@
+use std::error::Error;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::vec::Vec;
+
+#[cfg(feature = "async")]
+use crate::assess::AsyncEntropyAssessment;
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+#[cfg(feature = "async")]
+use crate::asynchronous::AsyncEntropySource;
+use crate::error::{self, io_kind, Classify};
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+/// `0x00`: report the entropy pool level in bits.
+const CMD_ENTROPY_LEVEL: u8 = 0x00;
+
+/// `0x01 N`: read up to `N` bytes without blocking.
+const CMD_READ_NONBLOCKING: u8 = 0x01;
+
+/// `0x02 N`: read exactly `N` bytes, blocking until available.
+const CMD_READ_BLOCKING: u8 = 0x02;
+
+/// Maximum number of bytes per read command.
+const MAX_REQUEST: usize = 255;
+
+/// Default number of idle connections kept for reuse.
+const DEFAULT_MAX_IDLE: usize = 4;
+
+#[derive(Debug)]
+pub enum EgdError {
+    /// The connection failed.
+    Io(std::io::Error),
+    /// The daemon sent a response that does not follow the protocol.
+    Protocol,
+    /// A non-blocking read returned fewer bytes than requested (the daemon's pool is starved).
+    Starved,
+}
+
+impl std::fmt::Display for EgdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for EgdError {}
+
+impl Classify for EgdError {
+    fn kind(&self) -> error::ErrorKind {
+        match self {
+            Self::Io(e) => io_kind(e),
+            Self::Protocol => error::ErrorKind::Backend(0),
+            Self::Starved => error::ErrorKind::Exhausted,
+        }
+    }
+}
+
+impl From<std::io::Error> for EgdError {
+    fn from(error: std::io::Error) -> Self {
+        match error.kind() {
+            ErrorKind::UnexpectedEof => Self::Protocol,
+            _ => Self::Io(error),
+        }
+    }
+}
+
+/// Which EGD read command to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EgdMode {
+    /// `0x02`: wait until the daemon has enough entropy.
+    Blocking,
+    /// `0x01`: fail with `EgdError::Starved` if the daemon cannot serve the full request immediately.
+    NonBlocking,
+}
+
+#[derive(Clone)]
+enum EgdAddress {
+    #[cfg(unix)]
+    Unix(PathBuf),
+    Tcp(SocketAddr),
+}
+
+enum Connection {
+    #[cfg(unix)]
+    Unix(UnixStream),
+    Tcp(TcpStream),
+}
+
+impl Connection {
+    fn open(address: &EgdAddress) -> std::io::Result<Self> {
+        match address {
+            #[cfg(unix)]
+            EgdAddress::Unix(path) => Ok(Self::Unix(UnixStream::connect(path)?)),
+            EgdAddress::Tcp(address) => Ok(Self::Tcp(TcpStream::connect(address)?)),
+        }
+    }
+
+    fn stream(&mut self) -> &mut dyn ReadWrite {
+        match self {
+            #[cfg(unix)]
+            Self::Unix(stream) => stream,
+            Self::Tcp(stream) => stream,
+        }
+    }
+}
+
+trait ReadWrite: Read + Write {}
+
+impl<T: Read + Write> ReadWrite for T {}
+
+/// An Entropy Gathering Daemon (egd/prngd) client.
+///
+/// NOTE: Connections are pooled: up to `max_idle` connections are kept open between reads, and any connection that
+/// fails is discarded rather than returned to the pool.
+pub struct EgdSource {
+    address: EgdAddress,
+    mode: EgdMode,
+    idle: Mutex<Vec<Connection>>,
+    max_idle: usize,
+}
+
+impl EgdSource {
+    /// Attempt to connect to a daemon listening on a Unix socket.
+    #[cfg(unix)]
+    pub fn connect(path: impl AsRef<Path>) -> Result<Self, EgdError> {
+        Self::with_address(EgdAddress::Unix(path.as_ref().to_path_buf()))
+    }
+
+    /// Attempt to connect to a daemon listening on a TCP socket (as supported by prngd).
+    pub fn connect_tcp(address: SocketAddr) -> Result<Self, EgdError> {
+        Self::with_address(EgdAddress::Tcp(address))
+    }
+
+    fn with_address(address: EgdAddress) -> Result<Self, EgdError> {
+        let connection = Connection::open(&address)?;
+
+        Ok(Self {
+            address,
+            mode: EgdMode::Blocking,
+            idle: Mutex::new([connection].into()),
+            max_idle: DEFAULT_MAX_IDLE,
+        })
+    }
+
+    /// Set the read command to use (blocking by default).
+    pub fn with_mode(mut self, mode: EgdMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the maximum number of idle connections kept for reuse (4 by default).
+    pub fn with_max_idle(mut self, max_idle: usize) -> Self {
+        self.max_idle = max_idle;
+        self
+    }
+
+    /// Attempt to query the daemon's entropy pool level in bits.
+    pub fn entropy_level(&self) -> Result<u32, EgdError> {
+        self.with_connection(|stream| {
+            stream.write_all(&[CMD_ENTROPY_LEVEL])?;
+
+            let mut level = [0u8; 4];
+
+            stream.read_exact(&mut level)?;
+
+            Ok(u32::from_be_bytes(level))
+        })
+    }
+
+    /// Run `f` on a pooled connection, returning it to the pool only if `f` succeeds.
+    fn with_connection<R>(&self, f: impl FnOnce(&mut dyn ReadWrite) -> Result<R, EgdError>) -> Result<R, EgdError> {
+        let pooled = self.idle.lock().unwrap_or_else(|e| e.into_inner()).pop();
+
+        let mut connection = match pooled {
+            Some(connection) => connection,
+            None => Connection::open(&self.address)?,
+        };
+
+        let result = f(connection.stream())?;
+
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+
+        if idle.len() < self.max_idle {
+            idle.push(connection);
+        }
+
+        Ok(result)
+    }
+
+    fn read_chunk(&self, stream: &mut dyn ReadWrite, chunk: &mut [u8]) -> Result<(), EgdError> {
+        match self.mode {
+            EgdMode::Blocking => {
+                stream.write_all(&[CMD_READ_BLOCKING, chunk.len() as u8])?;
+                stream.read_exact(chunk)?;
+            }
+            EgdMode::NonBlocking => {
+                stream.write_all(&[CMD_READ_NONBLOCKING, chunk.len() as u8])?;
+
+                let mut count = [0u8; 1];
+
+                stream.read_exact(&mut count)?;
+
+                let count = count[0] as usize;
+
+                if count > chunk.len() {
+                    return Err(EgdError::Protocol);
+                }
+
+                stream.read_exact(&mut chunk[..count])?;
+
+                if count < chunk.len() {
+                    return Err(EgdError::Starved);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+unsafe impl EntropySource for EgdSource {
+    type EntropySourceError = EgdError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.with_connection(|stream| {
+            for chunk in buffer.chunks_mut(MAX_REQUEST) {
+                self.read_chunk(stream, chunk)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// NOTE: The daemon serves the output of its own pool (or PRNG, for prngd), so it is credited as a generator.
+impl EntropyAssessment for EgdSource {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Other,
+            basis: Basis::Generator,
+        }
+    }
+}
+
+impl SelfTest for EgdSource {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
+
+#[cfg(feature = "async")]
+enum AsyncConnection {
+    #[cfg(unix)]
+    Unix(tokio::net::UnixStream),
+    Tcp(tokio::net::TcpStream),
+}
+
+#[cfg(feature = "async")]
+impl AsyncConnection {
+    async fn open(address: &EgdAddress) -> std::io::Result<Self> {
+        match address {
+            #[cfg(unix)]
+            EgdAddress::Unix(path) => Ok(Self::Unix(tokio::net::UnixStream::connect(path).await?)),
+            EgdAddress::Tcp(address) => Ok(Self::Tcp(tokio::net::TcpStream::connect(address).await?)),
+        }
+    }
+
+    async fn write_all(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        match self {
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.write_all(bytes).await,
+            Self::Tcp(stream) => stream.write_all(bytes).await,
+        }
+    }
+
+    async fn read_exact(&mut self, bytes: &mut [u8]) -> std::io::Result<()> {
+        use tokio::io::AsyncReadExt;
+
+        match self {
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.read_exact(bytes).await.map(|_| ()),
+            Self::Tcp(stream) => stream.read_exact(bytes).await.map(|_| ()),
+        }
+    }
+
+    async fn read_chunk(&mut self, mode: EgdMode, chunk: &mut [u8]) -> Result<(), EgdError> {
+        match mode {
+            EgdMode::Blocking => {
+                self.write_all(&[CMD_READ_BLOCKING, chunk.len() as u8]).await?;
+                self.read_exact(chunk).await?;
+            }
+            EgdMode::NonBlocking => {
+                self.write_all(&[CMD_READ_NONBLOCKING, chunk.len() as u8]).await?;
+
+                let mut count = [0u8; 1];
+
+                self.read_exact(&mut count).await?;
+
+                let count = count[0] as usize;
+
+                if count > chunk.len() {
+                    return Err(EgdError::Protocol);
+                }
+
+                self.read_exact(&mut chunk[..count]).await?;
+
+                if count < chunk.len() {
+                    return Err(EgdError::Starved);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// An async Entropy Gathering Daemon (egd/prngd) client on tokio sockets.
+///
+/// NOTE: Connections are pooled as in `EgdSource`; a read cancelled mid-request drops its connection.
+#[cfg(feature = "async")]
+pub struct AsyncEgdSource {
+    address: EgdAddress,
+    mode: EgdMode,
+    idle: Mutex<Vec<AsyncConnection>>,
+    max_idle: usize,
+}
+
+#[cfg(feature = "async")]
+impl AsyncEgdSource {
+    /// Attempt to connect to a daemon listening on a Unix socket.
+    #[cfg(unix)]
+    pub async fn connect(path: impl AsRef<Path>) -> Result<Self, EgdError> {
+        Self::with_address(EgdAddress::Unix(path.as_ref().to_path_buf())).await
+    }
+
+    /// Attempt to connect to a daemon listening on a TCP socket (as supported by prngd).
+    pub async fn connect_tcp(address: SocketAddr) -> Result<Self, EgdError> {
+        Self::with_address(EgdAddress::Tcp(address)).await
+    }
+
+    async fn with_address(address: EgdAddress) -> Result<Self, EgdError> {
+        let connection = AsyncConnection::open(&address).await?;
+
+        Ok(Self {
+            address,
+            mode: EgdMode::Blocking,
+            idle: Mutex::new([connection].into()),
+            max_idle: DEFAULT_MAX_IDLE,
+        })
+    }
+
+    /// Set the read command to use (blocking by default).
+    pub fn with_mode(mut self, mode: EgdMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the maximum number of idle connections kept for reuse (4 by default).
+    pub fn with_max_idle(mut self, max_idle: usize) -> Self {
+        self.max_idle = max_idle;
+        self
+    }
+
+    /// Attempt to query the daemon's entropy pool level in bits.
+    pub async fn entropy_level(&self) -> Result<u32, EgdError> {
+        let mut connection = self.connection().await?;
+
+        connection.write_all(&[CMD_ENTROPY_LEVEL]).await?;
+
+        let mut level = [0u8; 4];
+
+        connection.read_exact(&mut level).await?;
+
+        self.release(connection);
+
+        Ok(u32::from_be_bytes(level))
+    }
+
+    /// Take a pooled connection, or open a new one.
+    async fn connection(&self) -> Result<AsyncConnection, EgdError> {
+        let pooled = self.idle.lock().unwrap_or_else(|e| e.into_inner()).pop();
+
+        match pooled {
+            Some(connection) => Ok(connection),
+            None => Ok(AsyncConnection::open(&self.address).await?),
+        }
+    }
+
+    /// Return a connection which completed its request to the pool.
+    fn release(&self, connection: AsyncConnection) {
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+
+        if idle.len() < self.max_idle {
+            idle.push(connection);
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+unsafe impl AsyncEntropySource for AsyncEgdSource {
+    type EntropySourceError = EgdError;
+
+    async fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let mut connection = self.connection().await?;
+
+        for chunk in buffer.chunks_mut(MAX_REQUEST) {
+            connection.read_chunk(self.mode, chunk).await?;
+        }
+
+        self.release(connection);
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncEntropyAssessment for AsyncEgdSource {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Other,
+            basis: Basis::Generator,
+        }
+    }
+}
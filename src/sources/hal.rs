@@ -0,0 +1,97 @@
+use core::error::Error;
+use core::fmt::Debug;
+
+use embedded_hal::blocking::rng::Read;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{Classify, ErrorKind};
+use crate::lock::Lock;
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+/// An error reported by the wrapped RNG peripheral.
+#[derive(Debug)]
+pub struct HalRngError<E: Debug>(pub E);
+
+impl<E: Debug> core::fmt::Display for HalRngError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<E: Debug> Error for HalRngError<E> {}
+
+impl<E: Debug> Classify for HalRngError<E> {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Backend(0)
+    }
+}
+
+/// Entropy from a microcontroller RNG peripheral implementing `embedded_hal::blocking::rng::Read`.
+///
+/// NOTE: The peripheral is kept behind a lock since `EntropySource::read_bytes` only takes `&self`.
+pub struct HalRng<R: Read>
+where
+    R::Error: Debug,
+{
+    rng: Lock<R>,
+}
+
+impl<R: Read> HalRng<R>
+where
+    R::Error: Debug,
+{
+    /// Wrap the provided RNG peripheral.
+    pub fn new(rng: R) -> Self {
+        Self { rng: Lock::new(rng) }
+    }
+
+    /// Unwrap the underlying RNG peripheral.
+    pub fn into_inner(self) -> R {
+        self.rng.into_inner()
+    }
+}
+
+unsafe impl<R: Read> EntropySource for HalRng<R>
+where
+    R::Error: Debug,
+{
+    type EntropySourceError = HalRngError<R::Error>;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.rng.with(|rng| match rng.read(buffer) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(HalRngError(e)),
+        })
+    }
+}
+
+/// NOTE: Credited with full entropy, as microcontroller RNG peripherals are specified to deliver; check the part's
+/// datasheet, since some require conditioning of their output.
+impl<R: Read> EntropyAssessment for HalRng<R>
+where
+    R::Error: Debug,
+{
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Hardware,
+            basis: Basis::Specified,
+        }
+    }
+}
+
+impl<R: Read> SelfTest for HalRng<R>
+where
+    R::Error: Debug,
+{
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
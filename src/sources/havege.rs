@@ -0,0 +1,295 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use core::error::Error;
+
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+use crate::assess::{self, Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{Classify, ErrorKind};
+use crate::health::{HealthConfig, HealthError, HealthTests};
+use crate::lock::Lock;
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+/// Number of words in the walk table (32 KiB, larger than a typical L1 data cache).
+const TABLE_WORDS: usize = 8192;
+
+/// Number of table steps per measurement.
+const WALK_STEPS: usize = 64;
+
+/// Default number of unstuck measurements collected per output bit.
+const DEFAULT_OVERSAMPLING: usize = 3;
+
+/// Number of measurements taken by the startup test.
+const STARTUP_MEASUREMENTS: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HavegeError {
+    /// The timer is too coarse (or stuck) to measure the walk.
+    CoarseTimer,
+    /// The Repetition Count Test failed: the same walk time was measured too many times in a row.
+    RepetitionCount,
+    /// The Adaptive Proportion Test failed: a single walk time dominated the test window.
+    AdaptiveProportion,
+}
+
+impl core::fmt::Display for HavegeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for HavegeError {}
+
+impl Classify for HavegeError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::CoarseTimer => ErrorKind::Unsupported,
+            Self::RepetitionCount | Self::AdaptiveProportion => ErrorKind::HealthCheckFailed,
+        }
+    }
+}
+
+impl From<HealthError> for HavegeError {
+    fn from(e: HealthError) -> Self {
+        match e {
+            HealthError::RepetitionCount => HavegeError::RepetitionCount,
+            HealthError::AdaptiveProportion => HavegeError::AdaptiveProportion,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn default_timer() -> u64 {
+    static EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+
+    EPOCH.get_or_init(std::time::Instant::now).elapsed().as_nanos() as u64
+}
+
+struct HavegeState {
+    table: Box<[u32]>,
+    walker: u32,
+    pool: [u8; 32],
+    last_delta: u64,
+    last_delta2: u64,
+    health: HealthTests,
+}
+
+impl HavegeState {
+    fn new() -> Self {
+        Self {
+            table: vec![0u32; TABLE_WORDS].into_boxed_slice(),
+            walker: 0,
+            pool: [0u8; 32],
+            last_delta: 0,
+            last_delta2: 0,
+            health: HealthTests::new(HealthConfig::for_timing_noise()),
+        }
+    }
+
+    /// Walk the table from a position seeded by the previous delta, branching on and indexing by the words read.
+    ///
+    /// NOTE: Which cache lines are touched and which way each branch goes depend on the table, which in turn depends
+    /// on every earlier delta, so the walk time reflects cache and branch predictor state that the walk keeps
+    /// perturbing.
+    fn walk(&mut self) {
+        let mask = TABLE_WORDS - 1;
+        let mut walker = self.walker ^ (self.last_delta as u32);
+
+        for _ in 0..WALK_STEPS {
+            let i = walker as usize & mask;
+            let word = self.table[i];
+
+            if word & 1 == 0 {
+                walker = walker.wrapping_add(word.rotate_left(5));
+            } else {
+                walker ^= word.rotate_right(11);
+            }
+
+            if word & 2 == 0 {
+                walker = walker.wrapping_mul(0x9e37_79b9);
+            } else {
+                walker = walker.rotate_left(13).wrapping_add(1);
+            }
+
+            if walker & 4 == 0 {
+                self.table[i] = word ^ walker;
+            } else {
+                self.table[(i ^ (walker >> 7) as usize) & mask] ^= word.wrapping_add(walker);
+            }
+
+            walker = core::hint::black_box(walker);
+        }
+
+        self.walker = walker;
+    }
+
+    /// Take a single measurement of a walk, returning `None` if the delta is stuck (zero first, second, or third
+    /// derivative).
+    fn measure(&mut self, timer: fn() -> u64) -> Result<Option<u64>, HavegeError> {
+        let start = timer();
+
+        self.walk();
+
+        let delta = timer().wrapping_sub(start);
+        let delta2 = delta.wrapping_sub(self.last_delta);
+        let delta3 = delta2.wrapping_sub(self.last_delta2);
+
+        self.health.test(delta)?;
+
+        self.last_delta = delta;
+        self.last_delta2 = delta2;
+
+        match delta == 0 || delta2 == 0 || delta3 == 0 {
+            true => Ok(None),
+            false => Ok(Some(delta)),
+        }
+    }
+
+    /// Collect `256 * oversampling` unstuck measurements into the pool and derive 32 output bytes.
+    fn generate(&mut self, timer: fn() -> u64, oversampling: usize) -> Result<[u8; 32], HavegeError> {
+        let mut hasher = Sha256::new();
+
+        hasher.update(self.pool);
+
+        let mut collected = 0;
+
+        while collected < 256 * oversampling {
+            if let Some(delta) = self.measure(timer)? {
+                hasher.update(delta.to_le_bytes());
+                collected += 1;
+            }
+        }
+
+        self.pool = hasher.finalize().into();
+
+        let mut hasher = Sha256::new();
+
+        hasher.update([0x00]);
+        hasher.update(self.pool);
+
+        Ok(hasher.finalize().into())
+    }
+}
+
+impl Drop for HavegeState {
+    fn drop(&mut self) {
+        self.table.zeroize();
+        self.walker.zeroize();
+        self.pool.zeroize();
+    }
+}
+
+/// An entropy source timing walks over a table larger than the L1 cache (in the style of HAVEGE), so that the
+/// measured times reflect the processor's cache and branch predictor state.
+///
+/// Unlike `JitterSource`, which times a fixed memory access pattern, each walk here takes data-dependent branches and
+/// indices driven by the previous walk time, so the two sources measure different microarchitectural effects.
+///
+/// NOTE: Each output block conditions `256 * oversampling` walk times through SHA-256, and every walk time runs through
+/// the SP 800-90B Repetition Count and Adaptive Proportion tests. A failed health test is latched permanently. On
+/// hosts where software timing noise is the only option, combine this with a `JitterSource` (e.g. in a `Combiner`)
+/// rather than relying on either alone.
+pub struct Havege {
+    state: Lock<HavegeState>,
+    timer: fn() -> u64,
+    oversampling: usize,
+}
+
+impl Havege {
+    /// Attempt to create a HAVEGE source using the monotonic system clock (nanosecond resolution).
+    #[cfg(feature = "std")]
+    pub fn new() -> Result<Self, HavegeError> {
+        Self::with_timer(default_timer)
+    }
+
+    /// Attempt to create a HAVEGE source using a custom high-resolution timer (e.g. a cycle counter).
+    ///
+    /// NOTE: This runs a startup test and fails if the timer is too coarse to measure the walk.
+    pub fn with_timer(timer: fn() -> u64) -> Result<Self, HavegeError> {
+        let mut state = HavegeState::new();
+        let mut measured = 0;
+        let mut stuck = 0;
+        let mut failure = None;
+
+        while measured < STARTUP_MEASUREMENTS {
+            measured += 1;
+
+            match state.measure(timer) {
+                Ok(Some(_)) => {}
+                Ok(None) => stuck += 1,
+                Err(e) => {
+                    failure = Some(e);
+                    break;
+                }
+            }
+        }
+
+        if stuck * 10 > measured * 9 {
+            return Err(HavegeError::CoarseTimer);
+        }
+
+        if let Some(e) = failure {
+            return Err(e);
+        }
+
+        Ok(Self {
+            state: Lock::new(state),
+            timer,
+            oversampling: DEFAULT_OVERSAMPLING,
+        })
+    }
+
+    /// Set the number of measurements collected per output bit (3 by default, minimum 1).
+    pub fn with_oversampling(mut self, oversampling: usize) -> Self {
+        self.oversampling = oversampling.max(1);
+        self
+    }
+}
+
+unsafe impl EntropySource for Havege {
+    type EntropySourceError = HavegeError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.state.with(|state| {
+            if let Some(e) = state.health.failure() {
+                return Err(e.into());
+            }
+
+            for chunk in buffer.chunks_mut(32) {
+                let mut block = state.generate(self.timer, self.oversampling)?;
+
+                chunk.copy_from_slice(&block[..chunk.len()]);
+
+                block.zeroize();
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// NOTE: Every output bit is conditioned from `oversampling` walk times, each claimed (as for `JitterSource`) to carry
+/// at least 1 bit of min-entropy.
+impl EntropyAssessment for Havege {
+    fn min_entropy_per_byte(&self) -> f64 {
+        assess::conditioned(256.0 * self.oversampling as f64, 32)
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Noise,
+            basis: Basis::Claimed,
+        }
+    }
+}
+
+impl SelfTest for Havege {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
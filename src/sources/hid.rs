@@ -0,0 +1,270 @@
+use core::borrow::Borrow;
+use core::error::Error;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{Classify, ErrorKind};
+use crate::lock::Lock;
+use crate::pool::{Fortuna, FortunaError};
+use crate::trace;
+use crate::EntropySource;
+
+/// Default Fortuna source id used for input events.
+const DEFAULT_SOURCE_ID: u8 = 0x48;
+
+/// Default estimated entropy (in bits) required before reads succeed.
+const DEFAULT_MIN_BITS: u64 = 256;
+
+/// Most bits credited for a single event.
+const MAX_CREDIT: u32 = 11;
+
+#[derive(Debug)]
+pub enum HidEventsError {
+    /// Not enough entropy has been collected yet (contains the estimated bits so far).
+    Insufficient(u64),
+    /// The pool failed.
+    Pool(FortunaError),
+}
+
+impl core::fmt::Display for HidEventsError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for HidEventsError {}
+
+impl Classify for HidEventsError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Insufficient(_) => ErrorKind::Exhausted,
+            Self::Pool(e) => e.kind(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Timing {
+    last_time: u64,
+    last_delta: i64,
+    last_delta2: i64,
+    events: u64,
+    bits: u64,
+}
+
+impl Timing {
+    /// Estimate the entropy of an event from its first, second and third order timing deltas (like the Linux
+    /// kernel's `add_timer_randomness`), and return the first order delta.
+    fn update(&mut self, timestamp: u64) -> u64 {
+        let delta = timestamp.wrapping_sub(self.last_time) as i64;
+        let delta2 = delta.wrapping_sub(self.last_delta);
+        let delta3 = delta2.wrapping_sub(self.last_delta2);
+
+        self.last_time = timestamp;
+        self.last_delta = delta;
+        self.last_delta2 = delta2;
+
+        let smallest = delta.unsigned_abs().min(delta2.unsigned_abs()).min(delta3.unsigned_abs()) >> 1;
+        let credit = (u64::BITS - smallest.leading_zeros()).min(MAX_CREDIT);
+
+        // NOTE: The first event only establishes the baseline.
+        if self.events > 0 {
+            self.bits += credit as u64;
+        }
+
+        self.events += 1;
+
+        delta as u64
+    }
+}
+
+/// Entropy from the timing of human input events (keystrokes, mouse movement), accumulated into a Fortuna pool.
+///
+/// Events are pushed with `feed_event`, e.g. from a GUI's "move your mouse" key generation step, or read from a
+/// Linux evdev device with `Evdev`. Each event is added to the pool and credited conservatively from the variation of
+/// its timing; reads fail with `Insufficient` until the credited total reaches the configured minimum.
+///
+/// NOTE: Timestamps should come from a fine-grained monotonic clock (nanoseconds or CPU cycles); coarse clocks earn
+/// little credit. Event codes add no credit, since they are often guessable. The pool also needs a few dozen events
+/// before its first reseed, so keep collecting while reads fail with `Insufficient`, even past the minimum credit.
+pub struct HidEvents<P: Borrow<Fortuna> = Fortuna> {
+    pool: P,
+    source_id: u8,
+    min_bits: u64,
+    timing: Lock<Timing>,
+}
+
+impl HidEvents {
+    /// Collect input events into a new, private pool.
+    pub fn new() -> Self {
+        Self::with_pool(Fortuna::new())
+    }
+}
+
+impl Default for HidEvents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: Borrow<Fortuna>> HidEvents<P> {
+    /// Collect input events into an existing pool (e.g. `&Fortuna` or `Arc<Fortuna>` shared with other sources).
+    pub fn with_pool(pool: P) -> Self {
+        Self {
+            pool,
+            source_id: DEFAULT_SOURCE_ID,
+            min_bits: DEFAULT_MIN_BITS,
+            timing: Lock::new(Timing::default()),
+        }
+    }
+
+    /// Set the Fortuna source id the events are added under.
+    pub fn with_source_id(mut self, source_id: u8) -> Self {
+        self.source_id = source_id;
+        self
+    }
+
+    /// Set the estimated entropy (in bits) required before reads succeed.
+    pub fn with_min_bits(mut self, min_bits: u64) -> Self {
+        self.min_bits = min_bits;
+        self
+    }
+
+    /// Attempt to add an input event, timestamped by a monotonic clock, with a device-specific code (e.g. a key or
+    /// button code, or an axis).
+    pub fn feed_event(&self, timestamp: u64, code: u32) -> Result<(), HidEventsError> {
+        let delta = self.timing.with(|timing| timing.update(timestamp));
+
+        let mut event = [0u8; 20];
+
+        event[..8].copy_from_slice(&timestamp.to_le_bytes());
+        event[8..16].copy_from_slice(&delta.to_le_bytes());
+        event[16..].copy_from_slice(&code.to_le_bytes());
+
+        let result = self.pool.borrow().add_event(self.source_id, &event);
+
+        event.fill(0);
+
+        match result {
+            Ok(()) => Ok(()),
+            Err(e) => Err(HidEventsError::Pool(e)),
+        }
+    }
+
+    /// Get the number of events fed so far.
+    pub fn events(&self) -> u64 {
+        self.timing.with(|timing| timing.events)
+    }
+
+    /// Get the estimated entropy collected so far, in bits.
+    pub fn estimated_bits(&self) -> u64 {
+        self.timing.with(|timing| timing.bits)
+    }
+
+    /// Get a reference to the pool.
+    pub fn pool(&self) -> &Fortuna {
+        self.pool.borrow()
+    }
+}
+
+unsafe impl<P: Borrow<Fortuna>> EntropySource for HidEvents<P> {
+    type EntropySourceError = HidEventsError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        let bits = self.estimated_bits();
+
+        if bits < self.min_bits {
+            return Err(HidEventsError::Insufficient(bits));
+        }
+
+        match self.pool.borrow().read_bytes(buffer) {
+            Ok(()) => Ok(()),
+            Err(FortunaError::NotSeeded) => Err(HidEventsError::Insufficient(bits)),
+            Err(e) => Err(HidEventsError::Pool(e)),
+        }
+    }
+}
+
+/// NOTE: Reads only succeed once `min_bits` have been estimated, so the output is credited with full entropy if that
+/// covers a 256-bit seed, and with nothing otherwise.
+impl<P: Borrow<Fortuna>> EntropyAssessment for HidEvents<P> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        match self.min_bits >= 256 {
+            true => 8.0,
+            false => 0.0,
+        }
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Noise,
+            basis: Basis::Estimated,
+        }
+    }
+}
+
+/// A Linux evdev input device (`/dev/input/event*`).
+///
+/// NOTE: Reading input devices usually requires membership of the `input` group.
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub struct Evdev {
+    file: std::fs::File,
+}
+
+#[cfg(all(feature = "std", target_os = "linux"))]
+impl Evdev {
+    /// Attempt to open the evdev device at `path`.
+    pub fn open(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        Ok(Self {
+            file: std::fs::File::open(path)?,
+        })
+    }
+
+    /// Attempt to block for the next batch of input events and feed them to `events`, returning how many were fed.
+    ///
+    /// NOTE: `EV_SYN` events only mark the end of a batch and are skipped.
+    pub fn feed<P: Borrow<Fortuna>>(&mut self, events: &HidEvents<P>) -> std::io::Result<usize> {
+        use std::io::Read;
+
+        const EVENT_SIZE: usize = size_of::<libc::input_event>();
+        const EV_SYN: u16 = 0x00;
+
+        let mut batch = [0u8; EVENT_SIZE * 64];
+
+        let n = loop {
+            match self.file.read(&mut batch) {
+                Ok(n) => break n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        };
+
+        let mut fed = 0;
+
+        for raw in batch[..n].chunks_exact(EVENT_SIZE) {
+            // SAFETY: The kernel writes whole `struct input_event` records, and `read_unaligned` handles alignment.
+            let event = unsafe { raw.as_ptr().cast::<libc::input_event>().read_unaligned() };
+
+            if event.type_ == EV_SYN {
+                continue;
+            }
+
+            let timestamp = (event.time.tv_sec as u64)
+                .wrapping_mul(1_000_000)
+                .wrapping_add(event.time.tv_usec as u64);
+
+            let code = ((event.type_ as u32) << 16) | event.code as u32;
+
+            if let Err(e) = events.feed_event(timestamp, code) {
+                return Err(std::io::Error::other(e));
+            }
+
+            fed += 1;
+        }
+
+        batch.fill(0);
+
+        Ok(fed)
+    }
+}
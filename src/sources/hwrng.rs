@@ -0,0 +1,107 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use std::path::Path;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{self, io_kind, Classify};
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+/// Default path of the kernel's hardware RNG character device.
+pub const DEFAULT_HWRNG_PATH: &str = "/dev/hwrng";
+
+#[derive(Debug)]
+pub enum HwRngError {
+    /// The device could not be opened or read.
+    Io(std::io::Error),
+    /// The device reported end-of-file before filling the buffer.
+    UnexpectedEof,
+}
+
+impl std::fmt::Display for HwRngError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for HwRngError {}
+
+impl Classify for HwRngError {
+    fn kind(&self) -> error::ErrorKind {
+        match self {
+            Self::Io(e) => io_kind(e),
+            Self::UnexpectedEof => error::ErrorKind::Exhausted,
+        }
+    }
+}
+
+/// Entropy from a hardware RNG character device exposed by the kernel (`/dev/hwrng` by default).
+///
+/// NOTE: Reads are retried on `EINTR` and short reads are continued until the buffer is full.
+pub struct HwRng {
+    file: File,
+}
+
+impl HwRng {
+    /// Attempt to open `/dev/hwrng`.
+    pub fn open() -> Result<Self, HwRngError> {
+        Self::open_path(DEFAULT_HWRNG_PATH)
+    }
+
+    /// Attempt to open the hardware RNG device at `path`.
+    pub fn open_path(path: impl AsRef<Path>) -> Result<Self, HwRngError> {
+        match File::open(path) {
+            Ok(file) => Ok(Self { file }),
+            Err(e) => Err(HwRngError::Io(e)),
+        }
+    }
+}
+
+/// Fill `buffer` from `reader`, retrying on `EINTR` and continuing after short reads.
+pub(crate) fn read_exact(mut reader: impl Read, buffer: &mut [u8]) -> Result<(), HwRngError> {
+    let mut filled = 0;
+
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => return Err(HwRngError::UnexpectedEof),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(HwRngError::Io(e)),
+        }
+    }
+
+    Ok(())
+}
+
+unsafe impl EntropySource for HwRng {
+    type EntropySourceError = HwRngError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        read_exact(&self.file, buffer)
+    }
+}
+
+/// NOTE: Credited with full entropy, as the hardware generators behind `/dev/hwrng` are specified to deliver; the
+/// kernel's own credit for the device (its driver's `quality`) may be lower.
+impl EntropyAssessment for HwRng {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Hardware,
+            basis: Basis::Specified,
+        }
+    }
+}
+
+impl SelfTest for HwRng {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
@@ -0,0 +1,263 @@
+use core::error::Error;
+
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+use crate::assess::{self, Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{Classify, ErrorKind};
+use crate::health::{HealthConfig, HealthError, HealthTests};
+use crate::lock::Lock;
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+/// Size of a memory block touched by the noise loop.
+const MEMORY_BLOCK_SIZE: usize = 32;
+
+/// Number of memory blocks touched by the noise loop.
+const MEMORY_BLOCKS: usize = 64;
+
+/// Minimum number of memory accesses per measurement (a further 0 to 127 are added from the previous delta).
+const MEMORY_ACCESS_LOOPS: usize = 128;
+
+/// Default number of unstuck measurements collected per output bit.
+const DEFAULT_OVERSAMPLING: usize = 3;
+
+/// Number of measurements taken by the startup test.
+const STARTUP_MEASUREMENTS: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JitterError {
+    /// The timer is too coarse (or stuck) to measure execution jitter.
+    CoarseTimer,
+    /// The Repetition Count Test failed: the same delta was measured too many times in a row.
+    RepetitionCount,
+    /// The Adaptive Proportion Test failed: a single delta dominated the test window.
+    AdaptiveProportion,
+}
+
+impl core::fmt::Display for JitterError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for JitterError {}
+
+impl Classify for JitterError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::CoarseTimer => ErrorKind::Unsupported,
+            Self::RepetitionCount | Self::AdaptiveProportion => ErrorKind::HealthCheckFailed,
+        }
+    }
+}
+
+impl From<HealthError> for JitterError {
+    fn from(e: HealthError) -> Self {
+        match e {
+            HealthError::RepetitionCount => JitterError::RepetitionCount,
+            HealthError::AdaptiveProportion => JitterError::AdaptiveProportion,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn default_timer() -> u64 {
+    static EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+
+    EPOCH.get_or_init(std::time::Instant::now).elapsed().as_nanos() as u64
+}
+
+struct JitterState {
+    memory: [u8; MEMORY_BLOCK_SIZE * MEMORY_BLOCKS],
+    location: usize,
+    pool: [u8; 32],
+    last_delta: u64,
+    last_delta2: u64,
+    health: HealthTests,
+}
+
+impl JitterState {
+    fn new() -> Self {
+        Self {
+            memory: [0u8; MEMORY_BLOCK_SIZE * MEMORY_BLOCKS],
+            location: 0,
+            pool: [0u8; 32],
+            last_delta: 0,
+            last_delta2: 0,
+            health: HealthTests::new(HealthConfig::for_timing_noise()),
+        }
+    }
+
+    /// Touch memory in a cache-unfriendly pattern, with the access count varied by the previous delta.
+    fn memory_access(&mut self) {
+        let loops = MEMORY_ACCESS_LOOPS + (self.last_delta & 0x7f) as usize;
+
+        for _ in 0..loops {
+            let byte = &mut self.memory[self.location];
+
+            *byte = core::hint::black_box(byte.wrapping_add(1));
+
+            self.location = (self.location + MEMORY_BLOCK_SIZE - 1) % self.memory.len();
+        }
+    }
+
+    /// Take a single measurement, returning `None` if the delta is stuck (zero first, second, or third derivative).
+    fn measure(&mut self, timer: fn() -> u64) -> Result<Option<u64>, JitterError> {
+        let start = timer();
+
+        self.memory_access();
+
+        let delta = timer().wrapping_sub(start);
+        let delta2 = delta.wrapping_sub(self.last_delta);
+        let delta3 = delta2.wrapping_sub(self.last_delta2);
+
+        self.health.test(delta)?;
+
+        self.last_delta = delta;
+        self.last_delta2 = delta2;
+
+        match delta == 0 || delta2 == 0 || delta3 == 0 {
+            true => Ok(None),
+            false => Ok(Some(delta)),
+        }
+    }
+
+    /// Collect `256 * oversampling` unstuck measurements into the pool and derive 32 output bytes.
+    fn generate(&mut self, timer: fn() -> u64, oversampling: usize) -> Result<[u8; 32], JitterError> {
+        let mut hasher = Sha256::new();
+
+        hasher.update(self.pool);
+
+        let mut collected = 0;
+
+        while collected < 256 * oversampling {
+            if let Some(delta) = self.measure(timer)? {
+                hasher.update(delta.to_le_bytes());
+                collected += 1;
+            }
+        }
+
+        self.pool = hasher.finalize().into();
+
+        let mut hasher = Sha256::new();
+
+        hasher.update([0x00]);
+        hasher.update(self.pool);
+
+        Ok(hasher.finalize().into())
+    }
+}
+
+impl Drop for JitterState {
+    fn drop(&mut self) {
+        self.pool.zeroize();
+    }
+}
+
+/// An entropy source collecting CPU execution-time jitter (in the style of jitterentropy).
+///
+/// NOTE: Each output block conditions `256 * oversampling` timer deltas through SHA-256, and every delta runs through
+/// the SP 800-90B Repetition Count and Adaptive Proportion tests. A failed health test is latched permanently.
+pub struct JitterSource {
+    state: Lock<JitterState>,
+    timer: fn() -> u64,
+    oversampling: usize,
+}
+
+impl JitterSource {
+    /// Attempt to create a jitter source using the monotonic system clock (nanosecond resolution).
+    #[cfg(feature = "std")]
+    pub fn new() -> Result<Self, JitterError> {
+        Self::with_timer(default_timer)
+    }
+
+    /// Attempt to create a jitter source using a custom high-resolution timer (e.g. a cycle counter).
+    ///
+    /// NOTE: This runs a startup test and fails if the timer is too coarse to measure jitter.
+    pub fn with_timer(timer: fn() -> u64) -> Result<Self, JitterError> {
+        let mut state = JitterState::new();
+        let mut measured = 0;
+        let mut stuck = 0;
+        let mut failure = None;
+
+        while measured < STARTUP_MEASUREMENTS {
+            measured += 1;
+
+            match state.measure(timer) {
+                Ok(Some(_)) => {}
+                Ok(None) => stuck += 1,
+                Err(e) => {
+                    failure = Some(e);
+                    break;
+                }
+            }
+        }
+
+        // NOTE: Mirrors jitterentropy, which rejects timers where over 90% of measurements are stuck.
+        if stuck * 10 > measured * 9 {
+            return Err(JitterError::CoarseTimer);
+        }
+
+        if let Some(e) = failure {
+            return Err(e);
+        }
+
+        Ok(Self {
+            state: Lock::new(state),
+            timer,
+            oversampling: DEFAULT_OVERSAMPLING,
+        })
+    }
+
+    /// Set the number of measurements collected per output bit (3 by default, minimum 1).
+    pub fn with_oversampling(mut self, oversampling: usize) -> Self {
+        self.oversampling = oversampling.max(1);
+        self
+    }
+}
+
+unsafe impl EntropySource for JitterSource {
+    type EntropySourceError = JitterError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.state.with(|state| {
+            if let Some(e) = state.health.failure() {
+                return Err(e.into());
+            }
+
+            for chunk in buffer.chunks_mut(32) {
+                let mut block = state.generate(self.timer, self.oversampling)?;
+
+                chunk.copy_from_slice(&block[..chunk.len()]);
+
+                block.zeroize();
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// NOTE: Every 32-byte block conditions `256 * oversampling` deltas, each claimed to carry 1 bit of min-entropy.
+impl EntropyAssessment for JitterSource {
+    fn min_entropy_per_byte(&self) -> f64 {
+        assess::conditioned(256.0 * self.oversampling as f64, 32)
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Noise,
+            basis: Basis::Estimated,
+        }
+    }
+}
+
+impl SelfTest for JitterSource {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
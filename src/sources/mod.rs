@@ -0,0 +1,95 @@
+//! Built-in entropy sources.
+
+#[cfg(all(feature = "af-alg", target_os = "linux"))]
+mod af_alg;
+#[cfg(feature = "hwrng-arm")]
+mod arm;
+#[cfg(feature = "audio")]
+mod audio;
+#[cfg(all(feature = "camera", target_os = "macos"))]
+mod avfoundation;
+#[cfg(feature = "camera")]
+mod camera;
+#[cfg(feature = "egd")]
+mod egd;
+#[cfg(feature = "embedded")]
+mod hal;
+#[cfg(feature = "havege")]
+mod havege;
+#[cfg(feature = "hid")]
+mod hid;
+#[cfg(feature = "hwrng")]
+mod hwrng;
+#[cfg(feature = "jitter")]
+mod jitter;
+#[cfg(feature = "net-jitter")]
+mod net;
+#[cfg(feature = "hsm")]
+mod pkcs11;
+#[cfg(feature = "hwrng-riscv")]
+mod riscv;
+#[cfg(feature = "sgx")]
+mod sgx;
+#[cfg(feature = "tpm")]
+mod tpm;
+#[cfg(all(feature = "camera", target_os = "linux"))]
+mod v4l2;
+#[cfg(all(feature = "virtio", target_os = "linux"))]
+mod virtio;
+#[cfg(all(feature = "wasi", target_arch = "wasm32", target_os = "wasi"))]
+mod wasi;
+#[cfg(all(feature = "wasm-web", target_arch = "wasm32"))]
+mod web;
+#[cfg(all(feature = "windows-bcrypt", windows))]
+mod windows;
+#[cfg(feature = "hwrng-x86")]
+mod x86;
+
+#[cfg(all(feature = "af-alg", target_os = "linux"))]
+pub use af_alg::{AfAlgError, AfAlgRng, DEFAULT_AF_ALG_RNG};
+#[cfg(feature = "hwrng-arm")]
+pub use arm::{ArmRndr, ArmRngError};
+#[cfg(feature = "audio")]
+pub use audio::{AudioNoise, AudioNoiseError};
+#[cfg(all(feature = "camera", target_os = "macos"))]
+pub use avfoundation::AvFoundationCamera;
+#[cfg(feature = "camera")]
+pub use camera::{CameraNoise, CameraNoiseError, FrameSource};
+#[cfg(all(feature = "egd", feature = "async"))]
+pub use egd::AsyncEgdSource;
+#[cfg(feature = "egd")]
+pub use egd::{EgdError, EgdMode, EgdSource};
+#[cfg(feature = "embedded")]
+pub use hal::{HalRng, HalRngError};
+#[cfg(feature = "havege")]
+pub use havege::{Havege, HavegeError};
+#[cfg(all(feature = "hid", feature = "std", target_os = "linux"))]
+pub use hid::Evdev;
+#[cfg(feature = "hid")]
+pub use hid::{HidEvents, HidEventsError};
+#[cfg(feature = "hwrng")]
+pub use hwrng::{HwRng, HwRngError, DEFAULT_HWRNG_PATH};
+#[cfg(feature = "jitter")]
+pub use jitter::{JitterError, JitterSource};
+#[cfg(feature = "net-jitter")]
+pub use net::{NetJitter, NetJitterError, PacketSocket};
+#[cfg(feature = "hsm")]
+pub use pkcs11::{Pkcs11Config, Pkcs11Error, Pkcs11Source};
+#[cfg(feature = "hwrng-riscv")]
+pub use riscv::{RiscvSeed, RiscvSeedError};
+#[cfg(feature = "sgx")]
+pub use sgx::{SgxRand, SgxRandError};
+#[cfg(feature = "tpm")]
+pub use tpm::{DeviceTcti, Tcti, Tpm2, Tpm2Error, DEFAULT_TPM_PATH};
+#[cfg(all(feature = "camera", target_os = "linux"))]
+pub use v4l2::{V4l2Camera, DEFAULT_CAMERA_PATH};
+#[cfg(all(feature = "virtio", target_os = "linux"))]
+pub use virtio::{VirtioRng, VirtioRngError};
+#[cfg(all(feature = "wasi", target_arch = "wasm32", target_os = "wasi"))]
+pub use wasi::{WasiError, WasiRandom};
+#[cfg(all(feature = "wasm-web", target_arch = "wasm32"))]
+pub use web::{WebCrypto, WebCryptoError};
+#[cfg(all(feature = "windows-bcrypt", windows))]
+pub use windows::{BcryptError, WindowsBcrypt};
+#[cfg(feature = "hwrng-x86")]
+pub use x86::{RdRand, RdSeed, X86RngError};
@@ -0,0 +1,214 @@
+use std::error::Error;
+use std::io::Read;
+use std::net::{TcpStream, UdpSocket};
+use std::time::Instant;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::condition::Conditioned;
+use crate::error::{io_kind, Classify, ErrorKind};
+use crate::health::{HealthError, Monitored, MonitoredError};
+use crate::lock::Lock;
+use crate::trace;
+use crate::EntropySource;
+
+/// Min-entropy claimed per packet interarrival time, in bits.
+const MIN_ENTROPY: u8 = 1;
+
+/// Size of the scratch buffer packets are received into (and discarded from).
+const PACKET_BUFFER_SIZE: usize = 2048;
+
+#[derive(Debug)]
+pub enum NetJitterError {
+    /// The source was read without `acknowledge_weakness`.
+    Unacknowledged,
+    /// Receiving from the socket failed (including the socket's own read timeout).
+    Io(std::io::Error),
+    /// The peer closed the connection.
+    Closed,
+    /// A continuous health test failed on the raw timings.
+    Health(HealthError),
+}
+
+impl std::fmt::Display for NetJitterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for NetJitterError {}
+
+impl Classify for NetJitterError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Unacknowledged => ErrorKind::Unsupported,
+            Self::Io(e) => io_kind(e),
+            Self::Closed => ErrorKind::Exhausted,
+            Self::Health(e) => e.kind(),
+        }
+    }
+}
+
+/// A socket whose packet arrivals can be timed.
+pub trait PacketSocket {
+    /// Block until the next packet (or chunk of a stream) arrives, returning its length (0 at end of stream).
+    fn recv_packet(&self, buffer: &mut [u8]) -> std::io::Result<usize>;
+}
+
+impl PacketSocket for UdpSocket {
+    fn recv_packet(&self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        self.recv(buffer)
+    }
+}
+
+impl PacketSocket for TcpStream {
+    fn recv_packet(&self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        let mut stream = self;
+
+        stream.read(buffer)
+    }
+}
+
+#[cfg(unix)]
+impl PacketSocket for std::os::unix::net::UnixDatagram {
+    fn recv_packet(&self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        self.recv(buffer)
+    }
+}
+
+/// The raw timings: the low byte of each interarrival time in nanoseconds.
+struct Interarrivals<P: PacketSocket> {
+    socket: P,
+    last: Lock<Option<Instant>>,
+}
+
+impl<P: PacketSocket> Interarrivals<P> {
+    fn arrival(&self, packet: &mut [u8]) -> Result<Instant, NetJitterError> {
+        loop {
+            match self.socket.recv_packet(packet) {
+                Ok(0) => return Err(NetJitterError::Closed),
+                Ok(_) => return Ok(Instant::now()),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(NetJitterError::Io(e)),
+            }
+        }
+    }
+}
+
+unsafe impl<P: PacketSocket> EntropySource for Interarrivals<P> {
+    type EntropySourceError = NetJitterError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let mut packet = [0u8; PACKET_BUFFER_SIZE];
+
+        self.last.with(|last| {
+            let mut previous = match *last {
+                Some(previous) => previous,
+                None => self.arrival(&mut packet)?,
+            };
+
+            for byte in buffer.iter_mut() {
+                let now = self.arrival(&mut packet)?;
+
+                *byte = now.duration_since(previous).subsec_nanos() as u8;
+
+                previous = now;
+                *last = Some(now);
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl<P: PacketSocket> EntropyAssessment for Interarrivals<P> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        MIN_ENTROPY as f64
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Noise,
+            basis: Basis::Claimed,
+        }
+    }
+}
+
+/// Entropy from the fine-grained interarrival times of packets on a caller-provided socket, for headless machines
+/// (typically VMs) whose only unpredictable events come from the network.
+///
+/// Each packet yields one raw byte (the low 8 bits of the nanosecond delta since the previous packet), credited
+/// with 1 bit of min-entropy, health tested and then conditioned with SHA-256 at 16 raw bytes per output byte.
+/// Received packets are discarded.
+///
+/// NOTE: Packet timing is observable (and partly controllable) by anyone on the network path, and hypervisors often
+/// coarsen or batch delivery. Reads fail with `Unacknowledged` until `acknowledge_weakness` is called; even then,
+/// only use this combined with other sources (e.g. in a `Combiner`), never as the sole source of key material.
+pub struct NetJitter<P: PacketSocket> {
+    source: Conditioned<Monitored<Interarrivals<P>>>,
+    acknowledged: bool,
+}
+
+impl<P: PacketSocket> NetJitter<P> {
+    /// Time the packets arriving on `socket` (set a read timeout on it to bound how long reads can block).
+    pub fn new(socket: P) -> Self {
+        let raw = Interarrivals {
+            socket,
+            last: Lock::new(None),
+        };
+
+        Self {
+            source: Conditioned::new(Monitored::new(raw, MIN_ENTROPY)).with_ratio(16 / MIN_ENTROPY as usize),
+            acknowledged: false,
+        }
+    }
+
+    /// Acknowledge that network timing is a weak, externally influenced source, and allow reading from it.
+    pub fn acknowledge_weakness(mut self) -> Self {
+        self.acknowledged = true;
+        self
+    }
+
+    /// Get whether `acknowledge_weakness` was called.
+    pub fn is_acknowledged(&self) -> bool {
+        self.acknowledged
+    }
+
+    /// Get the health test failure latched on the raw timings, if any.
+    pub fn failure(&self) -> Option<HealthError> {
+        self.source.source().failure()
+    }
+
+    /// Get a reference to the socket.
+    pub fn socket(&self) -> &P {
+        &self.source.source().source().socket
+    }
+}
+
+unsafe impl<P: PacketSocket> EntropySource for NetJitter<P> {
+    type EntropySourceError = NetJitterError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        if !self.acknowledged {
+            return Err(NetJitterError::Unacknowledged);
+        }
+
+        match self.source.read_bytes(buffer) {
+            Ok(()) => Ok(()),
+            Err(MonitoredError::Source(e)) => Err(e),
+            Err(MonitoredError::Health(e)) => Err(NetJitterError::Health(e)),
+        }
+    }
+}
+
+/// NOTE: Conditioning 16 interarrival times of 1 bit each into every output byte credits it with full entropy.
+impl<P: PacketSocket> EntropyAssessment for NetJitter<P> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.source.provenance()
+    }
+}
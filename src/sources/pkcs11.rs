@@ -0,0 +1,200 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::string::String;
+
+use cryptoki::context::{CInitializeArgs, CInitializeFlags, Pkcs11};
+use cryptoki::error::RvError;
+use cryptoki::session::{Session, UserType};
+use cryptoki::slot::Slot;
+use cryptoki::types::AuthPin;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{Classify, ErrorKind};
+use crate::lock::Lock;
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+#[derive(Debug)]
+pub enum Pkcs11Error {
+    /// The PKCS#11 module returned an error.
+    Cryptoki(cryptoki::error::Error),
+    /// No slot was configured and no slot has a token present.
+    NoToken,
+    /// The configured slot ID is not valid.
+    InvalidSlot,
+}
+
+impl std::fmt::Display for Pkcs11Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for Pkcs11Error {}
+
+impl Classify for Pkcs11Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Cryptoki(e) if is_session_lost(e) => ErrorKind::Transient,
+            Self::Cryptoki(_) => ErrorKind::Backend(0),
+            Self::NoToken | Self::InvalidSlot => ErrorKind::Unsupported,
+        }
+    }
+}
+
+impl From<cryptoki::error::Error> for Pkcs11Error {
+    fn from(error: cryptoki::error::Error) -> Self {
+        Self::Cryptoki(error)
+    }
+}
+
+/// Configuration for a `Pkcs11Source`.
+pub struct Pkcs11Config {
+    module: PathBuf,
+    slot: Option<u64>,
+    pin: Option<AuthPin>,
+}
+
+impl Pkcs11Config {
+    /// Use the PKCS#11 module (shared library) at `module`, the first slot with a token, and no login.
+    pub fn new(module: impl AsRef<Path>) -> Self {
+        Self {
+            module: module.as_ref().to_path_buf(),
+            slot: None,
+            pin: None,
+        }
+    }
+
+    /// Use the slot with the given ID.
+    pub fn with_slot(mut self, slot: u64) -> Self {
+        self.slot = Some(slot);
+        self
+    }
+
+    /// Log in as the normal user with the given PIN after opening a session.
+    pub fn with_pin(mut self, pin: impl Into<String>) -> Self {
+        self.pin = Some(AuthPin::from(pin.into()));
+        self
+    }
+}
+
+/// Entropy from an HSM (or other token) via PKCS#11 `C_GenerateRandom`.
+///
+/// NOTE: If the session is lost (e.g. the token is removed and reinserted, or the HSM connection drops), a new session
+/// is opened (and logged in) and the read is retried once.
+pub struct Pkcs11Source {
+    pkcs11: Pkcs11,
+    slot: Slot,
+    pin: Option<AuthPin>,
+    session: Lock<Option<Session>>,
+}
+
+impl Pkcs11Source {
+    /// Attempt to load the configured module and open a session.
+    pub fn open(config: Pkcs11Config) -> Result<Self, Pkcs11Error> {
+        let pkcs11 = Pkcs11::new(&config.module)?;
+
+        match pkcs11.initialize(CInitializeArgs::new(CInitializeFlags::OS_LOCKING_OK)) {
+            Err(cryptoki::error::Error::Pkcs11(RvError::CryptokiAlreadyInitialized, _)) | Ok(_) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let slot = match config.slot {
+            Some(id) => Slot::try_from(id).map_err(|_| Pkcs11Error::InvalidSlot)?,
+            None => *pkcs11.get_slots_with_token()?.first().ok_or(Pkcs11Error::NoToken)?,
+        };
+
+        let source = Self {
+            pkcs11,
+            slot,
+            pin: config.pin,
+            session: Lock::new(None),
+        };
+
+        let session = source.open_session()?;
+
+        source.session.with(|current| *current = Some(session));
+
+        Ok(source)
+    }
+
+    fn open_session(&self) -> Result<Session, Pkcs11Error> {
+        let session = self.pkcs11.open_ro_session(self.slot)?;
+
+        if let Some(pin) = &self.pin {
+            match session.login(UserType::User, Some(pin)) {
+                Err(cryptoki::error::Error::Pkcs11(RvError::UserAlreadyLoggedIn, _)) | Ok(_) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(session)
+    }
+
+    /// Get the slot the source is reading from.
+    pub fn slot(&self) -> u64 {
+        self.slot.id()
+    }
+}
+
+/// Whether an error indicates the session is gone and should be reopened.
+fn is_session_lost(error: &cryptoki::error::Error) -> bool {
+    matches!(
+        error,
+        cryptoki::error::Error::Pkcs11(
+            RvError::SessionHandleInvalid
+                | RvError::SessionClosed
+                | RvError::DeviceRemoved
+                | RvError::DeviceError
+                | RvError::TokenNotPresent
+                | RvError::UserNotLoggedIn,
+            _
+        )
+    )
+}
+
+unsafe impl EntropySource for Pkcs11Source {
+    type EntropySourceError = Pkcs11Error;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.session.with(|session| {
+            if let Some(current) = session {
+                match current.generate_random_slice(buffer) {
+                    Ok(_) => return Ok(()),
+                    Err(e) if is_session_lost(&e) => {}
+                    Err(e) => return Err(e.into()),
+                }
+            }
+
+            *session = None;
+
+            let reopened = session.insert(self.open_session()?);
+
+            reopened.generate_random_slice(buffer)?;
+
+            Ok(())
+        })
+    }
+}
+
+impl EntropyAssessment for Pkcs11Source {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Hardware,
+            basis: Basis::Generator,
+        }
+    }
+}
+
+impl SelfTest for Pkcs11Source {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
@@ -0,0 +1,153 @@
+use core::error::Error;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{Classify, ErrorKind};
+use crate::health::HealthConfig;
+use crate::selftest::{startup_test_with_config, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+/// Number of polls (per 16 bits) while the source reports `BIST` or `WAIT`.
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+const RETRIES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiscvSeedError {
+    /// The target architecture is not RISC-V.
+    Unsupported,
+    /// The source kept reporting `BIST` or `WAIT` within the retry limit.
+    Exhausted,
+    /// The source reported `DEAD`, an unrecoverable self-test or health failure.
+    Dead,
+}
+
+impl core::fmt::Display for RiscvSeedError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for RiscvSeedError {}
+
+impl Classify for RiscvSeedError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Unsupported => ErrorKind::Unsupported,
+            Self::Exhausted => ErrorKind::Exhausted,
+            Self::Dead => ErrorKind::HealthCheckFailed,
+        }
+    }
+}
+
+/// Operational status of the `seed` CSR (bits 31:30).
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+enum Status {
+    Bist,
+    Wait,
+    Es16(u16),
+    Dead,
+}
+
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+fn read_seed() -> Status {
+    let value: usize;
+
+    // SAFETY: Only called on sources whose constructor guaranteed Zkr support. The CSR must be accessed with a write
+    // (`csrrw`), as read-only access is reserved by the specification.
+    unsafe { core::arch::asm!("csrrw {}, seed, x0", out(reg) value, options(nomem, nostack)) };
+
+    match (value >> 30) & 0b11 {
+        0b00 => Status::Bist,
+        0b01 => Status::Wait,
+        0b10 => Status::Es16(value as u16),
+        _ => Status::Dead,
+    }
+}
+
+/// Raw entropy from the RISC-V Zkr `seed` CSR, 16 bits at a time.
+///
+/// NOTE: Zkr output is raw noise source output, which the specification requires to be conditioned (e.g. by a DRBG's
+/// derivation function) before use as key material.
+pub struct RiscvSeed {
+    _private: (),
+}
+
+impl RiscvSeed {
+    /// Create the source on targets compiled with the `zkr` target feature.
+    #[cfg(target_feature = "zkr")]
+    pub fn new() -> Self {
+        Self { _private: () }
+    }
+
+    /// Create the source without checking for Zkr support.
+    ///
+    /// # Safety
+    ///
+    /// The CPU must implement Zkr and the `seed` CSR must be accessible at the current privilege level (e.g. via
+    /// `mseccfg.USEED`/`SSEED`), otherwise reads raise an illegal instruction exception.
+    pub unsafe fn new_unchecked() -> Self {
+        Self { _private: () }
+    }
+}
+
+unsafe impl EntropySource for RiscvSeed {
+    type EntropySourceError = RiscvSeedError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        {
+            for chunk in buffer.chunks_mut(2) {
+                let mut attempts = 0;
+
+                let bits = loop {
+                    match read_seed() {
+                        Status::Es16(bits) => break bits,
+                        Status::Dead => return Err(RiscvSeedError::Dead),
+                        Status::Bist | Status::Wait => {
+                            attempts += 1;
+
+                            if attempts >= RETRIES {
+                                return Err(RiscvSeedError::Exhausted);
+                            }
+
+                            core::hint::spin_loop();
+                        }
+                    }
+                };
+
+                chunk.copy_from_slice(&bits.to_le_bytes()[..chunk.len()]);
+            }
+
+            Ok(())
+        }
+
+        #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+        {
+            let _ = buffer;
+            Err(RiscvSeedError::Unsupported)
+        }
+    }
+}
+
+impl EntropyAssessment for RiscvSeed {
+    /// NOTE: Half a bit per bit, matching the 2:1 conditioning the Zkr specification recommends for seeding a DRBG.
+    fn min_entropy_per_byte(&self) -> f64 {
+        4.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Hardware,
+            basis: Basis::Specified,
+        }
+    }
+}
+
+/// NOTE: The startup tests use the cutoffs for the 4 bits per byte it is credited with, not for full entropy.
+impl SelfTest for RiscvSeed {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test_with_config(self, HealthConfig::for_min_entropy(4))
+    }
+}
@@ -0,0 +1,128 @@
+use core::error::Error;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{Classify, ErrorKind};
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+/// Number of attempts per word, matching `sgx_read_rand` in the Intel SGX SDK.
+#[cfg(target_arch = "x86_64")]
+const RETRIES: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SgxRandError {
+    /// The target architecture cannot host SGX enclaves.
+    Unsupported,
+    /// `RDRAND` kept failing within the retry limit (`SGX_ERROR_UNEXPECTED` in the SDK).
+    Exhausted,
+    /// `RDRAND` returned all ones, which hardware only produces when broken.
+    Faulty,
+}
+
+impl core::fmt::Display for SgxRandError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for SgxRandError {}
+
+impl Classify for SgxRandError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Unsupported => ErrorKind::Unsupported,
+            Self::Exhausted => ErrorKind::Exhausted,
+            Self::Faulty => ErrorKind::HealthCheckFailed,
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "rdrand")]
+unsafe fn rdrand_step(word: &mut u64) -> bool {
+    core::arch::x86_64::_rdrand64_step(word) == 1
+}
+
+/// Entropy from `RDRAND` inside an Intel SGX enclave, with the semantics of the SDK's `sgx_read_rand`.
+///
+/// Enclaves have no `/dev/urandom` and no syscalls, and `cpuid` faults inside them, so unlike `RdRand` this does not
+/// detect support at runtime: every SGX-capable CPU implements `RDRAND`. Each word is retried up to 10 times.
+///
+/// NOTE: `RDRAND` is the only entropy available without an OCALL, and the untrusted host cannot influence it; do not
+/// substitute an OCALL to the host's OS RNG, which the host controls.
+pub struct SgxRand {
+    _private: (),
+}
+
+impl SgxRand {
+    /// Attempt to create the source, failing on targets other than `x86_64`.
+    pub fn new() -> Result<Self, SgxRandError> {
+        #[cfg(target_arch = "x86_64")]
+        return Ok(Self { _private: () });
+
+        #[cfg(not(target_arch = "x86_64"))]
+        Err(SgxRandError::Unsupported)
+    }
+}
+
+unsafe impl EntropySource for SgxRand {
+    type EntropySourceError = SgxRandError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        #[cfg(target_arch = "x86_64")]
+        for chunk in buffer.chunks_mut(8) {
+            let mut word = 0;
+            let mut attempts = 0;
+
+            // SAFETY: `RDRAND` is implemented by every CPU that supports SGX.
+            while !unsafe { rdrand_step(&mut word) } {
+                attempts += 1;
+
+                if attempts >= RETRIES {
+                    buffer.fill(0);
+
+                    return Err(SgxRandError::Exhausted);
+                }
+            }
+
+            if word == u64::MAX {
+                buffer.fill(0);
+
+                return Err(SgxRandError::Faulty);
+            }
+
+            chunk.copy_from_slice(&word.to_ne_bytes()[..chunk.len()]);
+        }
+
+        #[cfg(target_arch = "x86_64")]
+        return Ok(());
+
+        #[cfg(not(target_arch = "x86_64"))]
+        {
+            let _ = buffer;
+            Err(SgxRandError::Unsupported)
+        }
+    }
+}
+
+impl EntropyAssessment for SgxRand {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Hardware,
+            basis: Basis::Generator,
+        }
+    }
+}
+
+impl SelfTest for SgxRand {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
@@ -0,0 +1,214 @@
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::Path;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{io_kind, Classify, ErrorKind};
+use crate::lock::Lock;
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+/// Default path of the kernel's TPM resource manager device.
+pub const DEFAULT_TPM_PATH: &str = "/dev/tpmrm0";
+
+/// `TPM_ST_NO_SESSIONS`.
+const TPM_ST_NO_SESSIONS: u16 = 0x8001;
+
+/// `TPM_CC_GetRandom`.
+const TPM_CC_GET_RANDOM: u32 = 0x0000_017b;
+
+/// `TPM_RC_SUCCESS`.
+const TPM_RC_SUCCESS: u32 = 0x000;
+
+/// Warning response codes after which the command should simply be resubmitted.
+const TPM_RC_RETRYABLE: [u32; 3] = [
+    0x908, // TPM_RC_YIELDED
+    0x90a, // TPM_RC_TESTING
+    0x922, // TPM_RC_RETRY
+];
+
+/// Number of times a command is resubmitted after a retryable response code.
+const RETRIES: usize = 16;
+
+/// Largest number of bytes requested per command (the size of the largest digest, `TPMU_HA`).
+const MAX_REQUEST: usize = 64;
+
+/// Size of the response buffer (the usual `TPM_MAX_COMMAND_SIZE`).
+const MAX_RESPONSE: usize = 4096;
+
+#[derive(Debug)]
+pub enum Tpm2Error {
+    /// The transport failed.
+    Io(std::io::Error),
+    /// The TPM returned a failing response code.
+    ResponseCode(u32),
+    /// The response could not be parsed.
+    MalformedResponse,
+}
+
+impl std::fmt::Display for Tpm2Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for Tpm2Error {}
+
+impl Classify for Tpm2Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Io(e) => io_kind(e),
+            Self::ResponseCode(code) if TPM_RC_RETRYABLE.contains(code) => ErrorKind::Transient,
+            Self::ResponseCode(code) => ErrorKind::Backend(*code as i32),
+            Self::MalformedResponse => ErrorKind::Backend(0),
+        }
+    }
+}
+
+/// A transport for TPM 2.0 commands (a TCTI).
+pub trait Tcti {
+    /// Send a marshalled command and read the marshalled response into `response`, returning its length.
+    fn transmit(&mut self, command: &[u8], response: &mut [u8]) -> std::io::Result<usize>;
+}
+
+/// A TCTI over a TPM character device (`/dev/tpmrm0` or `/dev/tpm0`).
+pub struct DeviceTcti {
+    file: File,
+}
+
+impl DeviceTcti {
+    /// Attempt to open the TPM device at `path`.
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+
+        Ok(Self { file })
+    }
+}
+
+impl Tcti for DeviceTcti {
+    fn transmit(&mut self, command: &[u8], response: &mut [u8]) -> std::io::Result<usize> {
+        self.file.write_all(command)?;
+
+        // NOTE: The kernel driver returns the complete response from a single read.
+        self.file.read(response)
+    }
+}
+
+/// Entropy from a TPM 2.0 via `TPM2_GetRandom`.
+///
+/// NOTE: Commands are sent without sessions (`TPM_ST_NO_SESSIONS`), relying on the kernel resource manager for
+/// context management. Requests are split into digest-sized chunks, and retryable warnings are resubmitted.
+pub struct Tpm2<T: Tcti = DeviceTcti> {
+    tcti: Lock<T>,
+}
+
+impl Tpm2<DeviceTcti> {
+    /// Attempt to open the TPM resource manager at `/dev/tpmrm0`.
+    pub fn open() -> Result<Self, Tpm2Error> {
+        Self::open_path(DEFAULT_TPM_PATH)
+    }
+
+    /// Attempt to open the TPM device at `path`.
+    pub fn open_path(path: impl AsRef<Path>) -> Result<Self, Tpm2Error> {
+        match DeviceTcti::open(path) {
+            Ok(tcti) => Ok(Self::with_tcti(tcti)),
+            Err(e) => Err(Tpm2Error::Io(e)),
+        }
+    }
+}
+
+impl<T: Tcti> Tpm2<T> {
+    /// Use a custom TCTI (e.g. a simulator or network transport).
+    pub fn with_tcti(tcti: T) -> Self {
+        Self { tcti: Lock::new(tcti) }
+    }
+
+    /// Run `TPM2_GetRandom` once, returning the number of bytes written (which may be fewer than requested).
+    fn get_random(tcti: &mut T, output: &mut [u8]) -> Result<usize, Tpm2Error> {
+        let mut command = [0u8; 12];
+
+        command[0..2].copy_from_slice(&TPM_ST_NO_SESSIONS.to_be_bytes());
+        command[2..6].copy_from_slice(&12u32.to_be_bytes());
+        command[6..10].copy_from_slice(&TPM_CC_GET_RANDOM.to_be_bytes());
+        command[10..12].copy_from_slice(&(output.len().min(MAX_REQUEST) as u16).to_be_bytes());
+
+        let mut response = [0u8; MAX_RESPONSE];
+        let mut attempts = 0;
+
+        let length = loop {
+            let length = match tcti.transmit(&command, &mut response) {
+                Ok(length) => length,
+                Err(e) => return Err(Tpm2Error::Io(e)),
+            };
+
+            if length < 10 {
+                return Err(Tpm2Error::MalformedResponse);
+            }
+
+            match u32::from_be_bytes(response[6..10].try_into().unwrap()) {
+                TPM_RC_SUCCESS => break length,
+                code if TPM_RC_RETRYABLE.contains(&code) && attempts < RETRIES => attempts += 1,
+                code => return Err(Tpm2Error::ResponseCode(code)),
+            }
+        };
+
+        // Response: header (10 bytes), then a `TPM2B_DIGEST` (16-bit size and bytes).
+        if length < 12 || u32::from_be_bytes(response[2..6].try_into().unwrap()) as usize != length {
+            return Err(Tpm2Error::MalformedResponse);
+        }
+
+        let size = u16::from_be_bytes([response[10], response[11]]) as usize;
+
+        if size > output.len() || 12 + size > length {
+            return Err(Tpm2Error::MalformedResponse);
+        }
+
+        output[..size].copy_from_slice(&response[12..12 + size]);
+
+        response.fill(0);
+
+        Ok(size)
+    }
+}
+
+unsafe impl<T: Tcti> EntropySource for Tpm2<T> {
+    type EntropySourceError = Tpm2Error;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.tcti.with(|tcti| {
+            let mut filled = 0;
+
+            while filled < buffer.len() {
+                match Self::get_random(tcti, &mut buffer[filled..])? {
+                    0 => return Err(Tpm2Error::MalformedResponse),
+                    n => filled += n,
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl<T: Tcti> EntropyAssessment for Tpm2<T> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Hardware,
+            basis: Basis::Generator,
+        }
+    }
+}
+
+impl<T: Tcti> SelfTest for Tpm2<T> {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
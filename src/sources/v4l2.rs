@@ -0,0 +1,346 @@
+use std::fs::{File, OpenOptions};
+use std::os::fd::AsRawFd;
+use std::path::Path;
+use std::vec::Vec;
+
+use super::camera::{CameraNoiseError, FrameSource};
+
+/// Default path of the first V4L2 capture device.
+pub const DEFAULT_CAMERA_PATH: &str = "/dev/video0";
+
+/// Number of memory-mapped capture buffers requested from the driver.
+const BUFFER_COUNT: u32 = 4;
+
+/// `V4L2_BUF_TYPE_VIDEO_CAPTURE`.
+const V4L2_BUF_TYPE_VIDEO_CAPTURE: u32 = 1;
+
+/// `V4L2_MEMORY_MMAP`.
+const V4L2_MEMORY_MMAP: u32 = 1;
+
+/// `V4L2_CAP_VIDEO_CAPTURE`.
+const V4L2_CAP_VIDEO_CAPTURE: u32 = 0x0000_0001;
+
+/// `V4L2_CAP_STREAMING`.
+const V4L2_CAP_STREAMING: u32 = 0x0400_0000;
+
+/// `V4L2_PIX_FMT_YUYV`: packed 4:2:2, luma in every even byte.
+const V4L2_PIX_FMT_YUYV: u32 = u32::from_le_bytes(*b"YUYV");
+
+/// `V4L2_PIX_FMT_GREY`: 8-bit luma only.
+const V4L2_PIX_FMT_GREY: u32 = u32::from_le_bytes(*b"GREY");
+
+// NOTE: The generic `_IOC` encoding, as used by x86, ARM and RISC-V (not by PowerPC, MIPS or SPARC).
+const fn ioc(dir: libc::c_ulong, nr: libc::c_ulong, size: usize) -> libc::c_ulong {
+    (dir << 30) | ((size as libc::c_ulong) << 16) | ((b'V' as libc::c_ulong) << 8) | nr
+}
+
+const VIDIOC_QUERYCAP: libc::c_ulong = ioc(2, 0, size_of::<V4l2Capability>());
+const VIDIOC_G_FMT: libc::c_ulong = ioc(3, 4, size_of::<V4l2Format>());
+const VIDIOC_S_FMT: libc::c_ulong = ioc(3, 5, size_of::<V4l2Format>());
+const VIDIOC_REQBUFS: libc::c_ulong = ioc(3, 8, size_of::<V4l2RequestBuffers>());
+const VIDIOC_QUERYBUF: libc::c_ulong = ioc(3, 9, size_of::<V4l2Buffer>());
+const VIDIOC_QBUF: libc::c_ulong = ioc(3, 15, size_of::<V4l2Buffer>());
+const VIDIOC_DQBUF: libc::c_ulong = ioc(3, 17, size_of::<V4l2Buffer>());
+const VIDIOC_STREAMON: libc::c_ulong = ioc(1, 18, size_of::<libc::c_int>());
+const VIDIOC_STREAMOFF: libc::c_ulong = ioc(1, 19, size_of::<libc::c_int>());
+
+/// `struct v4l2_capability`.
+#[repr(C)]
+struct V4l2Capability {
+    driver: [u8; 16],
+    card: [u8; 32],
+    bus_info: [u8; 32],
+    version: u32,
+    capabilities: u32,
+    device_caps: u32,
+    reserved: [u32; 3],
+}
+
+/// `struct v4l2_pix_format`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct V4l2PixFormat {
+    width: u32,
+    height: u32,
+    pixelformat: u32,
+    field: u32,
+    bytesperline: u32,
+    sizeimage: u32,
+    colorspace: u32,
+    private: u32,
+    flags: u32,
+    ycbcr_enc: u32,
+    quantization: u32,
+    xfer_func: u32,
+}
+
+/// The `fmt` union of `struct v4l2_format` (200 bytes, pointer-aligned because of `struct v4l2_window`).
+#[repr(C)]
+union V4l2FormatUnion {
+    pix: V4l2PixFormat,
+    raw: [u8; 200],
+    _align: [usize; 0],
+}
+
+/// `struct v4l2_format`.
+#[repr(C)]
+struct V4l2Format {
+    kind: u32,
+    fmt: V4l2FormatUnion,
+}
+
+/// `struct v4l2_requestbuffers`.
+#[repr(C)]
+struct V4l2RequestBuffers {
+    count: u32,
+    kind: u32,
+    memory: u32,
+    capabilities: u32,
+    flags: u8,
+    reserved: [u8; 3],
+}
+
+/// `struct v4l2_timecode`.
+#[repr(C)]
+struct V4l2Timecode {
+    kind: u32,
+    flags: u32,
+    frames: u8,
+    seconds: u8,
+    minutes: u8,
+    hours: u8,
+    userbits: [u8; 4],
+}
+
+/// `struct v4l2_buffer` (the `m` union is read as `offset` only).
+#[repr(C)]
+struct V4l2Buffer {
+    index: u32,
+    kind: u32,
+    bytesused: u32,
+    flags: u32,
+    field: u32,
+    timestamp: libc::timeval,
+    timecode: V4l2Timecode,
+    sequence: u32,
+    memory: u32,
+    m: usize,
+    length: u32,
+    reserved2: u32,
+    request_fd: i32,
+}
+
+impl V4l2Buffer {
+    fn new(index: u32) -> Self {
+        // SAFETY: `struct v4l2_buffer` is plain data for which all zeroes is a valid value.
+        let mut buffer: Self = unsafe { core::mem::zeroed() };
+
+        buffer.index = index;
+        buffer.kind = V4L2_BUF_TYPE_VIDEO_CAPTURE;
+        buffer.memory = V4L2_MEMORY_MMAP;
+
+        buffer
+    }
+
+    fn offset(&self) -> libc::off_t {
+        // NOTE: `m.offset` is the first (`__u32`) member of the union, which is little-endian on the supported targets.
+        self.m as u32 as libc::off_t
+    }
+}
+
+/// Issue an ioctl on `file`, retrying on `EINTR`.
+fn ioctl<T>(file: &File, request: libc::c_ulong, arg: &mut T) -> std::io::Result<()> {
+    loop {
+        // SAFETY: `arg` is the argument structure the kernel expects for `request`.
+        let result = unsafe { libc::ioctl(file.as_raw_fd(), request as _, arg as *mut T) };
+
+        if result != -1 {
+            return Ok(());
+        }
+
+        let error = std::io::Error::last_os_error();
+
+        if error.kind() != std::io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
+}
+
+/// A memory-mapped buffer shared with the driver.
+struct Mapping {
+    address: *mut libc::c_void,
+    length: usize,
+}
+
+/// A V4L2 capture device, streaming `YUYV` or `GREY` frames through memory-mapped buffers.
+pub struct V4l2Camera {
+    file: File,
+    buffers: Vec<Mapping>,
+    pixelformat: u32,
+}
+
+// SAFETY: The mappings are only accessed through `&mut self`.
+unsafe impl Send for V4l2Camera {}
+
+impl V4l2Camera {
+    /// Attempt to open `/dev/video0`.
+    pub fn open() -> Result<Self, CameraNoiseError> {
+        Self::open_path(DEFAULT_CAMERA_PATH)
+    }
+
+    /// Attempt to open the V4L2 capture device at `path` and start streaming at its current resolution.
+    pub fn open_path(path: impl AsRef<Path>) -> Result<Self, CameraNoiseError> {
+        let file = match OpenOptions::new().read(true).write(true).open(path) {
+            Ok(file) => file,
+            Err(e) => return Err(CameraNoiseError::Io(e)),
+        };
+
+        // SAFETY: `struct v4l2_capability` is plain data for which all zeroes is a valid value.
+        let mut capability: V4l2Capability = unsafe { core::mem::zeroed() };
+
+        if let Err(e) = ioctl(&file, VIDIOC_QUERYCAP, &mut capability) {
+            return Err(CameraNoiseError::Io(e));
+        }
+
+        if capability.capabilities & (V4L2_CAP_VIDEO_CAPTURE | V4L2_CAP_STREAMING)
+            != V4L2_CAP_VIDEO_CAPTURE | V4L2_CAP_STREAMING
+        {
+            return Err(CameraNoiseError::NotCapture);
+        }
+
+        let pixelformat = Self::set_format(&file)?;
+
+        let mut camera = Self {
+            file,
+            buffers: Vec::new(),
+            pixelformat,
+        };
+
+        if let Err(e) = camera.start() {
+            return Err(CameraNoiseError::Io(e));
+        }
+
+        Ok(camera)
+    }
+
+    /// Keep the current resolution but ask for `YUYV` (or `GREY`) frames.
+    fn set_format(file: &File) -> Result<u32, CameraNoiseError> {
+        let mut format = V4l2Format {
+            kind: V4L2_BUF_TYPE_VIDEO_CAPTURE,
+            fmt: V4l2FormatUnion { raw: [0; 200] },
+        };
+
+        if let Err(e) = ioctl(file, VIDIOC_G_FMT, &mut format) {
+            return Err(CameraNoiseError::Io(e));
+        }
+
+        for pixelformat in [V4L2_PIX_FMT_YUYV, V4L2_PIX_FMT_GREY] {
+            // SAFETY: `G_FMT` on a capture buffer type fills in the `pix` member.
+            let mut pix = unsafe { format.fmt.pix };
+
+            pix.pixelformat = pixelformat;
+
+            format.fmt = V4l2FormatUnion { pix };
+
+            if let Err(e) = ioctl(file, VIDIOC_S_FMT, &mut format) {
+                return Err(CameraNoiseError::Io(e));
+            }
+
+            // SAFETY: As above, the driver returns the format it settled on in `pix`.
+            let chosen = unsafe { format.fmt.pix.pixelformat };
+
+            if chosen == V4L2_PIX_FMT_YUYV || chosen == V4L2_PIX_FMT_GREY {
+                return Ok(chosen);
+            }
+        }
+
+        // SAFETY: As above.
+        Err(CameraNoiseError::UnsupportedFormat(unsafe { format.fmt.pix.pixelformat }))
+    }
+
+    fn start(&mut self) -> std::io::Result<()> {
+        let mut request = V4l2RequestBuffers {
+            count: BUFFER_COUNT,
+            kind: V4L2_BUF_TYPE_VIDEO_CAPTURE,
+            memory: V4L2_MEMORY_MMAP,
+            capabilities: 0,
+            flags: 0,
+            reserved: [0; 3],
+        };
+
+        ioctl(&self.file, VIDIOC_REQBUFS, &mut request)?;
+
+        for index in 0..request.count {
+            let mut buffer = V4l2Buffer::new(index);
+
+            ioctl(&self.file, VIDIOC_QUERYBUF, &mut buffer)?;
+
+            // SAFETY: Maps the driver buffer at the offset and length it reported for `index`.
+            let address = unsafe {
+                libc::mmap(
+                    core::ptr::null_mut(),
+                    buffer.length as usize,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_SHARED,
+                    self.file.as_raw_fd(),
+                    buffer.offset(),
+                )
+            };
+
+            if address == libc::MAP_FAILED {
+                return Err(std::io::Error::last_os_error());
+            }
+
+            self.buffers.push(Mapping {
+                address,
+                length: buffer.length as usize,
+            });
+
+            ioctl(&self.file, VIDIOC_QBUF, &mut buffer)?;
+        }
+
+        let mut kind = V4L2_BUF_TYPE_VIDEO_CAPTURE as libc::c_int;
+
+        ioctl(&self.file, VIDIOC_STREAMON, &mut kind)
+    }
+}
+
+impl FrameSource for V4l2Camera {
+    fn capture(&mut self, luma: &mut Vec<u8>) -> std::io::Result<()> {
+        let mut buffer = V4l2Buffer::new(0);
+
+        ioctl(&self.file, VIDIOC_DQBUF, &mut buffer)?;
+
+        let mapping = &self.buffers[buffer.index as usize];
+
+        // SAFETY: The driver has handed the dequeued buffer (of `bytesused` bytes) back to us until it is queued again.
+        let frame = unsafe {
+            core::slice::from_raw_parts(
+                mapping.address.cast::<u8>(),
+                (buffer.bytesused as usize).min(mapping.length),
+            )
+        };
+
+        luma.clear();
+
+        match self.pixelformat {
+            V4L2_PIX_FMT_YUYV => luma.extend(frame.iter().step_by(2)),
+            _ => luma.extend_from_slice(frame),
+        }
+
+        ioctl(&self.file, VIDIOC_QBUF, &mut buffer)
+    }
+}
+
+impl Drop for V4l2Camera {
+    fn drop(&mut self) {
+        let mut kind = V4L2_BUF_TYPE_VIDEO_CAPTURE as libc::c_int;
+
+        let _ = ioctl(&self.file, VIDIOC_STREAMOFF, &mut kind);
+
+        for mapping in &self.buffers {
+            // SAFETY: Each mapping was created by `mmap` with this length and is no longer referenced.
+            unsafe { libc::munmap(mapping.address, mapping.length) };
+        }
+    }
+}
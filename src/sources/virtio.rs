@@ -0,0 +1,162 @@
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Read};
+use std::os::unix::fs::OpenOptionsExt;
+use std::string::String;
+use std::time::{Duration, Instant};
+
+use super::hwrng::{read_exact, HwRngError, DEFAULT_HWRNG_PATH};
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{self, io_kind, Classify};
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+/// Path of the sysfs attribute naming the hardware RNG currently behind `/dev/hwrng`.
+const RNG_CURRENT_PATH: &str = "/sys/class/misc/hw_random/rng_current";
+
+/// Prefix of the names the kernel gives virtio-rng devices (`virtio_rng.0`, ...).
+const VIRTIO_RNG_PREFIX: &str = "virtio_rng";
+
+/// Default time the host is given to deliver the first bytes when probing.
+const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Time between non-blocking probe reads.
+const PROBE_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug)]
+pub enum VirtioRngError {
+    /// Detecting or opening the device failed.
+    Io(std::io::Error),
+    /// The current hardware RNG is not a virtio-rng device (contains its name, `none` if there is none).
+    NotVirtio(String),
+    /// The device did not deliver any bytes within the probe timeout (e.g. no entropy backend on the host).
+    Unbacked,
+    /// Reading from the device failed.
+    Device(HwRngError),
+}
+
+impl std::fmt::Display for VirtioRngError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for VirtioRngError {}
+
+impl Classify for VirtioRngError {
+    fn kind(&self) -> error::ErrorKind {
+        match self {
+            Self::Io(e) => io_kind(e),
+            Self::NotVirtio(_) | Self::Unbacked => error::ErrorKind::Unsupported,
+            Self::Device(e) => e.kind(),
+        }
+    }
+}
+
+/// Entropy provided by the host through a virtio-rng device, read from `/dev/hwrng` in a Linux guest.
+///
+/// Opening checks that the kernel's current hardware RNG is a virtio-rng device, then probes it with non-blocking
+/// reads, since a device whose host backend is missing or stalled makes blocking reads hang.
+///
+/// NOTE: The host can observe and choose everything this source returns. Mix it with guest-internal sources (e.g. in a
+/// `Combiner`) rather than relying on it alone.
+pub struct VirtioRng {
+    file: File,
+}
+
+impl VirtioRng {
+    /// Attempt to open the virtio-rng device, probing it with the default timeout.
+    pub fn open() -> Result<Self, VirtioRngError> {
+        Self::open_with_probe_timeout(DEFAULT_PROBE_TIMEOUT)
+    }
+
+    /// Attempt to open the virtio-rng device, giving the host `timeout` to deliver the first bytes.
+    pub fn open_with_probe_timeout(timeout: Duration) -> Result<Self, VirtioRngError> {
+        let current = Self::current()?;
+
+        if !current.starts_with(VIRTIO_RNG_PREFIX) {
+            return Err(VirtioRngError::NotVirtio(current));
+        }
+
+        Self::probe(timeout)?;
+
+        match File::open(DEFAULT_HWRNG_PATH) {
+            Ok(file) => Ok(Self { file }),
+            Err(e) => Err(VirtioRngError::Io(e)),
+        }
+    }
+
+    /// Attempt to get the name of the hardware RNG currently behind `/dev/hwrng`.
+    pub fn current() -> Result<String, VirtioRngError> {
+        match std::fs::read_to_string(RNG_CURRENT_PATH) {
+            Ok(name) => Ok(String::from(name.trim())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(String::from("none")),
+            Err(e) => Err(VirtioRngError::Io(e)),
+        }
+    }
+
+    /// Check that the device delivers bytes without blocking indefinitely.
+    fn probe(timeout: Duration) -> Result<(), VirtioRngError> {
+        let mut file = match OpenOptions::new().read(true).custom_flags(libc::O_NONBLOCK).open(DEFAULT_HWRNG_PATH) {
+            Ok(file) => file,
+            Err(e) => return Err(VirtioRngError::Io(e)),
+        };
+
+        let deadline = Instant::now() + timeout;
+        let mut probe = [0u8; 16];
+
+        loop {
+            let result = file.read(&mut probe);
+
+            probe.fill(0);
+
+            match result {
+                Ok(n) if n > 0 => return Ok(()),
+                Ok(_) => {}
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::Interrupted) => {}
+                Err(e) => return Err(VirtioRngError::Device(HwRngError::Io(e))),
+            }
+
+            if Instant::now() >= deadline {
+                return Err(VirtioRngError::Unbacked);
+            }
+
+            std::thread::sleep(PROBE_INTERVAL);
+        }
+    }
+}
+
+unsafe impl EntropySource for VirtioRng {
+    type EntropySourceError = VirtioRngError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        match read_exact(&self.file, buffer) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(VirtioRngError::Device(e)),
+        }
+    }
+}
+
+/// NOTE: The device is normally backed by the host's own generator (e.g. its `/dev/urandom`), so it is credited as a
+/// generator rather than as raw hardware.
+impl EntropyAssessment for VirtioRng {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Hardware,
+            basis: Basis::Generator,
+        }
+    }
+}
+
+impl SelfTest for VirtioRng {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
@@ -0,0 +1,115 @@
+use core::error::Error;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{Classify, ErrorKind};
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+#[cfg(not(target_env = "p2"))]
+#[link(wasm_import_module = "wasi_snapshot_preview1")]
+extern "C" {
+    fn random_get(buffer: *mut u8, length: usize) -> u16;
+}
+
+#[cfg(target_env = "p2")]
+#[link(wasm_import_module = "wasi:random/random@0.2.0")]
+extern "C" {
+    #[link_name = "get-random-u64"]
+    fn get_random_u64() -> u64;
+}
+
+/// WASI `errno` values reported by `random_get`.
+#[cfg(not(target_env = "p2"))]
+mod errno {
+    pub(super) const FAULT: u16 = 21;
+    pub(super) const INTR: u16 = 27;
+    pub(super) const INVAL: u16 = 28;
+    pub(super) const NOSYS: u16 = 52;
+    pub(super) const NOTSUP: u16 = 58;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WasiError {
+    /// The host does not implement `random_get` (`ENOSYS`/`ENOTSUP`).
+    Unsupported,
+    /// The host rejected the buffer (`EFAULT`/`EINVAL`).
+    InvalidBuffer,
+    /// Any other WASI `errno`.
+    Errno(u16),
+}
+
+impl core::fmt::Display for WasiError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for WasiError {}
+
+impl Classify for WasiError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Unsupported => ErrorKind::Unsupported,
+            Self::InvalidBuffer => ErrorKind::Backend(0),
+            Self::Errno(errno) => ErrorKind::Backend(*errno as i32),
+        }
+    }
+}
+
+/// Entropy from the WASI host (`random_get` on preview 1, `wasi:random/random` on preview 2).
+pub struct WasiRandom;
+
+unsafe impl EntropySource for WasiRandom {
+    type EntropySourceError = WasiError;
+
+    #[cfg(not(target_env = "p2"))]
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        loop {
+            // SAFETY: The pointer and length describe `buffer`.
+            return match unsafe { random_get(buffer.as_mut_ptr(), buffer.len()) } {
+                0 => Ok(()),
+                errno::INTR => continue,
+                errno::NOSYS | errno::NOTSUP => Err(WasiError::Unsupported),
+                errno::FAULT | errno::INVAL => Err(WasiError::InvalidBuffer),
+                errno => Err(WasiError::Errno(errno)),
+            };
+        }
+    }
+
+    /// NOTE: `wasi:random/random` is infallible.
+    #[cfg(target_env = "p2")]
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        for chunk in buffer.chunks_mut(8) {
+            // SAFETY: `get-random-u64` takes no arguments and returns a plain `u64`.
+            let word = unsafe { get_random_u64() };
+
+            chunk.copy_from_slice(&word.to_le_bytes()[..chunk.len()]);
+        }
+
+        Ok(())
+    }
+}
+
+impl EntropyAssessment for WasiRandom {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Os,
+            basis: Basis::Generator,
+        }
+    }
+}
+
+impl SelfTest for WasiRandom {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
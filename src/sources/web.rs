@@ -0,0 +1,115 @@
+use core::error::Error;
+
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{Classify, ErrorKind};
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+/// Maximum length of a single `getRandomValues` call.
+const MAX_REQUEST: usize = 65536;
+
+#[wasm_bindgen]
+extern "C" {
+    type Global;
+
+    #[wasm_bindgen(method, getter)]
+    fn crypto(this: &Global) -> JsValue;
+
+    type Crypto;
+
+    #[wasm_bindgen(method, js_name = getRandomValues, catch)]
+    fn get_random_values(this: &Crypto, array: &Uint8Array) -> Result<JsValue, JsValue>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebCryptoError {
+    /// The global `crypto` object (Web Crypto API) is not available.
+    Unavailable,
+    /// `crypto.getRandomValues` threw an exception.
+    GetRandomValues,
+}
+
+impl core::fmt::Display for WebCryptoError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for WebCryptoError {}
+
+impl Classify for WebCryptoError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Unavailable => ErrorKind::Unsupported,
+            Self::GetRandomValues => ErrorKind::Backend(0),
+        }
+    }
+}
+
+/// Entropy from the Web Crypto API (`crypto.getRandomValues`) in browsers and other JavaScript hosts.
+///
+/// NOTE: Requests are split into chunks of 65536 bytes, the limit of a single `getRandomValues` call.
+pub struct WebCrypto {
+    crypto: Crypto,
+}
+
+impl WebCrypto {
+    /// Attempt to look up the global `crypto` object.
+    pub fn new() -> Result<Self, WebCryptoError> {
+        let crypto = js_sys::global().unchecked_into::<Global>().crypto();
+
+        match crypto.is_object() {
+            true => Ok(Self {
+                crypto: crypto.unchecked_into(),
+            }),
+            false => Err(WebCryptoError::Unavailable),
+        }
+    }
+}
+
+unsafe impl EntropySource for WebCrypto {
+    type EntropySourceError = WebCryptoError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        // NOTE: Generated into a JavaScript-owned array and copied out, since wasm memory may be a `SharedArrayBuffer`.
+        let array = Uint8Array::new_with_length(buffer.len().min(MAX_REQUEST) as u32);
+
+        for chunk in buffer.chunks_mut(MAX_REQUEST) {
+            let view = array.subarray(0, chunk.len() as u32);
+
+            if self.crypto.get_random_values(&view).is_err() {
+                return Err(WebCryptoError::GetRandomValues);
+            }
+
+            view.copy_to(chunk);
+        }
+
+        Ok(())
+    }
+}
+
+impl EntropyAssessment for WebCrypto {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Os,
+            basis: Basis::Generator,
+        }
+    }
+}
+
+impl SelfTest for WebCrypto {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
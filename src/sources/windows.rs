@@ -0,0 +1,140 @@
+use std::error::Error;
+
+use windows_sys::Win32::Foundation::{STATUS_INVALID_HANDLE, STATUS_INVALID_PARAMETER, STATUS_NOT_SUPPORTED};
+use windows_sys::Win32::Security::Cryptography::{
+    BCryptGenRandom, BCRYPT_ALG_HANDLE, BCRYPT_USE_SYSTEM_PREFERRED_RNG,
+};
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{Classify, ErrorKind};
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BcryptError {
+    /// `STATUS_INVALID_HANDLE`: the algorithm handle is not valid.
+    InvalidHandle,
+    /// `STATUS_INVALID_PARAMETER`: one or more parameters are not valid.
+    InvalidParameter,
+    /// `STATUS_NOT_SUPPORTED`: the algorithm does not support random number generation.
+    NotSupported,
+    /// Any other failing `NTSTATUS` code.
+    Other(i32),
+}
+
+impl BcryptError {
+    fn from_status(status: i32) -> Self {
+        match status {
+            STATUS_INVALID_HANDLE => Self::InvalidHandle,
+            STATUS_INVALID_PARAMETER => Self::InvalidParameter,
+            STATUS_NOT_SUPPORTED => Self::NotSupported,
+            status => Self::Other(status),
+        }
+    }
+
+    /// Get the raw `NTSTATUS` code.
+    pub fn status(&self) -> i32 {
+        match self {
+            Self::InvalidHandle => STATUS_INVALID_HANDLE,
+            Self::InvalidParameter => STATUS_INVALID_PARAMETER,
+            Self::NotSupported => STATUS_NOT_SUPPORTED,
+            Self::Other(status) => *status,
+        }
+    }
+}
+
+impl std::fmt::Display for BcryptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} (NTSTATUS {:#010x})", self, self.status())
+    }
+}
+
+impl Error for BcryptError {}
+
+impl Classify for BcryptError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::NotSupported => ErrorKind::Unsupported,
+            _ => ErrorKind::Backend(self.status()),
+        }
+    }
+}
+
+/// Entropy from `BCryptGenRandom`, using the system-preferred RNG or a caller-supplied algorithm handle.
+pub struct WindowsBcrypt {
+    algorithm: BCRYPT_ALG_HANDLE,
+}
+
+// SAFETY: CNG algorithm handles may be used concurrently from multiple threads.
+unsafe impl Send for WindowsBcrypt {}
+unsafe impl Sync for WindowsBcrypt {}
+
+impl WindowsBcrypt {
+    /// Use the system-preferred RNG (`BCRYPT_USE_SYSTEM_PREFERRED_RNG`).
+    pub fn new() -> Self {
+        Self {
+            algorithm: core::ptr::null_mut(),
+        }
+    }
+
+    /// Use a caller-supplied algorithm handle (e.g. from `BCryptOpenAlgorithmProvider` with `BCRYPT_RNG_ALGORITHM`).
+    ///
+    /// # Safety
+    ///
+    /// `algorithm` must be a valid random number generator algorithm handle for as long as the source is used, and
+    /// remains owned (and closed) by the caller.
+    pub unsafe fn with_algorithm(algorithm: BCRYPT_ALG_HANDLE) -> Self {
+        Self { algorithm }
+    }
+}
+
+impl Default for WindowsBcrypt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl EntropySource for WindowsBcrypt {
+    type EntropySourceError = BcryptError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        let flags = match self.algorithm.is_null() {
+            true => BCRYPT_USE_SYSTEM_PREFERRED_RNG,
+            false => 0,
+        };
+
+        for chunk in buffer.chunks_mut(u32::MAX as usize) {
+            // SAFETY: The pointer and length describe `chunk`, and the handle is either null (with the system-preferred
+            // flag) or valid per `with_algorithm`.
+            let status = unsafe { BCryptGenRandom(self.algorithm, chunk.as_mut_ptr(), chunk.len() as u32, flags) };
+
+            if status < 0 {
+                return Err(BcryptError::from_status(status));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl EntropyAssessment for WindowsBcrypt {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Os,
+            basis: Basis::Generator,
+        }
+    }
+}
+
+impl SelfTest for WindowsBcrypt {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
@@ -0,0 +1,235 @@
+use core::error::Error;
+
+use crate::assess::{Basis, EntropyAssessment, Provenance, SourceClass};
+use crate::error::{Classify, ErrorKind};
+use crate::selftest::{startup_test, SelfTest, SelfTestError, SelfTestReport};
+use crate::trace;
+use crate::EntropySource;
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86 as arch;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64 as arch;
+
+/// Number of attempts per word for RDRAND, as recommended by Intel.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+const RDRAND_RETRIES: usize = 10;
+
+/// Number of attempts per word for RDSEED, which may legitimately underflow under load.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+const RDSEED_RETRIES: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum X86RngError {
+    /// The CPU (or target architecture) does not support the instruction.
+    Unsupported,
+    /// The instruction kept failing within the retry limit (the hardware entropy is exhausted).
+    Exhausted,
+    /// The instruction returned a known-broken output (e.g. all ones, as on some AMD parts after resume).
+    Faulty,
+}
+
+impl core::fmt::Display for X86RngError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for X86RngError {}
+
+impl Classify for X86RngError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Unsupported => ErrorKind::Unsupported,
+            Self::Exhausted => ErrorKind::Exhausted,
+            Self::Faulty => ErrorKind::HealthCheckFailed,
+        }
+    }
+}
+
+// NOTE: `__cpuid` only became a safe function in newer toolchains, so the `unsafe` blocks are kept for older ones.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[allow(unused_unsafe)]
+fn has_rdrand() -> bool {
+    // SAFETY: `cpuid` is available on every CPU capable of running Rust's x86 targets.
+    let info = unsafe { arch::__cpuid(1) };
+
+    info.ecx & (1 << 30) != 0
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[allow(unused_unsafe)]
+fn has_rdseed() -> bool {
+    // SAFETY: `cpuid` is available on every CPU capable of running Rust's x86 targets.
+    let max_leaf = unsafe { arch::__cpuid(0) }.eax;
+
+    // SAFETY: As above.
+    max_leaf >= 7 && unsafe { arch::__cpuid_count(7, 0) }.ebx & (1 << 18) != 0
+}
+
+#[cfg(target_arch = "x86_64")]
+type Word = u64;
+#[cfg(target_arch = "x86")]
+type Word = u32;
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "rdrand")]
+unsafe fn rdrand_step(word: &mut Word) -> bool {
+    arch::_rdrand64_step(word) == 1
+}
+
+#[cfg(target_arch = "x86")]
+#[target_feature(enable = "rdrand")]
+unsafe fn rdrand_step(word: &mut Word) -> bool {
+    arch::_rdrand32_step(word) == 1
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "rdseed")]
+unsafe fn rdseed_step(word: &mut Word) -> bool {
+    arch::_rdseed64_step(word) == 1
+}
+
+#[cfg(target_arch = "x86")]
+#[target_feature(enable = "rdseed")]
+unsafe fn rdseed_step(word: &mut Word) -> bool {
+    arch::_rdseed32_step(word) == 1
+}
+
+/// Fill `buffer` word by word, retrying each word up to `retries` times.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn fill(buffer: &mut [u8], retries: usize, step: unsafe fn(&mut Word) -> bool) -> Result<(), X86RngError> {
+    for chunk in buffer.chunks_mut(core::mem::size_of::<Word>()) {
+        let mut word = 0;
+        let mut attempts = 0;
+
+        // SAFETY: `step` is only called once support for its instruction has been detected.
+        while !unsafe { step(&mut word) } {
+            attempts += 1;
+
+            if attempts >= retries {
+                return Err(X86RngError::Exhausted);
+            }
+
+            core::hint::spin_loop();
+        }
+
+        if word == Word::MAX {
+            return Err(X86RngError::Faulty);
+        }
+
+        chunk.copy_from_slice(&word.to_ne_bytes()[..chunk.len()]);
+    }
+
+    Ok(())
+}
+
+/// Entropy from the x86 `RDRAND` instruction (output of the on-chip DRBG).
+///
+/// NOTE: Support is detected at runtime via `cpuid`; on other architectures `new` always fails.
+pub struct RdRand {
+    _private: (),
+}
+
+impl RdRand {
+    /// Attempt to create the source, failing if the CPU does not support `RDRAND`.
+    pub fn new() -> Result<Self, X86RngError> {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if has_rdrand() {
+            return Ok(Self { _private: () });
+        }
+
+        Err(X86RngError::Unsupported)
+    }
+}
+
+unsafe impl EntropySource for RdRand {
+    type EntropySourceError = X86RngError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        return fill(buffer, RDRAND_RETRIES, rdrand_step);
+
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        {
+            let _ = buffer;
+            Err(X86RngError::Unsupported)
+        }
+    }
+}
+
+impl EntropyAssessment for RdRand {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Hardware,
+            basis: Basis::Generator,
+        }
+    }
+}
+
+/// Entropy from the x86 `RDSEED` instruction (conditioned output of the on-chip noise source).
+///
+/// NOTE: Support is detected at runtime via `cpuid`; on other architectures `new` always fails.
+pub struct RdSeed {
+    _private: (),
+}
+
+impl RdSeed {
+    /// Attempt to create the source, failing if the CPU does not support `RDSEED`.
+    pub fn new() -> Result<Self, X86RngError> {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if has_rdseed() {
+            return Ok(Self { _private: () });
+        }
+
+        Err(X86RngError::Unsupported)
+    }
+}
+
+unsafe impl EntropySource for RdSeed {
+    type EntropySourceError = X86RngError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        return fill(buffer, RDSEED_RETRIES, rdseed_step);
+
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        {
+            let _ = buffer;
+            Err(X86RngError::Unsupported)
+        }
+    }
+}
+
+impl EntropyAssessment for RdSeed {
+    fn min_entropy_per_byte(&self) -> f64 {
+        8.0
+    }
+
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            class: SourceClass::Hardware,
+            basis: Basis::Specified,
+        }
+    }
+}
+
+impl SelfTest for RdRand {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
+
+impl SelfTest for RdSeed {
+    fn self_test(&self) -> Result<SelfTestReport, SelfTestError> {
+        startup_test(self)
+    }
+}
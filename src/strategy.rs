@@ -0,0 +1,40 @@
+//! `proptest` strategies for entropy values.
+//!
+//! NOTE: Failing cases are reported through the redacting `Debug` impls; print `debug_reveal` in assertion messages
+//! to see their bytes.
+
+use proptest::arbitrary::Arbitrary;
+use proptest::collection::{vec, SizeRange};
+use proptest::num::u8;
+use proptest::strategy::{BoxedStrategy, Strategy};
+
+use crate::{Entropy, EntropyVec};
+
+/// Generate `Entropy<LENGTH>` values with arbitrary bytes.
+pub fn entropy<const LENGTH: usize>() -> impl Strategy<Value = Entropy<LENGTH>> {
+    proptest::array::uniform::<_, LENGTH>(u8::ANY).prop_map(|bytes| Entropy { bytes })
+}
+
+/// Generate `EntropyVec` values with arbitrary bytes and a length in the given range.
+pub fn entropy_vec(length: impl Into<SizeRange>) -> impl Strategy<Value = EntropyVec> {
+    vec(u8::ANY, length).prop_map(|bytes| EntropyVec { bytes })
+}
+
+impl<const LENGTH: usize> Arbitrary for Entropy<LENGTH> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        entropy().boxed()
+    }
+}
+
+/// NOTE: Lengths default to `0..100`, like `Vec<u8>`; pass a `SizeRange` to `any_with` to change them.
+impl Arbitrary for EntropyVec {
+    type Parameters = SizeRange;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(length: Self::Parameters) -> Self::Strategy {
+        entropy_vec(length).boxed()
+    }
+}
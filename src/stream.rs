@@ -0,0 +1,87 @@
+//! Streams of entropy chunks for async pipelines (e.g. padding generators or cover-traffic emitters).
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use std::boxed::Box;
+use std::sync::Arc;
+
+use bytes::{Bytes, BytesMut};
+use futures_core::Stream;
+
+use crate::asynchronous::AsyncEntropySource;
+
+/// A read of one chunk in progress.
+type PendingChunk<E> = Pin<Box<dyn Future<Output = Result<Bytes, E>> + Send>>;
+
+/// An endless stream of fixed-size chunks read from an `AsyncEntropySource`, created by `entropy_chunks`.
+pub struct EntropyChunks<S: AsyncEntropySource> {
+    source: Arc<S>,
+    chunk_size: usize,
+    pending: Option<PendingChunk<S::EntropySourceError>>,
+}
+
+/// Stream chunks of `chunk_size` bytes (minimum 1) from `source`, reading one chunk at a time as they are polled.
+///
+/// NOTE: The stream never ends; a failed read yields its error and the next poll reads again. Blocking sources can be
+/// streamed through `Blocking`. `Bytes` cannot be wiped, so this is meant for output which is sent on rather than
+/// kept secret (e.g. padding), not for key material.
+pub fn entropy_chunks<S>(source: S, chunk_size: usize) -> EntropyChunks<S>
+where
+    S: AsyncEntropySource + Send + Sync + 'static,
+    S::EntropySourceError: Send + 'static,
+{
+    EntropyChunks {
+        source: Arc::new(source),
+        chunk_size: chunk_size.max(1),
+        pending: None,
+    }
+}
+
+impl<S: AsyncEntropySource> EntropyChunks<S> {
+    /// Get the size of every chunk.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+}
+
+impl<S> Stream for EntropyChunks<S>
+where
+    S: AsyncEntropySource + Send + Sync + 'static,
+    S::EntropySourceError: Send + 'static,
+{
+    type Item = Result<Bytes, S::EntropySourceError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        let pending = this.pending.get_or_insert_with(|| {
+            let source = this.source.clone();
+            let mut chunk = BytesMut::zeroed(this.chunk_size);
+
+            Box::pin(async move {
+                source.read_bytes(&mut chunk).await?;
+
+                Ok(chunk.freeze())
+            })
+        });
+
+        match pending.as_mut().poll(cx) {
+            Poll::Ready(result) => {
+                this.pending = None;
+
+                Poll::Ready(Some(result))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
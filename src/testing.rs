@@ -0,0 +1,349 @@
+//! Predictable sources for testing code that is generic over `EntropySource`.
+//!
+//! NOTE: None of these sources are secure. They implement `EntropySource` only so they can stand in for real ones in
+//! tests; never use them to produce key material.
+
+use core::convert::Infallible;
+use core::error::Error;
+
+use crate::error::{Classify, ErrorKind};
+use crate::lock::Lock;
+use crate::{EntropySource, EntropySourceMut};
+
+#[derive(Debug, Clone, Copy)]
+enum Pattern {
+    Counter,
+    Repeat(&'static [u8]),
+}
+
+impl Pattern {
+    /// Fill `buffer` with the stream starting at `position`, advancing it.
+    fn produce(self, position: &mut usize, buffer: &mut [u8]) {
+        for byte in buffer.iter_mut() {
+            *byte = match self {
+                Pattern::Counter => *position as u8,
+                Pattern::Repeat([]) => 0,
+                Pattern::Repeat(pattern) => pattern[*position % pattern.len()],
+            };
+
+            *position = position.wrapping_add(1);
+        }
+    }
+}
+
+/// Produces the same byte stream on every run.
+pub struct DeterministicSource {
+    pattern: Pattern,
+    position: Lock<usize>,
+}
+
+impl DeterministicSource {
+    /// Produce the bytes `0, 1, 2, ..`, wrapping after `255`.
+    pub const fn counter() -> Self {
+        Self {
+            pattern: Pattern::Counter,
+            position: Lock::new(0),
+        }
+    }
+
+    /// Produce the given pattern over and over.
+    ///
+    /// NOTE: An empty pattern produces zeroes.
+    pub const fn repeat(pattern: &'static [u8]) -> Self {
+        Self {
+            pattern: Pattern::Repeat(pattern),
+            position: Lock::new(0),
+        }
+    }
+
+    /// Get the number of bytes produced so far.
+    pub fn position(&self) -> usize {
+        self.position.with(|position| *position)
+    }
+
+    /// Start over from the beginning of the stream.
+    pub fn reset(&self) {
+        self.position.with(|position| *position = 0);
+    }
+}
+
+unsafe impl EntropySource for DeterministicSource {
+    type EntropySourceError = Infallible;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        self.position.with(|position| self.pattern.produce(position, buffer));
+
+        Ok(())
+    }
+}
+
+unsafe impl EntropySourceMut for DeterministicSource {
+    type EntropySourceError = Infallible;
+
+    fn read_bytes_mut(&mut self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        self.pattern.produce(self.position.get_mut(), buffer);
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum FailingSourceError<E: Error> {
+    /// The wrapped source failed.
+    Source(E),
+    /// The read would have gone past the configured limit.
+    Exhausted,
+}
+
+impl<E: Error> core::fmt::Display for FailingSourceError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<E: Error> Error for FailingSourceError<E> {}
+
+impl<E: Error + Classify> Classify for FailingSourceError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Source(e) => e.kind(),
+            Self::Exhausted => ErrorKind::Exhausted,
+        }
+    }
+}
+
+/// Serves a limited number of bytes from the wrapped source, then fails every read.
+///
+/// NOTE: A read that would cross the limit fails as a whole, without serving the remaining bytes.
+pub struct FailingSource<S: EntropySource = DeterministicSource> {
+    source: S,
+    limit: usize,
+    served: Lock<usize>,
+}
+
+impl FailingSource {
+    /// Serve `limit` counter bytes before failing.
+    pub const fn after(limit: usize) -> Self {
+        Self::new(DeterministicSource::counter(), limit)
+    }
+}
+
+impl<S: EntropySource> FailingSource<S> {
+    /// Serve `limit` bytes from the provided source before failing.
+    pub const fn new(source: S, limit: usize) -> Self {
+        Self {
+            source,
+            limit,
+            served: Lock::new(0),
+        }
+    }
+
+    /// Get the number of bytes served so far.
+    pub fn served(&self) -> usize {
+        self.served.with(|served| *served)
+    }
+
+    /// Allow another `limit` bytes to be served.
+    pub fn reset(&self) {
+        self.served.with(|served| *served = 0);
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySource for FailingSource<S> {
+    type EntropySourceError = FailingSourceError<S::EntropySourceError>;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        self.served.with(|served| {
+            if self.limit - *served < buffer.len() {
+                return Err(FailingSourceError::Exhausted);
+            }
+
+            if let Err(e) = self.source.read_bytes(buffer) {
+                return Err(FailingSourceError::Source(e));
+            }
+
+            *served += buffer.len();
+
+            Ok(())
+        })
+    }
+}
+
+/// Sleeps before every read from the wrapped source, to exercise timeouts and slow paths.
+#[cfg(feature = "std")]
+pub struct SlowSource<S: EntropySource = DeterministicSource> {
+    source: S,
+    delay: std::time::Duration,
+}
+
+#[cfg(feature = "std")]
+impl<S: EntropySource> SlowSource<S> {
+    /// Sleep for `delay` before every read from the provided source.
+    pub const fn new(source: S, delay: std::time::Duration) -> Self {
+        Self { source, delay }
+    }
+
+    /// Get the delay before every read.
+    pub fn delay(&self) -> std::time::Duration {
+        self.delay
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+}
+
+#[cfg(feature = "std")]
+unsafe impl<S: EntropySource> EntropySource for SlowSource<S> {
+    type EntropySourceError = S::EntropySourceError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        std::thread::sleep(self.delay);
+
+        self.source.read_bytes(buffer)
+    }
+}
+
+/// Records every byte served by the wrapped source onto a tape, for replay with `Replayer`.
+///
+/// NOTE: Only successful reads are recorded.
+#[cfg(feature = "alloc")]
+pub struct Recorder<S: EntropySource> {
+    source: S,
+    tape: Lock<alloc::vec::Vec<u8>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<S: EntropySource> Recorder<S> {
+    /// Record the bytes served by the provided source.
+    pub const fn new(source: S) -> Self {
+        Self {
+            source,
+            tape: Lock::new(alloc::vec::Vec::new()),
+        }
+    }
+
+    /// Get a copy of the tape recorded so far.
+    pub fn tape(&self) -> alloc::vec::Vec<u8> {
+        self.tape.with(|tape| tape.clone())
+    }
+
+    /// Take the tape recorded so far, starting a new one.
+    pub fn take_tape(&self) -> alloc::vec::Vec<u8> {
+        self.tape.with(core::mem::take)
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Unwrap the source and the tape.
+    pub fn into_parts(self) -> (S, alloc::vec::Vec<u8>) {
+        (self.source, self.tape.into_inner())
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<S: EntropySource> EntropySource for Recorder<S> {
+    type EntropySourceError = S::EntropySourceError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        self.source.read_bytes(buffer)?;
+
+        self.tape.with(|tape| tape.extend_from_slice(buffer));
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub enum ReplayerError {
+    /// The read would have gone past the end of the tape.
+    EndOfTape,
+}
+
+#[cfg(feature = "alloc")]
+impl core::fmt::Display for ReplayerError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Error for ReplayerError {}
+
+#[cfg(feature = "alloc")]
+impl Classify for ReplayerError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Exhausted
+    }
+}
+
+/// Replays a tape recorded by `Recorder`, byte for byte.
+///
+/// NOTE: A read that would go past the end of the tape fails as a whole, without consuming the remaining bytes.
+#[cfg(feature = "alloc")]
+pub struct Replayer {
+    tape: alloc::vec::Vec<u8>,
+    position: Lock<usize>,
+}
+
+#[cfg(feature = "alloc")]
+impl Replayer {
+    /// Replay the provided tape.
+    pub fn new(tape: impl Into<alloc::vec::Vec<u8>>) -> Self {
+        Self {
+            tape: tape.into(),
+            position: Lock::new(0),
+        }
+    }
+
+    /// Get the number of bytes left on the tape.
+    pub fn remaining(&self) -> usize {
+        self.tape.len() - self.position.with(|position| *position)
+    }
+
+    /// Rewind to the start of the tape.
+    pub fn rewind(&self) {
+        self.position.with(|position| *position = 0);
+    }
+
+    fn replay(tape: &[u8], position: &mut usize, buffer: &mut [u8]) -> Result<(), ReplayerError> {
+        match tape.get(*position..*position + buffer.len()) {
+            Some(bytes) => {
+                buffer.copy_from_slice(bytes);
+
+                *position += buffer.len();
+
+                Ok(())
+            }
+            None => Err(ReplayerError::EndOfTape),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl EntropySource for Replayer {
+    type EntropySourceError = ReplayerError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        self.position.with(|position| Self::replay(&self.tape, position, buffer))
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl EntropySourceMut for Replayer {
+    type EntropySourceError = ReplayerError;
+
+    fn read_bytes_mut(&mut self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        Self::replay(&self.tape, self.position.get_mut(), buffer)
+    }
+}
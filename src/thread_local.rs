@@ -0,0 +1,166 @@
+//! Per-thread entropy buffers over a shared source.
+
+use core::cell::RefCell;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::rc::Rc;
+use std::vec::Vec;
+
+use crate::assess::{EntropyAssessment, Provenance};
+use crate::buffered::Buffer;
+use crate::trace;
+use crate::EntropySource;
+
+/// Default per-thread buffer size.
+const DEFAULT_CAPACITY: usize = 4096;
+
+/// Source of unique pool identifiers (0 means unassigned).
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+std::thread_local! {
+    static BUFFERS: RefCell<Vec<(usize, Rc<RefCell<Buffer>>)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A shared source fronted by one buffer per thread, so the hot path takes no lock and makes no syscall.
+///
+/// NOTE: The shared source is only read to refill a thread's buffer. Buffers live until their thread exits, even if
+/// the pool is dropped earlier, so prefer `static` pools.
+pub struct LocalPool<S: EntropySource + Sync + 'static> {
+    source: &'static S,
+    id: AtomicUsize,
+    capacity: usize,
+}
+
+impl<S: EntropySource + Sync + 'static> LocalPool<S> {
+    /// Create a pool over a shared source with 4 KiB per-thread buffers.
+    pub const fn new(source: &'static S) -> Self {
+        Self {
+            source,
+            id: AtomicUsize::new(0),
+            capacity: DEFAULT_CAPACITY,
+        }
+    }
+
+    /// Set the per-thread buffer size (minimum 1).
+    pub const fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = if capacity == 0 { 1 } else { capacity };
+        self
+    }
+
+    /// Run `f` with this thread's buffered source.
+    pub fn with_local<R>(&self, f: impl FnOnce(&LocalSource<'_, S>) -> R) -> R {
+        let id = self.id();
+
+        let buffer = BUFFERS.with(|buffers| {
+            let mut buffers = buffers.borrow_mut();
+
+            match buffers.iter().find(|(other, _)| *other == id) {
+                Some((_, buffer)) => buffer.clone(),
+                None => {
+                    let buffer = Rc::new(RefCell::new(Buffer::new()));
+
+                    buffers.push((id, buffer.clone()));
+
+                    buffer
+                }
+            }
+        });
+
+        f(&LocalSource {
+            source: self.source,
+            buffer: &buffer,
+            capacity: self.capacity,
+        })
+    }
+
+    /// Get a reference to the shared source.
+    pub fn source(&self) -> &'static S {
+        self.source
+    }
+
+    fn id(&self) -> usize {
+        match self.id.load(Ordering::Relaxed) {
+            0 => {
+                let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+                match self.id.compare_exchange(0, id, Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => id,
+                    Err(existing) => existing,
+                }
+            }
+            id => id,
+        }
+    }
+}
+
+unsafe impl<S: EntropySource + Sync + 'static> EntropySource for LocalPool<S> {
+    type EntropySourceError = S::EntropySourceError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.with_local(|source| source.read_bytes(buffer))
+    }
+
+    fn read_bytes_vectored(&self, buffers: &mut [std::io::IoSliceMut<'_>]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffers.iter().map(|buffer| buffer.len()).sum());
+
+        self.with_local(|source| source.read_bytes_vectored(buffers))
+    }
+}
+
+/// NOTE: The per-thread buffers hold bytes read from the shared source, so the pool keeps its rate.
+impl<S: EntropyAssessment + Sync + 'static> EntropyAssessment for LocalPool<S> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.source.provenance()
+    }
+}
+
+/// A thread's buffered view of a `LocalPool`.
+///
+/// NOTE: Reading from the same pool inside the shared source's `read_bytes` panics.
+pub struct LocalSource<'a, S: EntropySource> {
+    source: &'a S,
+    buffer: &'a RefCell<Buffer>,
+    capacity: usize,
+}
+
+impl<S: EntropySource> LocalSource<'_, S> {
+    /// Discard (and wipe) this thread's buffered bytes.
+    pub fn clear(&self) {
+        self.buffer.borrow_mut().clear();
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySource for LocalSource<'_, S> {
+    type EntropySourceError = S::EntropySourceError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        self.buffer.borrow_mut().read(self.source, buffer, self.capacity)
+    }
+
+    fn read_bytes_vectored(&self, buffers: &mut [std::io::IoSliceMut<'_>]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffers.iter().map(|buffer| buffer.len()).sum());
+
+        let mut state = self.buffer.borrow_mut();
+
+        buffers
+            .iter_mut()
+            .try_for_each(|buffer| state.read(self.source, buffer, self.capacity))
+    }
+}
+
+impl<S: EntropyAssessment> EntropyAssessment for LocalSource<'_, S> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.source.provenance()
+    }
+}
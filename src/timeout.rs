@@ -0,0 +1,189 @@
+//! Bounding how long reads from blocking sources may take.
+
+use std::error::Error;
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+use std::vec;
+
+use zeroize::Zeroizing;
+
+use crate::assess::{EntropyAssessment, Provenance};
+use crate::error::{Classify, ErrorKind};
+use crate::trace;
+use crate::EntropySource;
+
+#[derive(Debug)]
+pub enum TimeoutError<E: Error> {
+    /// The wrapped source failed.
+    Source(E),
+    /// The read did not complete in time.
+    TimedOut,
+    /// The thread reading from the source panicked.
+    Panicked,
+}
+
+impl<E: Error> core::fmt::Display for TimeoutError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<E: Error> Error for TimeoutError<E> {}
+
+impl<E: Error + Classify> Classify for TimeoutError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Source(e) => e.kind(),
+            Self::TimedOut => ErrorKind::Timeout,
+            Self::Panicked => ErrorKind::Backend(0),
+        }
+    }
+}
+
+/// Fails reads from the wrapped source that take longer than a timeout, instead of blocking the caller.
+///
+/// NOTE: Every read runs on a new thread through an intermediate buffer, which is wiped afterwards. A read that
+/// timed out keeps running in the background and its output is discarded; the thread is never killed.
+pub struct TimedOut<S: EntropySource> {
+    source: Arc<S>,
+    timeout: Duration,
+}
+
+impl<S: EntropySource> TimedOut<S> {
+    /// Wrap a blocking source.
+    pub fn new(source: S, timeout: Duration) -> Self {
+        Self::from_arc(Arc::new(source), timeout)
+    }
+
+    /// Wrap a blocking source shared with other users.
+    pub fn from_arc(source: Arc<S>, timeout: Duration) -> Self {
+        Self { source, timeout }
+    }
+
+    /// Get the timeout.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &Arc<S> {
+        &self.source
+    }
+}
+
+unsafe impl<S> EntropySource for TimedOut<S>
+where
+    S: EntropySource + Send + Sync + 'static,
+    S::EntropySourceError: Send + 'static,
+{
+    type EntropySourceError = TimeoutError<S::EntropySourceError>;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        let source = self.source.clone();
+        let length = buffer.len();
+
+        let (sender, receiver) = mpsc::sync_channel(1);
+
+        std::thread::spawn(move || {
+            let mut bytes = Zeroizing::new(vec![0u8; length]);
+
+            // NOTE: The receiver is gone if the read timed out, in which case the bytes are simply wiped.
+            let _ = sender.send(source.read_bytes(&mut bytes).map(|_| bytes));
+        });
+
+        match receiver.recv_timeout(self.timeout) {
+            Ok(Ok(bytes)) => {
+                buffer.copy_from_slice(&bytes);
+
+                Ok(())
+            }
+            Ok(Err(e)) => Err(TimeoutError::Source(e)),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                trace::event!(warn, timeout = ?self.timeout, "read timed out");
+
+                Err(TimeoutError::TimedOut)
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(TimeoutError::Panicked),
+        }
+    }
+}
+
+impl<S> EntropyAssessment for TimedOut<S>
+where
+    S: EntropyAssessment + Send + Sync + 'static,
+    S::EntropySourceError: Send + 'static,
+{
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.source.provenance()
+    }
+}
+
+/// Fails reads from the wrapped async source that take longer than a timeout (with `tokio::time::timeout`).
+///
+/// NOTE: A read that timed out is cancelled by dropping it; `Blocking` sources keep running in the background.
+#[cfg(feature = "async")]
+pub struct AsyncTimedOut<S: crate::asynchronous::AsyncEntropySource> {
+    source: S,
+    timeout: Duration,
+}
+
+#[cfg(feature = "async")]
+impl<S: crate::asynchronous::AsyncEntropySource> AsyncTimedOut<S> {
+    /// Wrap an async source.
+    pub fn new(source: S, timeout: Duration) -> Self {
+        Self { source, timeout }
+    }
+
+    /// Get the timeout.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+}
+
+#[cfg(feature = "async")]
+unsafe impl<S> crate::asynchronous::AsyncEntropySource for AsyncTimedOut<S>
+where
+    S: crate::asynchronous::AsyncEntropySource + Sync,
+{
+    type EntropySourceError = TimeoutError<S::EntropySourceError>;
+
+    async fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        match tokio::time::timeout(self.timeout, self.source.read_bytes(buffer)).await {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(e)) => Err(TimeoutError::Source(e)),
+            Err(_) => {
+                // NOTE: The buffer may have been partially written before the read was cancelled.
+                buffer.fill(0);
+
+                trace::event!(warn, timeout = ?self.timeout, "async read timed out");
+
+                Err(TimeoutError::TimedOut)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<S> crate::assess::AsyncEntropyAssessment for AsyncTimedOut<S>
+where
+    S: crate::assess::AsyncEntropyAssessment + Sync,
+{
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.source.provenance()
+    }
+}
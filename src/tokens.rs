@@ -0,0 +1,180 @@
+//! Passwords and tokens over configurable alphabets.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::error::Error;
+
+use zeroize::Zeroize;
+
+use crate::EntropySource;
+
+/// Default token length, in characters.
+pub const DEFAULT_LENGTH: usize = 32;
+
+/// Size of the batches of random bytes drawn while selecting characters.
+const BATCH_SIZE: usize = 64;
+
+const ALPHANUMERIC: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+const URL_SAFE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const HEX: &str = "0123456789abcdef";
+
+#[derive(Debug)]
+pub enum TokenError<E: Error> {
+    /// The entropy source failed.
+    Source(E),
+    /// The custom charset has fewer than 2 characters, non-ASCII characters, or duplicates.
+    InvalidCharset,
+}
+
+impl<E: Error> core::fmt::Display for TokenError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<E: Error> Error for TokenError<E> {}
+
+/// The characters a token is drawn from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Charset {
+    /// `A-Z`, `a-z` and `0-9`.
+    Alphanumeric,
+    /// `A-Z`, `a-z`, `0-9`, `-` and `_` (the base64url alphabet).
+    UrlSafe,
+    /// `0-9` and `a-f`.
+    Hex,
+    /// Any set of distinct ASCII characters.
+    Custom(&'static str),
+}
+
+impl Charset {
+    /// Get the characters of the charset.
+    pub fn characters(&self) -> &'static str {
+        match self {
+            Self::Alphanumeric => ALPHANUMERIC,
+            Self::UrlSafe => URL_SAFE,
+            Self::Hex => HEX,
+            Self::Custom(characters) => characters,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        is_valid_charset(self.characters())
+    }
+}
+
+/// Whether `characters` are at least 2 distinct ASCII characters.
+pub(crate) fn is_valid_charset(characters: &str) -> bool {
+    let characters = characters.as_bytes();
+
+    characters.len() >= 2
+        && characters.is_ascii()
+        && characters.iter().enumerate().all(|(index, c)| !characters[..index].contains(c))
+}
+
+/// Generates random tokens, selecting every character uniformly (no `% charset.len()` bias).
+pub struct TokenGenerator {
+    charset: Charset,
+    length: usize,
+}
+
+impl TokenGenerator {
+    /// Generate tokens of the default length from the given charset.
+    pub fn new(charset: Charset) -> Self {
+        Self {
+            charset,
+            length: DEFAULT_LENGTH,
+        }
+    }
+
+    /// Generate tokens of exactly `length` characters.
+    pub fn with_length(mut self, length: usize) -> Self {
+        self.length = length;
+        self
+    }
+
+    /// Generate the shortest tokens carrying at least `bits` bits of entropy.
+    pub fn with_min_entropy(mut self, bits: u32) -> Self {
+        let per_character = log2(self.charset.characters().len());
+
+        self.length = match per_character {
+            0 => 0,
+            per_character => ((bits as u64) << 32).div_ceil(per_character) as usize,
+        };
+
+        self
+    }
+
+    /// Get the token length, in characters.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// Get the entropy of one token, in bits.
+    pub fn entropy_bits(&self) -> f64 {
+        self.length as f64 * log2(self.charset.characters().len()) as f64 / (1u64 << 32) as f64
+    }
+
+    /// Attempt to generate a token from the provided `EntropySource`.
+    pub fn generate<S: EntropySource>(&self, source: &S) -> Result<String, TokenError<S::EntropySourceError>> {
+        if !self.charset.is_valid() {
+            return Err(TokenError::InvalidCharset);
+        }
+
+        let characters = self.charset.characters().as_bytes();
+
+        // NOTE: Bytes at or above the largest multiple of the charset size are rejected.
+        let zone = 256 - 256 % characters.len();
+
+        let mut token = Vec::with_capacity(self.length);
+        let mut batch = [0u8; BATCH_SIZE];
+
+        while token.len() < self.length {
+            if let Err(e) = source.read_bytes(&mut batch) {
+                batch.zeroize();
+                token.zeroize();
+
+                return Err(TokenError::Source(e));
+            }
+
+            for byte in batch.iter().filter(|byte| (**byte as usize) < zone) {
+                if token.len() == self.length {
+                    break;
+                }
+
+                token.push(characters[*byte as usize % characters.len()]);
+            }
+        }
+
+        batch.zeroize();
+
+        // NOTE: Infallible, every character is ASCII.
+        String::from_utf8(token).map_err(|_| TokenError::InvalidCharset)
+    }
+}
+
+/// Compute `log2(n)` as a 32.32 fixed-point number (`0` for `n < 2`).
+///
+/// NOTE: `core` has no floating-point `log2`, so the fractional bits are found by repeated squaring.
+fn log2(n: usize) -> u64 {
+    if n < 2 {
+        return 0;
+    }
+
+    let integer = n.ilog2() as u64;
+
+    // NOTE: `n / 2^integer` in `[1, 2)`, as a 2.62 fixed-point number.
+    let mut mantissa = ((n as u128) << 62) >> integer;
+    let mut fraction = 0u64;
+
+    for bit in (0..32).rev() {
+        mantissa = (mantissa * mantissa) >> 62;
+
+        if mantissa >= 2 << 62 {
+            mantissa >>= 1;
+            fraction |= 1 << bit;
+        }
+    }
+
+    (integer << 32) | fraction
+}
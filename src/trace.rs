@@ -0,0 +1,41 @@
+//! Internal hooks for the optional `tracing` instrumentation.
+//!
+//! NOTE: Everything here compiles to nothing without the `tracing` feature. Only lengths, type names and errors are
+//! ever recorded, never the bytes themselves.
+
+// NOTE: Not every helper is used by every feature combination.
+#![allow(dead_code, unused_macros, unused_imports)]
+
+/// Guard for the span covering one `read_bytes` call.
+pub(crate) struct ReadSpan {
+    #[cfg(feature = "tracing")]
+    _span: tracing::span::EnteredSpan,
+}
+
+/// Enter a span covering one `read_bytes` call of `S`.
+#[inline(always)]
+#[allow(clippy::extra_unused_type_parameters)]
+pub(crate) fn read_span<S: ?Sized>(length: usize) -> ReadSpan {
+    #[cfg(feature = "tracing")]
+    return ReadSpan {
+        _span: tracing::trace_span!(target: "librypt_entropy", "read_bytes", source = core::any::type_name::<S>(), length)
+            .entered(),
+    };
+
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = length;
+
+        ReadSpan {}
+    }
+}
+
+/// Emit an event at the given level (`error`, `warn`, `info`, `debug` or `trace`), as with the `tracing` macros.
+macro_rules! event {
+    ($level:ident, $($arguments:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        tracing::$level!(target: "librypt_entropy", $($arguments)*);
+    }};
+}
+
+pub(crate) use event;
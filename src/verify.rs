@@ -0,0 +1,323 @@
+//! Cross-checking supposedly independent sources, to catch setups where they are secretly the same backend.
+
+use core::fmt::Write;
+use std::boxed::Box;
+use std::string::String;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::vec::Vec;
+
+use zeroize::Zeroizing;
+
+use crate::audit::Backend;
+use crate::erased::ErasedEntropySource;
+use crate::json::Number;
+use crate::lock::Lock;
+use crate::metrics::{Counters, MetricsSink};
+use crate::trace;
+use crate::EntropySource;
+
+/// Default number of bytes drawn from every source per check.
+pub const DEFAULT_SAMPLE_SIZE: usize = 4096;
+
+/// Smallest sample size accepted.
+const MIN_SAMPLE_SIZE: usize = 64;
+
+/// Length of the prefix searched for when looking for overlapping samples.
+const OVERLAP_LENGTH: usize = 16;
+
+/// Bit agreement between two samples (in standard deviations from chance) above which they are correlated.
+///
+/// NOTE: Independent samples exceed it with probability around `2^-29`.
+const Z_THRESHOLD: f64 = 6.0;
+
+/// A problem found by a `CrossCheck`; sources are identified by their index in `CrossCheckReport::sources`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Finding {
+    /// Reading from the source failed, so it was not compared.
+    ReadFailed { source: usize },
+    /// The source returned a constant sample, or the same sample as on the previous check.
+    Stuck { source: usize },
+    /// The two sources returned the same sample.
+    Identical { first: usize, second: usize },
+    /// The start of one sample appears within the other, as if both read from one stream.
+    Overlapping { first: usize, second: usize },
+    /// The bits of the two samples agree (or disagree) far more often than chance (`z_score` standard deviations).
+    Correlated { first: usize, second: usize, z_score: f64 },
+}
+
+/// The result of one `CrossCheck::check`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrossCheckReport {
+    /// The sources checked, in the order they were added.
+    pub sources: Vec<Backend>,
+    /// Number of bytes drawn from every source.
+    pub sample_size: usize,
+    pub findings: Vec<Finding>,
+}
+
+impl CrossCheckReport {
+    /// Whether nothing was found.
+    pub fn passed(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Serialize the report as a JSON object, with the sources described as in `audit::Report`.
+    ///
+    /// NOTE: Findings are recorded as objects with a `kind` string (e.g. `"Correlated"`) and the indices of the
+    /// sources involved.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+
+        // NOTE: Writing to a `String` cannot fail.
+        let _ = self.write_json(&mut json);
+
+        json
+    }
+
+    fn write_json(&self, json: &mut String) -> core::fmt::Result {
+        write!(json, "{{\"passed\":{},\"sample_size\":{},\"sources\":[", self.passed(), self.sample_size)?;
+
+        for (i, backend) in self.sources.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+
+            backend.write_json(json)?;
+        }
+
+        write!(json, "],\"findings\":[")?;
+
+        for (i, finding) in self.findings.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+
+            match *finding {
+                Finding::ReadFailed { source } => write!(json, "{{\"kind\":\"ReadFailed\",\"source\":{}}}", source)?,
+                Finding::Stuck { source } => write!(json, "{{\"kind\":\"Stuck\",\"source\":{}}}", source)?,
+                Finding::Identical { first, second } => {
+                    write!(json, "{{\"kind\":\"Identical\",\"first\":{},\"second\":{}}}", first, second)?
+                }
+                Finding::Overlapping { first, second } => {
+                    write!(json, "{{\"kind\":\"Overlapping\",\"first\":{},\"second\":{}}}", first, second)?
+                }
+                Finding::Correlated { first, second, z_score } => {
+                    write!(json, "{{\"kind\":\"Correlated\",\"first\":{},\"second\":{},", first, second)?;
+                    write!(json, "\"z_score\":{}}}", Number(z_score))?
+                }
+            }
+        }
+
+        write!(json, "]}}")
+    }
+}
+
+/// Whether the start of `needle` appears in `haystack` (other than at the very start).
+fn overlaps(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack[1..].windows(OVERLAP_LENGTH).any(|window| window == &needle[..OVERLAP_LENGTH])
+}
+
+/// How many standard deviations the bit agreement of two samples of equal length is from the expected half.
+fn z_score(first: &[u8], second: &[u8]) -> f64 {
+    let bits = first.len() as f64 * 8.0;
+
+    let disagreeing: u32 = first.iter().zip(second).map(|(a, b)| (a ^ b).count_ones()).sum();
+    let agreeing = bits - disagreeing as f64;
+
+    (agreeing - bits / 2.0) / (bits / 4.0).sqrt()
+}
+
+/// Draws samples from two or more supposedly independent sources and compares them with cheap distinguishers, to
+/// catch a misconfiguration where they are actually the same backend (e.g. two "independent" DRBGs seeded from one
+/// replayed file, or two handles to one stuck device).
+///
+/// Every check reads `sample_size` bytes from each source, then reports a source that is stuck (a constant sample, or a
+/// repeat of its previous one), and any pair whose samples are identical, overlap, or have correlated bits. Every check
+/// is reported to the `MetricsSink` and to `tracing`, and returned as a report which serializes like an
+/// `audit::Report`.
+///
+/// NOTE: This only catches sources whose outputs are visibly related. Two sources conditioned by a CSPRNG on top of one
+/// shared backend (e.g. two `OsEntropy` handles) look independent to any statistical test, so this is not a substitute
+/// for knowing where each source comes from. Samples are wiped after use, but the previous sample of every source is
+/// kept (wiped) until the next check.
+pub struct CrossCheck<M: MetricsSink = Counters> {
+    sources: Vec<(Backend, Box<dyn ErasedEntropySource + Send + Sync>)>,
+    previous: Lock<Vec<Option<Zeroizing<Vec<u8>>>>>,
+    sample_size: usize,
+    sink: M,
+}
+
+impl CrossCheck {
+    /// Create a cross-check without sources, counting its findings.
+    pub fn new() -> Self {
+        Self::with_sink(Counters::new())
+    }
+}
+
+impl Default for CrossCheck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M: MetricsSink> CrossCheck<M> {
+    /// Create a cross-check without sources, reporting to a custom sink.
+    pub fn with_sink(sink: M) -> Self {
+        Self {
+            sources: Vec::new(),
+            previous: Lock::new(Vec::new()),
+            sample_size: DEFAULT_SAMPLE_SIZE,
+            sink,
+        }
+    }
+
+    /// Add a source to the sources checked against each other.
+    pub fn with_source<S>(mut self, source: S) -> Self
+    where
+        S: EntropySource + Send + Sync + 'static,
+        S::EntropySourceError: Send + Sync + 'static,
+    {
+        self.sources.push((Backend::of::<S>(), source.erase()));
+        self.previous.get_mut().push(None);
+        self
+    }
+
+    /// Set the number of bytes drawn from every source per check (4 KiB by default, minimum 64).
+    pub fn with_sample_size(mut self, sample_size: usize) -> Self {
+        self.sample_size = sample_size.max(MIN_SAMPLE_SIZE);
+        self
+    }
+
+    /// Get a reference to the sink.
+    pub fn sink(&self) -> &M {
+        &self.sink
+    }
+
+    /// Draw a sample from every source and compare them.
+    pub fn check(&self) -> CrossCheckReport {
+        let mut findings = Vec::new();
+
+        let samples: Vec<Option<Zeroizing<Vec<u8>>>> = self
+            .sources
+            .iter()
+            .enumerate()
+            .map(|(source, (_, erased))| {
+                let mut sample = Zeroizing::new(std::vec![0u8; self.sample_size]);
+
+                match erased.read_bytes_erased(&mut sample) {
+                    Ok(()) => Some(sample),
+                    Err(_) => {
+                        findings.push(Finding::ReadFailed { source });
+
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        // NOTE: Stuck sources are left out of the pairwise comparisons, which they would fail trivially.
+        let mut live = std::vec![false; samples.len()];
+
+        self.previous.with(|previous| {
+            for (source, sample) in samples.iter().enumerate() {
+                let sample = match sample {
+                    Some(sample) => sample,
+                    None => continue,
+                };
+
+                let constant = sample.iter().all(|byte| *byte == sample[0]);
+                let repeated = previous[source].as_ref().is_some_and(|previous| previous[..] == sample[..]);
+
+                match constant || repeated {
+                    true => findings.push(Finding::Stuck { source }),
+                    false => live[source] = true,
+                }
+            }
+
+            // NOTE: A failed read keeps the previous sample, so a source which also fails now and then is still caught
+            // repeating itself.
+            for (previous, sample) in previous.iter_mut().zip(&samples) {
+                if sample.is_some() {
+                    previous.clone_from(sample);
+                }
+            }
+        });
+
+        for first in 0..samples.len() {
+            for second in first + 1..samples.len() {
+                let (a, b) = match (&samples[first], &samples[second]) {
+                    (Some(a), Some(b)) if live[first] && live[second] => (a, b),
+                    _ => continue,
+                };
+
+                if a[..] == b[..] {
+                    findings.push(Finding::Identical { first, second });
+                } else if overlaps(a, b) || overlaps(b, a) {
+                    findings.push(Finding::Overlapping { first, second });
+                } else {
+                    let z_score = z_score(a, b);
+
+                    if z_score.abs() > Z_THRESHOLD {
+                        findings.push(Finding::Correlated { first, second, z_score });
+                    }
+                }
+            }
+        }
+
+        for _finding in &findings {
+            trace::event!(warn, finding = ?_finding, "cross-check finding");
+        }
+
+        self.sink.on_cross_check(findings.len());
+
+        CrossCheckReport {
+            sources: self.sources.iter().map(|(backend, _)| *backend).collect(),
+            sample_size: self.sample_size,
+            findings,
+        }
+    }
+
+    /// Run a check every `interval` on a background thread until stopped, passing each report to `on_report`.
+    pub fn spawn(self, interval: Duration, on_report: impl Fn(CrossCheckReport) + Send + 'static) -> CrossCheckHandle
+    where
+        M: Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopping = stop.clone();
+
+        let thread = std::thread::spawn(move || {
+            while !stopping.load(Ordering::Relaxed) {
+                on_report(self.check());
+
+                std::thread::park_timeout(interval);
+            }
+        });
+
+        CrossCheckHandle { stop, thread }
+    }
+}
+
+/// A running periodic `CrossCheck`, stopped by `stop`.
+pub struct CrossCheckHandle {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+impl CrossCheckHandle {
+    /// Whether the checks have stopped (which only happens if asked to, or if `on_report` panicked).
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// Stop the checks and wait for the one in progress, if any.
+    ///
+    /// NOTE: This function will panic if `on_report` panicked.
+    pub fn stop(self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.thread.thread().unpark();
+        self.thread.join().unwrap()
+    }
+}
@@ -0,0 +1,235 @@
+//! Detecting sources that stall or starve, so services can shed load or alert before key generation hangs.
+
+use std::boxed::Box;
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::assess::{EntropyAssessment, Provenance};
+use crate::lock::Lock;
+use crate::trace;
+use crate::EntropySource;
+
+/// Default time after which a read is considered stalled.
+const DEFAULT_STALL_THRESHOLD: Duration = Duration::from_secs(1);
+
+/// Default number of recent reads the failure rate is measured over.
+const DEFAULT_FAILURE_WINDOW: usize = 32;
+
+/// Default number of failures within the window at which the source is considered starving.
+const DEFAULT_MAX_FAILURES: usize = 8;
+
+/// Why a `Watchdog` considers its source degraded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symptom {
+    /// A read has been in progress for at least the stall threshold (contains how long so far).
+    Stalled(Duration),
+    /// The last read completed, but took at least the stall threshold (contains how long).
+    Slow(Duration),
+    /// Too many of the recent reads failed.
+    Failing {
+        /// Failed reads within the window.
+        failures: usize,
+        /// Reads within the window.
+        reads: usize,
+    },
+}
+
+struct WatchdogState {
+    in_flight: BTreeMap<u64, Instant>,
+    next_read: u64,
+    outcomes: VecDeque<bool>,
+    failures: usize,
+    last_latency: Duration,
+    symptom: Option<Symptom>,
+}
+
+/// Tracks the latency and failures of the reads from the wrapped source, reporting when it appears stalled or
+/// starving.
+///
+/// The source is degraded while a read has been in progress for longer than the stall threshold, after a read that
+/// took that long, or while too many of the recent reads failed; it recovers by itself once none of these hold. The
+/// callback runs each time the source becomes degraded, and `is_degraded` can be polled instead (e.g. from a readiness
+/// probe).
+///
+/// NOTE: A read that never returns cannot report itself, so a stall is only noticed by `check` or `is_degraded`, or by
+/// a read started on another thread. The callback runs on whichever thread noticed, so keep it cheap.
+pub struct Watchdog<S: EntropySource> {
+    source: S,
+    state: Lock<WatchdogState>,
+    stall_threshold: Duration,
+    failure_window: usize,
+    max_failures: usize,
+    callback: Option<Box<dyn Fn(Symptom) + Send + Sync>>,
+}
+
+impl<S: EntropySource> Watchdog<S> {
+    /// Watch a source, considering reads of 1 second stalled and 8 failures in the last 32 reads starving.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            state: Lock::new(WatchdogState {
+                in_flight: BTreeMap::new(),
+                next_read: 0,
+                outcomes: VecDeque::with_capacity(DEFAULT_FAILURE_WINDOW),
+                failures: 0,
+                last_latency: Duration::ZERO,
+                symptom: None,
+            }),
+            stall_threshold: DEFAULT_STALL_THRESHOLD,
+            failure_window: DEFAULT_FAILURE_WINDOW,
+            max_failures: DEFAULT_MAX_FAILURES,
+            callback: None,
+        }
+    }
+
+    /// Set the time after which a read is considered stalled.
+    pub fn with_stall_threshold(mut self, threshold: Duration) -> Self {
+        self.stall_threshold = threshold;
+        self
+    }
+
+    /// Consider the source starving when at least `failures` of the last `window` reads failed (both minimum 1).
+    pub fn with_failure_threshold(mut self, failures: usize, window: usize) -> Self {
+        self.failure_window = window.max(1);
+        self.max_failures = failures.clamp(1, self.failure_window);
+        self.state.with(|state| {
+            state.outcomes.clear();
+            state.failures = 0;
+        });
+        self
+    }
+
+    /// Set a callback to run each time the source becomes degraded.
+    pub fn with_callback(mut self, callback: impl Fn(Symptom) + Send + Sync + 'static) -> Self {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Get the time after which a read is considered stalled.
+    pub fn stall_threshold(&self) -> Duration {
+        self.stall_threshold
+    }
+
+    /// Get a reference to the wrapped source.
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Check the source now (noticing reads in progress which have stalled), returning why it is degraded, if it is.
+    pub fn check(&self) -> Option<Symptom> {
+        self.update(|_| {})
+    }
+
+    /// Whether the source is currently degraded (see `check`).
+    pub fn is_degraded(&self) -> bool {
+        self.check().is_some()
+    }
+
+    fn evaluate(&self, state: &WatchdogState, now: Instant) -> Option<Symptom> {
+        if let Some(started) = state.in_flight.values().min() {
+            let elapsed = now.saturating_duration_since(*started);
+
+            if elapsed >= self.stall_threshold {
+                return Some(Symptom::Stalled(elapsed));
+            }
+        }
+
+        if state.last_latency >= self.stall_threshold {
+            return Some(Symptom::Slow(state.last_latency));
+        }
+
+        if state.failures >= self.max_failures {
+            return Some(Symptom::Failing {
+                failures: state.failures,
+                reads: state.outcomes.len(),
+            });
+        }
+
+        None
+    }
+
+    /// Apply `f` to the state and re-evaluate it, running the callback if the source just became degraded.
+    fn update(&self, f: impl FnOnce(&mut WatchdogState)) -> Option<Symptom> {
+        let (symptom, previous) = self.state.with(|state| {
+            f(state);
+
+            let symptom = self.evaluate(state, Instant::now());
+
+            (symptom, core::mem::replace(&mut state.symptom, symptom))
+        });
+
+        // NOTE: The callback runs outside the lock, so it may query the watchdog.
+        if let (Some(symptom), None) = (symptom, previous) {
+            trace::event!(warn, ?symptom, "source degraded");
+
+            if let Some(callback) = &self.callback {
+                callback(symptom);
+            }
+        }
+
+        symptom
+    }
+
+    fn record(&self, latency: Duration, failed: bool) {
+        self.update(|state| {
+            if state.outcomes.len() >= self.failure_window {
+                if let Some(true) = state.outcomes.pop_front() {
+                    state.failures -= 1;
+                }
+            }
+
+            state.outcomes.push_back(failed);
+            state.failures += failed as usize;
+            state.last_latency = latency;
+        });
+    }
+}
+
+/// Removes a read from the in-flight set when it returns or unwinds.
+struct InFlight<'a, S: EntropySource> {
+    watchdog: &'a Watchdog<S>,
+    read: u64,
+}
+
+impl<S: EntropySource> Drop for InFlight<'_, S> {
+    fn drop(&mut self) {
+        self.watchdog.state.with(|state| state.in_flight.remove(&self.read));
+    }
+}
+
+unsafe impl<S: EntropySource> EntropySource for Watchdog<S> {
+    type EntropySourceError = S::EntropySourceError;
+
+    fn read_bytes(&self, buffer: &mut [u8]) -> Result<(), Self::EntropySourceError> {
+        let _span = trace::read_span::<Self>(buffer.len());
+
+        let mut read = 0;
+
+        self.update(|state| {
+            read = state.next_read;
+            state.next_read += 1;
+            state.in_flight.insert(read, Instant::now());
+        });
+
+        let in_flight = InFlight { watchdog: self, read };
+        let started = Instant::now();
+
+        let result = self.source.read_bytes(buffer);
+
+        drop(in_flight);
+
+        self.record(started.elapsed(), result.is_err());
+
+        result
+    }
+}
+
+impl<S: EntropyAssessment> EntropyAssessment for Watchdog<S> {
+    fn min_entropy_per_byte(&self) -> f64 {
+        self.source.min_entropy_per_byte()
+    }
+
+    fn provenance(&self) -> Provenance {
+        self.source.provenance()
+    }
+}